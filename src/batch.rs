@@ -0,0 +1,72 @@
+use crate::navmesh::NavAreaId;
+use crate::{NavCost, NavTree};
+use rayon::prelude::*;
+
+impl NavTree {
+    /// Find the cheapest paths between many pairs of areas in parallel
+    ///
+    /// The searches are spread over the rayon thread pool, every search works like
+    /// [`find_path_with_cost`]. The paths are returned in the same order as the pairs, with
+    /// `None` for pairs where the goal can't be reached from the start.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, DistanceCost, NavAreaId};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let spawns: Vec<NavAreaId> = vec![1.into(), 2.into()];
+    /// let objectives: Vec<NavAreaId> = vec![250.into(), 300.into(), 420.into()];
+    /// let pairs: Vec<_> = spawns
+    ///     .iter()
+    ///     .flat_map(|spawn| objectives.iter().map(move |objective| (*spawn, *objective)))
+    ///     .collect();
+    /// for ((spawn, objective), path) in pairs.iter().zip(tree.find_paths_batch(&pairs, &DistanceCost)) {
+    ///     if let Some(path) = path {
+    ///         println!("{} -> {}: {} areas", spawn, objective, path.len());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`find_path_with_cost`]: #method.find_path_with_cost
+    pub fn find_paths_batch(
+        &self,
+        pairs: &[(NavAreaId, NavAreaId)],
+        cost: &(impl NavCost + Sync),
+    ) -> Vec<Option<Vec<NavAreaId>>> {
+        pairs
+            .par_iter()
+            .map(|(start, goal)| self.find_path_with_cost(*start, *goal, cost))
+            .collect()
+    }
+}
+
+#[test]
+fn test_find_paths_batch() {
+    use crate::DistanceCost;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let mut pairs = vec![(start, goal), (goal, start), (start, start)];
+    pairs.extend(tree.areas().step_by(97).map(|area| (start, area.id)));
+    pairs.push((start, NavAreaId::from(u32::MAX)));
+
+    let paths = tree.find_paths_batch(&pairs, &DistanceCost);
+    assert_eq!(pairs.len(), paths.len());
+    for ((start, goal), path) in pairs.iter().zip(&paths) {
+        assert_eq!(
+            &tree.find_path_with_cost(*start, *goal, &DistanceCost),
+            path
+        );
+    }
+    assert_eq!(&None, paths.last().unwrap());
+
+    assert!(tree.find_paths_batch(&[], &DistanceCost).is_empty());
+}
@@ -0,0 +1,132 @@
+use crate::navmesh::NavAreaId;
+use crate::NavTree;
+
+impl NavTree {
+    /// Block or unblock an area, like when a gate closes or a train moves into the way
+    ///
+    /// Blocking only affects movement over the mesh. Blocked areas can't be entered or left, they
+    /// are skipped by path searches, reachability, raycasts and when looking up the area of a point
+    /// to start or end a path at. The geometry of the mesh doesn't change, so a blocked area is
+    /// still returned by the spatial queries like [`query`] and used for the height and slope at a
+    /// point. Use [`nearest_area_where`] with [`is_blocked`] to find the nearest open area.
+    ///
+    /// Blocking is runtime state that isn't stored in nav files, so it's kept in the tree used for
+    /// the searches instead of in the [`NavMesh`].
+    ///
+    /// Returns `false` if the area was already blocked or unblocked. Blocking an area that isn't
+    /// in the tree has no effect until an area with that id is added.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mut tree = get_area_tree(file)?;
+    /// // the gate in front of the blue spawn closes
+    /// tree.set_blocked(120.into(), true);
+    /// let detour = tree.find_path(1.into(), 250.into());
+    /// tree.set_blocked(120.into(), false);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`query`]: #method.query
+    /// [`nearest_area_where`]: #method.nearest_area_where
+    /// [`is_blocked`]: #method.is_blocked
+    /// [`NavMesh`]: ./struct.NavMesh.html
+    pub fn set_blocked(&mut self, id: NavAreaId, blocked: bool) -> bool {
+        if blocked {
+            self.blocked.insert(id)
+        } else {
+            self.blocked.remove(&id)
+        }
+    }
+
+    /// Block or unblock a number of areas, see [`set_blocked`]
+    ///
+    /// [`set_blocked`]: #method.set_blocked
+    pub fn set_blocked_all(&mut self, ids: impl IntoIterator<Item = NavAreaId>, blocked: bool) {
+        for id in ids {
+            self.set_blocked(id, blocked);
+        }
+    }
+
+    /// Unblock all areas
+    pub fn clear_blocked(&mut self) {
+        self.blocked.clear();
+    }
+
+    /// Whether an area is blocked
+    pub fn is_blocked(&self, id: NavAreaId) -> bool {
+        self.blocked.contains(&id)
+    }
+
+    /// Get the ids of all blocked areas
+    pub fn blocked_areas(&self) -> impl Iterator<Item = NavAreaId> + '_ {
+        self.blocked.iter().copied()
+    }
+}
+
+#[test]
+fn test_set_blocked() {
+    use crate::DistanceCost;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let path = tree.find_path(start, goal).unwrap();
+    let reachable = tree.reachable_from(start, &DistanceCost).len();
+
+    let middle = path[path.len() / 2];
+    assert!(tree.set_blocked(middle, true));
+    assert!(!tree.set_blocked(middle, true));
+    assert!(tree.is_blocked(middle));
+    assert_eq!(vec![middle], tree.blocked_areas().collect::<Vec<_>>());
+
+    // searches go around the blocked area
+    assert!(tree
+        .traversable_neighbors(&tree[path[path.len() / 2 - 1]])
+        .all(|(next, _)| next.id != middle));
+    assert_eq!(0, tree.traversable_neighbors(&tree[middle]).count());
+    if let Some(detour) = tree.find_path(start, goal) {
+        assert!(!detour.contains(&middle));
+    }
+    assert!(!tree.reachable_from(start, &DistanceCost).contains(&middle));
+    assert_eq!(None, tree.find_path(middle, goal));
+    let center = tree[middle].center();
+    assert!(
+        tree.open_area_under(center.0, center.1, center.2)
+            .map(|area| area.id)
+            != Some(middle)
+    );
+    assert!(tree.query(center.0, center.1).any(|area| area.id == middle));
+    assert_eq!(
+        Some(middle),
+        tree.area_under(center.0, center.1, center.2)
+            .map(|area| area.id)
+    );
+    assert_eq!(
+        tree[middle].normal_at(center.0, center.1),
+        tree.normal_at(center.0, center.1, center.2).unwrap()
+    );
+
+    // lines over the blocked area leave the mesh
+    let before = tree[path[path.len() / 2 - 1]].center();
+    assert!(tree
+        .raycast_2d((before.0, before.1), (center.0, center.1))
+        .is_some());
+
+    tree.set_blocked_all(path[1..3].iter().copied(), true);
+    assert_eq!(3, tree.blocked_areas().count());
+    if let Some(detour) = tree.find_path(start, goal) {
+        assert!(detour.iter().all(|id| !tree.is_blocked(*id)));
+    }
+
+    tree.clear_blocked();
+    assert_eq!(Some(path), tree.find_path(start, goal));
+    assert_eq!(reachable, tree.reachable_from(start, &DistanceCost).len());
+}
@@ -0,0 +1,231 @@
+use crate::navmesh::{NavArea, NavDirection, Vector3};
+use crate::NavTree;
+
+/// Edge segments shorter than this are ignored
+const MIN_SEGMENT_LENGTH: f32 = 1.0;
+
+/// A segment of the edge of an area without a connection to another area
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundaryEdge {
+    /// The side of the area the segment is on
+    pub direction: NavDirection,
+    pub from: Vector3,
+    pub to: Vector3,
+}
+
+/// An area at the edge of the mesh, see [`NavTree::boundary_areas`]
+///
+/// [`NavTree::boundary_areas`]: ./struct.NavTree.html#method.boundary_areas
+#[derive(Debug, Clone)]
+pub struct BoundaryArea<'a> {
+    pub area: &'a NavArea,
+    /// The segments of the edges of the area that aren't connected to another area
+    pub edges: Vec<BoundaryEdge>,
+}
+
+/// Remove the covered ranges from a range
+fn uncovered(range: (f32, f32), mut covered: Vec<(f32, f32)>) -> Vec<(f32, f32)> {
+    covered.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+    let mut segments = Vec::new();
+    let mut start = range.0;
+    for (min, max) in covered {
+        if min > start {
+            segments.push((start, min.min(range.1)));
+        }
+        start = start.max(max);
+    }
+    segments.push((start, range.1));
+    segments.retain(|(min, max)| max - min >= MIN_SEGMENT_LENGTH);
+    segments
+}
+
+impl NavTree {
+    /// Find all areas with a part of their edge not connected to any other area
+    ///
+    /// For every area, the parts of the sides that aren't covered by an area connected on that
+    /// side are returned. Connections to areas that don't exist in the tree are ignored.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for boundary in tree.boundary_areas() {
+    ///     for edge in &boundary.edges {
+    ///         println!("area {} ends between {:?} and {:?}", boundary.area.id, edge.from, edge.to);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn boundary_areas(&self) -> Vec<BoundaryArea<'_>> {
+        self.areas()
+            .filter_map(|area| {
+                let edges = self.boundary_edges(area);
+                if edges.is_empty() {
+                    None
+                } else {
+                    Some(BoundaryArea { area, edges })
+                }
+            })
+            .collect()
+    }
+
+    /// Get the horizontal distance from a x/y coordinate to the closest edge of the mesh
+    ///
+    /// The edges of the mesh are the parts of the sides of areas that aren't connected to another
+    /// area, like for [`boundary_areas`]. Only the x/y coordinates are taken into account, so
+    /// edges of areas on other floors are included. Returns `None` if the mesh has no edges.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// if tree.distance_to_boundary(150.0, -312.0) < Some(32.0) {
+    ///     println!("careful, close to the edge");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`boundary_areas`]: #method.boundary_areas
+    pub fn distance_to_boundary(&self, x: f32, y: f32) -> Option<f32> {
+        let max_distance = self.max_distance(x, y);
+        let mut radius = 64.0f32.min(max_distance);
+        loop {
+            // any edge within the radius belongs to an area within the radius
+            let nearest = self
+                .query_radius(x, y, radius)
+                .flat_map(|area| self.boundary_edges(area))
+                .map(|edge| edge_distance(&edge, x, y))
+                .fold(None, |nearest: Option<f32>, distance| {
+                    Some(nearest.map_or(distance, |nearest| nearest.min(distance)))
+                });
+            match nearest {
+                Some(distance) if distance <= radius => return Some(distance),
+                _ if radius >= max_distance => return nearest,
+                _ => radius = (radius * 2.0).min(max_distance),
+            }
+        }
+    }
+
+    /// Get the parts of the sides of an area that aren't connected to another area
+    fn boundary_edges(&self, area: &NavArea) -> Vec<BoundaryEdge> {
+        let quad = &area.quad;
+        NavDirection::ALL
+            .iter()
+            .flat_map(|direction| {
+                let direction = *direction;
+                let (fixed, range) = quad.side(direction);
+                let covered = area.connections[direction]
+                    .iter()
+                    .filter_map(|id| self.area(*id))
+                    .map(|neighbor| neighbor.quad.side(direction.opposite()).1)
+                    .collect();
+                uncovered(range, covered)
+                    .into_iter()
+                    .map(move |(min, max)| {
+                        let point = |along: f32| match direction {
+                            NavDirection::North | NavDirection::South => {
+                                Vector3(along, fixed, quad.interpolated_z(along, fixed))
+                            }
+                            NavDirection::East | NavDirection::West => {
+                                Vector3(fixed, along, quad.interpolated_z(fixed, along))
+                            }
+                        };
+                        BoundaryEdge {
+                            direction,
+                            from: point(min),
+                            to: point(max),
+                        }
+                    })
+            })
+            .collect()
+    }
+}
+
+/// Get the horizontal distance from a point to an edge
+fn edge_distance(edge: &BoundaryEdge, x: f32, y: f32) -> f32 {
+    // edges are axis aligned
+    let dx = (edge.from.0.min(edge.to.0) - x)
+        .max(x - edge.from.0.max(edge.to.0))
+        .max(0.0);
+    let dy = (edge.from.1.min(edge.to.1) - y)
+        .max(y - edge.from.1.max(edge.to.1))
+        .max(0.0);
+    (dx * dx + dy * dy).sqrt()
+}
+
+#[test]
+fn test_uncovered() {
+    assert_eq!(vec![(0.0, 10.0)], uncovered((0.0, 10.0), vec![]));
+    assert_eq!(
+        Vec::<(f32, f32)>::new(),
+        uncovered((0.0, 10.0), vec![(-5.0, 4.0), (4.0, 20.0)])
+    );
+    assert_eq!(
+        vec![(0.0, 2.0), (4.0, 6.0), (8.0, 10.0)],
+        uncovered((0.0, 10.0), vec![(6.0, 8.0), (2.0, 4.0)])
+    );
+}
+
+#[test]
+fn test_boundary_areas() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+    let boundary = tree.boundary_areas();
+    assert!(!boundary.is_empty());
+    assert!(boundary.len() < tree.len());
+
+    for area in tree.areas() {
+        let open_side = NavDirection::ALL
+            .iter()
+            .any(|direction| area.connections[*direction].is_empty());
+        if open_side {
+            assert!(boundary.iter().any(|boundary| boundary.area.id == area.id));
+        }
+    }
+
+    for boundary in &boundary {
+        for edge in &boundary.edges {
+            let quad = &boundary.area.quad;
+            let length =
+                ((edge.to.0 - edge.from.0).powi(2) + (edge.to.1 - edge.from.1).powi(2)).sqrt();
+            assert!(length >= MIN_SEGMENT_LENGTH);
+            assert!(quad.distance_2d(edge.from.0, edge.from.1) < 0.01);
+            assert!(quad.distance_2d(edge.to.0, edge.to.1) < 0.01);
+        }
+    }
+}
+
+#[test]
+fn test_distance_to_boundary() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+    let edges: Vec<BoundaryEdge> = tree
+        .boundary_areas()
+        .into_iter()
+        .flat_map(|boundary| boundary.edges)
+        .collect();
+
+    for (x, y) in &[(320.0, -1030.0), (0.0, 0.0), (-100_000.0, 0.0)] {
+        let expected = edges
+            .iter()
+            .map(|edge| edge_distance(edge, *x, *y))
+            .fold(f32::INFINITY, f32::min);
+        assert_eq!(Some(expected), tree.distance_to_boundary(*x, *y));
+    }
+
+    let edge = edges[0];
+    assert_eq!(
+        Some(0.0),
+        tree.distance_to_boundary(edge.from.0, edge.from.1)
+    );
+}
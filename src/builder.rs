@@ -0,0 +1,906 @@
+use crate::navmesh::{
+    Connections, LadderConnections, LightIntensity, NavArea, NavAreaId, NavDirection, NavPlace,
+    NavQuad, Vector3, VisibleArea,
+};
+use crate::{NavGame, NavMesh};
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// Errors that can occur when editing a navigation mesh
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum EditError {
+    /// The edited area doesn't exist
+    #[error("Area {0} doesn't exist")]
+    UnknownArea(NavAreaId),
+    /// An area with the id already exists
+    #[error("Area {0} already exists")]
+    DuplicateArea(NavAreaId),
+    /// The edit would leave an area with no or a negative size
+    #[error("The edit would leave area {0} without size")]
+    InvalidQuad(NavAreaId),
+    /// The place table is full
+    #[error("Too many places")]
+    TooManyPlaces,
+    /// There is no unused area id above the existing ids left
+    #[error("Too many areas")]
+    TooManyAreas,
+    /// The split coordinate is not inside the area
+    #[error("Area {0} can't be split outside of it's bounds")]
+    InvalidSplit(NavAreaId),
+    /// The areas don't share a full edge and can't be merged into a single area
+    #[error("Areas {0} and {1} can't be merged")]
+    NotMergeable(NavAreaId, NavAreaId),
+}
+
+/// One of the corners of an area
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum NavCorner {
+    NorthWest,
+    NorthEast,
+    SouthEast,
+    SouthWest,
+}
+
+/// An axis of the horizontal plane
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum NavAxis {
+    X,
+    Y,
+}
+
+impl NavAxis {
+    /// The range a quad covers along the axis
+    fn range(self, quad: &NavQuad) -> (f32, f32) {
+        match self {
+            NavAxis::X => (quad.north_west.0, quad.south_east.0),
+            NavAxis::Y => (quad.north_west.1, quad.south_east.1),
+        }
+    }
+
+    /// The direction pointing towards higher coordinates along the axis
+    fn direction(self) -> NavDirection {
+        match self {
+            NavAxis::X => NavDirection::East,
+            NavAxis::Y => NavDirection::South,
+        }
+    }
+}
+
+fn ranges_overlap(a: (f32, f32), b: (f32, f32)) -> bool {
+    a.1.min(b.1) - a.0.max(b.0) > 0.0
+}
+
+/// Build a new navigation mesh or edit an existing one
+///
+/// Connections are kept consistent while editing: connecting two areas adds the connection in
+/// both directions and removing an area removes all references to it.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{read_mesh, NavDirection, NavMeshBuilder, NavQuad, Vector3};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let mut builder = NavMeshBuilder::from_mesh(read_mesh(file)?);
+///
+/// let first = builder.mesh().areas[0].id;
+/// let new = builder.add_area(NavQuad {
+///     north_west: Vector3(0.0, 0.0, 0.0),
+///     south_east: Vector3(50.0, 50.0, 0.0),
+///     north_east_z: 0.0,
+///     south_west_z: 0.0,
+/// })?;
+/// builder.connect(first, new, NavDirection::South)?;
+/// builder.set_place(new, Some("Bridge"))?;
+///
+/// let mesh = builder.build();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NavMeshBuilder {
+    mesh: NavMesh,
+    index: HashMap<NavAreaId, usize>,
+}
+
+impl Default for NavMeshBuilder {
+    fn default() -> Self {
+        NavMeshBuilder::new(NavGame::Unknown)
+    }
+}
+
+impl NavMeshBuilder {
+    /// Start building an empty navigation mesh for a game
+    pub fn new(game: NavGame) -> Self {
+        NavMeshBuilder::from_mesh(NavMesh {
+            game,
+            major_version: 16,
+            minor_version: 0,
+            bsp_size: 0,
+            is_analyzed: false,
+            places: Vec::new(),
+            has_unnamed_areas: false,
+            areas: Vec::new(),
+            ladders: Vec::new(),
+            custom_data: Vec::new(),
+        })
+    }
+
+    /// Start editing an existing navigation mesh
+    pub fn from_mesh(mesh: NavMesh) -> Self {
+        let mut builder = NavMeshBuilder {
+            mesh,
+            index: HashMap::new(),
+        };
+        builder.reindex();
+        builder
+    }
+
+    fn reindex(&mut self) {
+        self.index = self
+            .mesh
+            .areas
+            .iter()
+            .enumerate()
+            .map(|(index, area)| (area.id, index))
+            .collect();
+    }
+
+    /// Finish editing and get the navigation mesh
+    pub fn build(self) -> NavMesh {
+        self.mesh
+    }
+
+    /// The mesh as edited so far
+    pub fn mesh(&self) -> &NavMesh {
+        &self.mesh
+    }
+
+    /// Get an area by it's id
+    pub fn area(&self, id: NavAreaId) -> Option<&NavArea> {
+        self.index.get(&id).map(|index| &self.mesh.areas[*index])
+    }
+
+    fn area_mut(&mut self, id: NavAreaId) -> Result<&mut NavArea, EditError> {
+        match self.index.get(&id) {
+            Some(index) => Ok(&mut self.mesh.areas[*index]),
+            None => Err(EditError::UnknownArea(id)),
+        }
+    }
+
+    /// Add a new area, using the lowest unused id above all existing area ids
+    pub fn add_area(&mut self, quad: NavQuad) -> Result<NavAreaId, EditError> {
+        let id = self.next_id()?;
+        self.add_area_with_id(id, quad)?;
+        Ok(id)
+    }
+
+    /// Add a new area with a specific id
+    pub fn add_area_with_id(&mut self, id: NavAreaId, quad: NavQuad) -> Result<(), EditError> {
+        if self.index.contains_key(&id) {
+            return Err(EditError::DuplicateArea(id));
+        }
+        if quad.width() <= 0.0 || quad.height() <= 0.0 {
+            return Err(EditError::InvalidQuad(id));
+        }
+
+        self.index.insert(id, self.mesh.areas.len());
+        self.mesh.areas.push(NavArea {
+            id,
+            quad,
+            flags: 0,
+            connections: Connections::default(),
+            hiding_spots: Vec::new(),
+            approach_areas: Vec::new(),
+            encounter_paths: Vec::new(),
+            place: 0,
+            light_intensity: LightIntensity {
+                north_west: 1.0,
+                north_east: 1.0,
+                south_west: 1.0,
+                south_east: 1.0,
+            },
+            ladder_connections: LadderConnections::default(),
+            earliest_occupy_first_team: 0.0,
+            earliest_occupy_second_team: 0.0,
+            visible_areas: Vec::new(),
+            inherit_visibility_from_area_id: 0,
+            custom_data: vec![0; self.mesh.game.area_data_size().unwrap_or(0)],
+        });
+        Ok(())
+    }
+
+    /// Remove an area and all references to it from other areas and ladders
+    pub fn remove_area(&mut self, id: NavAreaId) -> Result<NavArea, EditError> {
+        let mut removed = self.remove_areas(&[id])?;
+        Ok(removed.remove(0))
+    }
+
+    /// Remove multiple areas and all references to them from other areas and ladders
+    ///
+    /// This is a lot faster than removing the areas one by one. If any of the areas doesn't
+    /// exist, no area is removed.
+    pub fn remove_areas(&mut self, ids: &[NavAreaId]) -> Result<Vec<NavArea>, EditError> {
+        if let Some(id) = ids.iter().find(|id| !self.index.contains_key(id)) {
+            return Err(EditError::UnknownArea(*id));
+        }
+        let ids: HashSet<NavAreaId> = ids.iter().copied().collect();
+        let is_removed = |id: &NavAreaId| ids.contains(id);
+
+        let (removed, areas) = std::mem::take(&mut self.mesh.areas)
+            .into_iter()
+            .partition(|area| is_removed(&area.id));
+        self.mesh.areas = areas;
+        self.reindex();
+
+        for other in &mut self.mesh.areas {
+            for direction in other.connections.0.iter_mut() {
+                direction.retain(|target| !is_removed(target));
+            }
+            other
+                .encounter_paths
+                .retain(|path| !is_removed(&path.from_area_id) && !is_removed(&path.to_area_id));
+            other.approach_areas.retain(|approach| {
+                !is_removed(&approach.approach_here)
+                    && !is_removed(&approach.approach_pre)
+                    && !is_removed(&approach.approach_next)
+            });
+            other
+                .visible_areas
+                .retain(|visible| !is_removed(&visible.id));
+            if is_removed(&other.inherit_visibility_from_area_id.into()) {
+                other.inherit_visibility_from_area_id = 0;
+            }
+        }
+
+        for ladder in &mut self.mesh.ladders {
+            for target in [
+                &mut ladder.top_forward_area,
+                &mut ladder.top_left_area,
+                &mut ladder.top_right_area,
+                &mut ladder.top_behind_area,
+                &mut ladder.bottom_area,
+            ]
+            .iter_mut()
+            {
+                if target.is_some_and(|id| is_removed(&id)) {
+                    **target = None;
+                }
+            }
+        }
+
+        Ok(removed)
+    }
+
+    /// Move a corner of an area
+    ///
+    /// Since areas are axis aligned, this also moves the x or y coordinate of the neighbouring
+    /// corners.
+    pub fn move_corner(
+        &mut self,
+        id: NavAreaId,
+        corner: NavCorner,
+        position: Vector3,
+    ) -> Result<(), EditError> {
+        let area = self.area_mut(id)?;
+        let mut quad = area.quad.clone();
+        match corner {
+            NavCorner::NorthWest => quad.north_west = position,
+            NavCorner::NorthEast => {
+                quad.south_east.0 = position.0;
+                quad.north_west.1 = position.1;
+                quad.north_east_z = position.2;
+            }
+            NavCorner::SouthEast => quad.south_east = position,
+            NavCorner::SouthWest => {
+                quad.north_west.0 = position.0;
+                quad.south_east.1 = position.1;
+                quad.south_west_z = position.2;
+            }
+        }
+        if quad.width() <= 0.0 || quad.height() <= 0.0 {
+            return Err(EditError::InvalidQuad(id));
+        }
+        area.quad = quad;
+        Ok(())
+    }
+
+    /// Replace the corners of an area
+    pub fn set_quad(&mut self, id: NavAreaId, quad: NavQuad) -> Result<(), EditError> {
+        if quad.width() <= 0.0 || quad.height() <= 0.0 {
+            return Err(EditError::InvalidQuad(id));
+        }
+        self.area_mut(id)?.quad = quad;
+        Ok(())
+    }
+
+    /// Set the attribute flags of an area
+    pub fn set_flags(&mut self, id: NavAreaId, flags: u32) -> Result<(), EditError> {
+        self.area_mut(id)?.flags = flags;
+        Ok(())
+    }
+
+    /// Set the place of an area by name, adding the place to the place table if needed
+    pub fn set_place(&mut self, id: NavAreaId, place: Option<&str>) -> Result<(), EditError> {
+        self.area_mut(id)?;
+
+        let place = match place {
+            Some(name) => match self.mesh.places.iter().find(|place| place.name == name) {
+                Some(place) => place.id,
+                None => {
+                    if self.mesh.places.len() >= u16::MAX as usize {
+                        return Err(EditError::TooManyPlaces);
+                    }
+                    let place_id = self.mesh.places.len() as u16 + 1;
+                    self.mesh.places.push(NavPlace {
+                        id: place_id,
+                        name: name.to_string(),
+                    });
+                    place_id
+                }
+            },
+            None => 0,
+        };
+
+        self.area_mut(id)?.place = place;
+        Ok(())
+    }
+
+    /// Connect two areas in both directions
+    ///
+    /// The `direction` is the direction from `from` to `to`, the reverse connection is added
+    /// in the opposite direction. Existing connections are not duplicated.
+    pub fn connect(
+        &mut self,
+        from: NavAreaId,
+        to: NavAreaId,
+        direction: NavDirection,
+    ) -> Result<(), EditError> {
+        self.area_mut(to)?;
+        self.connect_one_way(from, to, direction)?;
+        self.connect_one_way(to, from, direction.opposite())
+    }
+
+    /// Connect an area to another area without adding the reverse connection
+    ///
+    /// One way connections are used for drops that can't be climbed back up.
+    pub fn connect_one_way(
+        &mut self,
+        from: NavAreaId,
+        to: NavAreaId,
+        direction: NavDirection,
+    ) -> Result<(), EditError> {
+        if !self.index.contains_key(&to) {
+            return Err(EditError::UnknownArea(to));
+        }
+        let connections = &mut self.area_mut(from)?.connections.0[direction as u8 as usize];
+        if !connections.contains(&to) {
+            connections.push(to);
+        }
+        Ok(())
+    }
+
+    /// Remove all connections between two areas, in both directions
+    pub fn disconnect(&mut self, a: NavAreaId, b: NavAreaId) -> Result<(), EditError> {
+        self.area_mut(b)?;
+        for direction in self.area_mut(a)?.connections.0.iter_mut() {
+            direction.retain(|target| *target != b);
+        }
+        for direction in self.area_mut(b)?.connections.0.iter_mut() {
+            direction.retain(|target| *target != a);
+        }
+        Ok(())
+    }
+
+    /// Remove the connection from an area to another area without removing the reverse connection
+    pub fn disconnect_one_way(&mut self, from: NavAreaId, to: NavAreaId) -> Result<(), EditError> {
+        for direction in self.area_mut(from)?.connections.0.iter_mut() {
+            direction.retain(|target| *target != to);
+        }
+        Ok(())
+    }
+
+    fn next_id(&self) -> Result<NavAreaId, EditError> {
+        let id = self
+            .mesh
+            .areas
+            .iter()
+            .map(|area| u32::from(area.id))
+            .max()
+            .unwrap_or(0)
+            .checked_add(1)
+            .ok_or(EditError::TooManyAreas)?;
+        Ok(NavAreaId::from(id))
+    }
+
+    /// Split an area in two along a line
+    ///
+    /// The area is split at the x coordinate for [`NavAxis::X`] or at the y coordinate for
+    /// [`NavAxis::Y`]. The existing area keeps the west or north part, the east or south part
+    /// becomes a new area of which the id is returned.
+    ///
+    /// The height of the new corners is interpolated from the original corners. Connections
+    /// from and to the area are divided over both parts based on which part borders the
+    /// connected area, both parts are connected to each other.
+    ///
+    /// [`NavAxis::X`]: ./enum.NavAxis.html#variant.X
+    /// [`NavAxis::Y`]: ./enum.NavAxis.html#variant.Y
+    pub fn split_area(
+        &mut self,
+        id: NavAreaId,
+        axis: NavAxis,
+        coordinate: f32,
+    ) -> Result<NavAreaId, EditError> {
+        let area = self.area(id).ok_or(EditError::UnknownArea(id))?.clone();
+        let quad = &area.quad;
+        let (min, max) = axis.range(quad);
+        if coordinate <= min || coordinate >= max {
+            return Err(EditError::InvalidSplit(id));
+        }
+
+        let (first_quad, second_quad) = match axis {
+            NavAxis::X => {
+                let north = quad.interpolated_z(coordinate, quad.north_west.1);
+                let south = quad.interpolated_z(coordinate, quad.south_east.1);
+                (
+                    NavQuad {
+                        north_west: quad.north_west,
+                        south_east: Vector3(coordinate, quad.south_east.1, south),
+                        north_east_z: north,
+                        south_west_z: quad.south_west_z,
+                    },
+                    NavQuad {
+                        north_west: Vector3(coordinate, quad.north_west.1, north),
+                        south_east: quad.south_east,
+                        north_east_z: quad.north_east_z,
+                        south_west_z: south,
+                    },
+                )
+            }
+            NavAxis::Y => {
+                let west = quad.interpolated_z(quad.north_west.0, coordinate);
+                let east = quad.interpolated_z(quad.south_east.0, coordinate);
+                (
+                    NavQuad {
+                        north_west: quad.north_west,
+                        south_east: Vector3(quad.south_east.0, coordinate, east),
+                        north_east_z: quad.north_east_z,
+                        south_west_z: west,
+                    },
+                    NavQuad {
+                        north_west: Vector3(quad.north_west.0, coordinate, west),
+                        south_east: quad.south_east,
+                        north_east_z: east,
+                        south_west_z: quad.south_west_z,
+                    },
+                )
+            }
+        };
+        let is_second = |x: f32, y: f32| match axis {
+            NavAxis::X => x >= coordinate,
+            NavAxis::Y => y >= coordinate,
+        };
+
+        let second_id = self.next_id()?;
+        self.add_area_with_id(second_id, second_quad.clone())?;
+        let far = axis.direction();
+
+        // divide the outgoing connections over both parts
+        let mut first_connections = Connections::default();
+        let mut second_connections = Connections::default();
+        for direction in NavDirection::ALL.iter() {
+            for target in &area.connections[*direction] {
+                let (to_first, to_second) = if *direction == far {
+                    (false, true)
+                } else if *direction == far.opposite() {
+                    (true, false)
+                } else {
+                    match self.area(*target) {
+                        Some(target) => {
+                            let range = axis.range(&target.quad);
+                            (
+                                ranges_overlap(range, axis.range(&first_quad)),
+                                ranges_overlap(range, axis.range(&second_quad)),
+                            )
+                        }
+                        None => (true, false),
+                    }
+                };
+                if to_first {
+                    first_connections.0[*direction as u8 as usize].push(*target);
+                }
+                if to_second {
+                    second_connections.0[*direction as u8 as usize].push(*target);
+                }
+            }
+        }
+        first_connections.0[far as u8 as usize].push(second_id);
+        second_connections.0[far.opposite() as u8 as usize].push(id);
+
+        // divide the ladders over both parts by the position of the end of the ladder
+        let mut first_ladders = LadderConnections::default();
+        let mut second_ladders = LadderConnections::default();
+        for (direction, ladders) in area.ladder_connections.0.iter().enumerate() {
+            for ladder_id in ladders {
+                let ladder = self.mesh.ladders.iter_mut().find(|l| l.id == *ladder_id);
+                let second = match ladder {
+                    Some(ladder) => {
+                        let end = if ladder.bottom_area == Some(id) {
+                            ladder.bottom
+                        } else {
+                            ladder.top
+                        };
+                        let second = is_second(end.0, end.1);
+                        if second {
+                            for target in [
+                                &mut ladder.top_forward_area,
+                                &mut ladder.top_left_area,
+                                &mut ladder.top_right_area,
+                                &mut ladder.top_behind_area,
+                                &mut ladder.bottom_area,
+                            ]
+                            .iter_mut()
+                            {
+                                if **target == Some(id) {
+                                    **target = Some(second_id);
+                                }
+                            }
+                        }
+                        second
+                    }
+                    None => false,
+                };
+                if second {
+                    second_ladders.0[direction].push(*ladder_id);
+                } else {
+                    first_ladders.0[direction].push(*ladder_id);
+                }
+            }
+        }
+
+        // redirect the incoming connections to the parts bordering the connected area
+        for other in &mut self.mesh.areas {
+            if other.id == id || other.id == second_id {
+                continue;
+            }
+            let other_range = axis.range(&other.quad);
+            for direction in NavDirection::ALL.iter() {
+                let connections = &mut other.connections.0[*direction as u8 as usize];
+                if !connections.contains(&id) {
+                    continue;
+                }
+                let (to_first, to_second) = if *direction == far {
+                    (true, false)
+                } else if *direction == far.opposite() {
+                    (false, true)
+                } else {
+                    (
+                        ranges_overlap(other_range, axis.range(&first_quad)),
+                        ranges_overlap(other_range, axis.range(&second_quad)),
+                    )
+                };
+                if !to_first {
+                    connections.retain(|target| *target != id);
+                }
+                if to_second {
+                    connections.push(second_id);
+                }
+            }
+
+            if let Some(visible) = other.visible_areas.iter().find(|visible| visible.id == id) {
+                let visible = VisibleArea {
+                    id: second_id,
+                    attributes: visible.attributes,
+                };
+                other.visible_areas.push(visible);
+            }
+        }
+
+        let (first_spots, second_spots) = area
+            .hiding_spots
+            .iter()
+            .cloned()
+            .partition(|spot| !is_second(spot.location.0, spot.location.1));
+
+        let first = self.area_mut(id)?;
+        first.quad = first_quad;
+        first.connections = first_connections;
+        first.ladder_connections = first_ladders;
+        first.hiding_spots = first_spots;
+
+        let second = self.area_mut(second_id)?;
+        second.connections = second_connections;
+        second.ladder_connections = second_ladders;
+        second.hiding_spots = second_spots;
+        second.flags = area.flags;
+        second.place = area.place;
+        second.light_intensity = area.light_intensity.clone();
+        second.earliest_occupy_first_team = area.earliest_occupy_first_team;
+        second.earliest_occupy_second_team = area.earliest_occupy_second_team;
+        second.visible_areas = area.visible_areas.clone();
+        second.inherit_visibility_from_area_id = area.inherit_visibility_from_area_id;
+        second.custom_data = area.custom_data.clone();
+
+        Ok(second_id)
+    }
+
+    /// Merge two neighbouring areas into a single area
+    ///
+    /// The areas need to share a full edge, so that together they form a rectangle. The merged
+    /// area keeps the id of the first area, all references to the second area are redirected to
+    /// the merged area.
+    pub fn merge_areas(&mut self, a: NavAreaId, b: NavAreaId) -> Result<(), EditError> {
+        const EPSILON: f32 = 1.0;
+        if a == b {
+            return Err(EditError::NotMergeable(a, b));
+        }
+        let first = self.area(a).ok_or(EditError::UnknownArea(a))?;
+        let second = self.area(b).ok_or(EditError::UnknownArea(b))?;
+        let (p, q) = (&first.quad, &second.quad);
+
+        let direction = p
+            .touching_direction(q, f32::INFINITY)
+            .ok_or(EditError::NotMergeable(a, b))?;
+        let axis = match direction {
+            NavDirection::North | NavDirection::South => NavAxis::X,
+            NavDirection::East | NavDirection::West => NavAxis::Y,
+        };
+        let (p_range, q_range) = (axis.range(p), axis.range(q));
+        if (p_range.0 - q_range.0).abs() > EPSILON || (p_range.1 - q_range.1).abs() > EPSILON {
+            return Err(EditError::NotMergeable(a, b));
+        }
+        let quad = match direction {
+            NavDirection::East => NavQuad {
+                north_west: p.north_west,
+                south_east: q.south_east,
+                north_east_z: q.north_east_z,
+                south_west_z: p.south_west_z,
+            },
+            NavDirection::West => NavQuad {
+                north_west: q.north_west,
+                south_east: p.south_east,
+                north_east_z: p.north_east_z,
+                south_west_z: q.south_west_z,
+            },
+            NavDirection::South => NavQuad {
+                north_west: p.north_west,
+                south_east: q.south_east,
+                north_east_z: p.north_east_z,
+                south_west_z: q.south_west_z,
+            },
+            NavDirection::North => NavQuad {
+                north_west: q.north_west,
+                south_east: p.south_east,
+                north_east_z: q.north_east_z,
+                south_west_z: p.south_west_z,
+            },
+        };
+
+        let mut connections = first.connections.clone();
+        for (merged, extra) in connections.0.iter_mut().zip(second.connections.0.iter()) {
+            merged.extend(extra.iter().copied());
+            merged.retain(|target| *target != a && *target != b);
+            dedup(merged);
+        }
+        let mut ladders = first.ladder_connections.clone();
+        for (merged, extra) in ladders.0.iter_mut().zip(second.ladder_connections.0.iter()) {
+            merged.extend(extra.iter().copied());
+            dedup(merged);
+        }
+        let mut hiding_spots = first.hiding_spots.clone();
+        hiding_spots.extend(second.hiding_spots.iter().cloned());
+        let mut visible_areas = first.visible_areas.clone();
+        for visible in &second.visible_areas {
+            if !visible_areas
+                .iter()
+                .any(|existing| existing.id == visible.id)
+            {
+                visible_areas.push(visible.clone());
+            }
+        }
+
+        // redirect all references to the second area
+        let redirect = |id: &mut NavAreaId| {
+            if *id == b {
+                *id = a;
+            }
+        };
+        for other in &mut self.mesh.areas {
+            for direction in other.connections.0.iter_mut() {
+                direction.iter_mut().for_each(redirect);
+                dedup(direction);
+            }
+            for path in &mut other.encounter_paths {
+                redirect(&mut path.from_area_id);
+                redirect(&mut path.to_area_id);
+            }
+            for approach in &mut other.approach_areas {
+                redirect(&mut approach.approach_here);
+                redirect(&mut approach.approach_pre);
+                redirect(&mut approach.approach_next);
+            }
+            for visible in &mut other.visible_areas {
+                redirect(&mut visible.id);
+            }
+            let mut visible_ids = HashSet::new();
+            other
+                .visible_areas
+                .retain(|visible| visible_ids.insert(visible.id));
+            if other.inherit_visibility_from_area_id == u32::from(b) {
+                other.inherit_visibility_from_area_id = a.into();
+            }
+        }
+        for ladder in &mut self.mesh.ladders {
+            for target in [
+                &mut ladder.top_forward_area,
+                &mut ladder.top_left_area,
+                &mut ladder.top_right_area,
+                &mut ladder.top_behind_area,
+                &mut ladder.bottom_area,
+            ]
+            .iter_mut()
+            .filter_map(|target| target.as_mut())
+            {
+                redirect(target);
+            }
+        }
+
+        let merged = self.area_mut(a)?;
+        merged.quad = quad;
+        merged.connections = connections;
+        merged.ladder_connections = ladders;
+        merged.hiding_spots = hiding_spots;
+        merged.visible_areas = visible_areas;
+
+        let index = self.index[&b];
+        self.mesh.areas.remove(index);
+        self.reindex();
+
+        Ok(())
+    }
+}
+
+/// Remove duplicate ids while keeping the order
+fn dedup<T: Copy + Eq + std::hash::Hash>(ids: &mut Vec<T>) {
+    let mut seen = HashSet::new();
+    ids.retain(|id| seen.insert(*id));
+}
+
+#[test]
+fn test_builder() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut builder = NavMeshBuilder::from_mesh(crate::read_mesh(file).unwrap());
+
+    let first = builder.mesh().areas[0].id;
+    let quad = builder.area(first).unwrap().quad.clone();
+    let new = builder
+        .add_area(NavQuad {
+            north_west: Vector3(quad.north_west.0, quad.south_east.1, quad.south_west_z),
+            south_east: Vector3(
+                quad.south_east.0,
+                quad.south_east.1 + quad.height(),
+                quad.south_east.2,
+            ),
+            north_east_z: quad.south_east.2,
+            south_west_z: quad.south_west_z,
+        })
+        .unwrap();
+
+    builder.connect(first, new, NavDirection::South).unwrap();
+    builder.set_place(new, Some("Bridge")).unwrap();
+    assert!(builder.area(first).unwrap().connections[NavDirection::South].contains(&new));
+    assert_eq!(
+        vec![first],
+        builder.area(new).unwrap().connections[NavDirection::North]
+    );
+
+    let mesh = builder.clone().build();
+    assert_eq!(1931, mesh.areas.len());
+    assert!(mesh
+        .check_consistency(None)
+        .iter()
+        .all(|issue| !issue.is_fatal()));
+    let written = mesh.to_bytes(16).unwrap();
+    let parsed = crate::read_mesh(written).unwrap();
+    assert_eq!(mesh, parsed);
+    assert_eq!(
+        "Bridge",
+        parsed
+            .area_place(parsed.areas.last().unwrap())
+            .unwrap()
+            .name
+    );
+
+    builder.remove_area(first).unwrap();
+    assert!(builder
+        .area(new)
+        .unwrap()
+        .connections
+        .iter()
+        .next()
+        .is_none());
+    assert!(builder
+        .mesh()
+        .areas
+        .iter()
+        .all(|area| area.connections.iter().all(|target| *target != first)));
+    assert_eq!(
+        Err(EditError::UnknownArea(first)),
+        builder.set_flags(first, 1)
+    );
+}
+
+#[test]
+fn test_split_merge() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    let mut builder = NavMeshBuilder::from_mesh(mesh.clone());
+
+    let sorted = |connections: &Connections| {
+        let mut connections = connections.clone();
+        for direction in connections.0.iter_mut() {
+            direction.sort();
+        }
+        connections
+    };
+
+    for axis in [NavAxis::X, NavAxis::Y].iter() {
+        let area = mesh
+            .areas
+            .iter()
+            .find(|area| area.connections.iter().count() > 4 && area.quad.width() > 50.0)
+            .unwrap();
+        let (min, max) = axis.range(&area.quad);
+        let split = (min + max) / 2.0;
+
+        let second = builder.split_area(area.id, *axis, split).unwrap();
+        let first_part = builder.area(area.id).unwrap();
+        let second_part = builder.area(second).unwrap();
+        assert_eq!(split, axis.range(&first_part.quad).1);
+        assert_eq!(split, axis.range(&second_part.quad).0);
+        assert!(first_part.connections[axis.direction()].contains(&second));
+        assert!(builder
+            .mesh()
+            .check_consistency(None)
+            .iter()
+            .all(|issue| !issue.is_fatal()));
+
+        builder.merge_areas(area.id, second).unwrap();
+        let merged = builder.area(area.id).unwrap();
+        assert_eq!(area.quad, merged.quad);
+        assert_eq!(sorted(&area.connections), sorted(&merged.connections));
+        assert!(builder.area(second).is_none());
+        assert_eq!(1930, builder.mesh().areas.len());
+    }
+
+    let area = mesh.areas[0].id;
+    assert_eq!(
+        Err(EditError::InvalidSplit(area)),
+        builder.split_area(area, NavAxis::X, 1.0e9)
+    );
+}
+
+#[test]
+fn test_ids_exhausted() {
+    let mut builder = NavMeshBuilder::new(NavGame::Unknown);
+    let quad = |x: f32| NavQuad {
+        north_west: Vector3(x, 0.0, 0.0),
+        south_east: Vector3(x + 100.0, 100.0, 0.0),
+        north_east_z: 0.0,
+        south_west_z: 0.0,
+    };
+    builder
+        .add_area_with_id(NavAreaId::from(u32::MAX), quad(0.0))
+        .unwrap();
+
+    assert!(matches!(
+        builder.add_area(quad(200.0)),
+        Err(EditError::TooManyAreas)
+    ));
+    assert!(matches!(
+        builder.split_area(NavAreaId::from(u32::MAX), NavAxis::X, 50.0),
+        Err(EditError::TooManyAreas)
+    ));
+    assert_eq!(1, builder.mesh().areas.len());
+}
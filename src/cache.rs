@@ -0,0 +1,131 @@
+use crate::navmesh::{NavArea, NavLadder};
+use crate::{NavGame, NavTree};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+const CACHE_MAGIC: &[u8; 8] = b"NAVCACHE";
+
+/// Increased whenever the layout of the cache changes
+const CACHE_VERSION: u32 = 3;
+
+/// Errors that can occur when loading a cached tree
+#[derive(Debug, Error)]
+pub enum CacheError {
+    /// The data is not a tree cache
+    #[error("Invalid cache data")]
+    InvalidCache,
+    /// The cache was created by a different version of this library and needs to be rebuild
+    #[error("The cache was created with an incompatible version ({0})")]
+    VersionMismatch(String),
+    /// An error occurred while encoding or decoding the cached data
+    #[error("Error while encoding or decoding the cache: {0}")]
+    Encoding(#[from] bincode::Error),
+}
+
+#[derive(Serialize, Deserialize)]
+struct TreeCache {
+    version: u32,
+    library_version: String,
+    game: NavGame,
+    areas: Vec<(NavArea, u64)>,
+    ladders: Vec<NavLadder>,
+}
+
+impl NavTree {
+    /// Serialize the tree into a compact binary cache
+    ///
+    /// The cache can only be loaded by the same version of this library, caches from other
+    /// versions are rejected with [`CacheError::VersionMismatch`] and need to be rebuild from the
+    /// nav file.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, CacheError, NavTree};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let tree = match std::fs::read("path/to/navfile.cache") {
+    ///     Ok(cache) => NavTree::from_cache(&cache)?,
+    ///     Err(_) => {
+    ///         let tree = get_area_tree(std::fs::read("path/to/navfile.nav")?)?;
+    ///         std::fs::write("path/to/navfile.cache", tree.to_cache()?)?;
+    ///         tree
+    ///     }
+    /// };
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`CacheError::VersionMismatch`]: ./enum.CacheError.html#variant.VersionMismatch
+    pub fn to_cache(&self) -> Result<Vec<u8>, CacheError> {
+        let mut areas: Vec<_> = self
+            .areas_with_hashes()
+            .map(|(area, hash)| (area.clone(), hash))
+            .collect();
+        areas.sort_by_key(|(area, _)| area.id);
+        let mut ladders: Vec<_> = self.ladders().cloned().collect();
+        ladders.sort_by_key(|ladder| ladder.id);
+
+        let cache = TreeCache {
+            version: CACHE_VERSION,
+            library_version: env!("CARGO_PKG_VERSION").to_string(),
+            game: self.game(),
+            areas,
+            ladders,
+        };
+
+        let mut data = CACHE_MAGIC.to_vec();
+        bincode::serialize_into(&mut data, &cache)?;
+        Ok(data)
+    }
+
+    /// Load a tree from a cache created by [`to_cache`]
+    ///
+    /// [`to_cache`]: #method.to_cache
+    pub fn from_cache(data: &[u8]) -> Result<NavTree, CacheError> {
+        if !data.starts_with(CACHE_MAGIC) {
+            return Err(CacheError::InvalidCache);
+        }
+        let data = &data[CACHE_MAGIC.len()..];
+
+        // check the version before decoding the rest, which might have a different layout
+        let (version, library_version): (u32, String) = bincode::deserialize(data)?;
+        if version != CACHE_VERSION || library_version != env!("CARGO_PKG_VERSION") {
+            return Err(CacheError::VersionMismatch(library_version));
+        }
+
+        let cache: TreeCache = bincode::deserialize(data)?;
+        Ok(NavTree::build(
+            cache.areas.into_iter(),
+            cache.ladders,
+            cache.game,
+        ))
+    }
+}
+
+#[test]
+fn test_cache() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let cache = tree.to_cache().unwrap();
+    let loaded = NavTree::from_cache(&cache).unwrap();
+
+    assert_eq!(tree.len(), loaded.len());
+    let mut heights: Vec<f32> = tree.find_z_height(320.0, -1030.0).collect();
+    let mut loaded_heights: Vec<f32> = loaded.find_z_height(320.0, -1030.0).collect();
+    heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    loaded_heights.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    assert_eq!(heights, loaded_heights);
+
+    let mut outdated = cache.clone();
+    outdated[CACHE_MAGIC.len()] += 1;
+    assert!(matches!(
+        NavTree::from_cache(&outdated),
+        Err(CacheError::VersionMismatch(_))
+    ));
+    assert!(matches!(
+        NavTree::from_cache(&cache[1..]),
+        Err(CacheError::InvalidCache)
+    ));
+}
@@ -0,0 +1,216 @@
+use crate::navmesh::NavPlace;
+use std::sync::OnceLock;
+
+/// Well-known place names as used by the place database shipped with Valve games
+///
+/// Sorted by their normalized form, see [`normalize_place_name`].
+///
+/// [`normalize_place_name`]: ./fn.normalize_place_name.html
+static PLACE_NAMES: &[&str] = &[
+    "Airplane",
+    "Alley",
+    "Apartment",
+    "Apartments",
+    "Arch",
+    "Atrium",
+    "Attic",
+    "Back",
+    "BackAlley",
+    "BackDoor",
+    "BackHall",
+    "BackPlat",
+    "BackRoom",
+    "BackWay",
+    "BackYard",
+    "Balcony",
+    "Banana",
+    "Bank",
+    "Basement",
+    "Bathroom",
+    "Bedroom",
+    "BigOffice",
+    "BlueRoom",
+    "Boiler",
+    "BombsiteA",
+    "BombsiteB",
+    "BombsiteC",
+    "Bridge",
+    "Bunker",
+    "Catwalk",
+    "Cellar",
+    "ComputerRoom",
+    "ConferenceRoom",
+    "Connector",
+    "Construction",
+    "Courtyard",
+    "Crates",
+    "Crawlspace",
+    "CTSpawn",
+    "Deck",
+    "Den",
+    "Dock",
+    "DoubleDoors",
+    "Downstairs",
+    "Ducts",
+    "Dumpster",
+    "Elevator",
+    "Entrance",
+    "Entryway",
+    "FamilyRoom",
+    "FarSide",
+    "Fence",
+    "Foyer",
+    "Front",
+    "FrontDoor",
+    "FrontHall",
+    "FrontRoom",
+    "FrontYard",
+    "Garage",
+    "Gate",
+    "GateHouse",
+    "Graveyard",
+    "GuardHouse",
+    "Heaven",
+    "Hell",
+    "HostageRescueZone",
+    "Hostages",
+    "House",
+    "Inside",
+    "Jungle",
+    "Kitchen",
+    "Ladder",
+    "Library",
+    "LittleOffice",
+    "LivingRoom",
+    "LoadingDock",
+    "Lobby",
+    "Loft",
+    "Long",
+    "LongHall",
+    "Lower",
+    "MainHall",
+    "Market",
+    "MeetingRoom",
+    "Middle",
+    "Mines",
+    "Office",
+    "Outside",
+    "Overpass",
+    "Palace",
+    "Patio",
+    "Pit",
+    "Porch",
+    "ProjectorRoom",
+    "Quad",
+    "Ramp",
+    "Rear",
+    "Roof",
+    "Sandbags",
+    "SecurityDoors",
+    "Sewers",
+    "Short",
+    "Side",
+    "SideAlley",
+    "SideDoor",
+    "SideHall",
+    "SideRoom",
+    "SideYard",
+    "Snipers",
+    "Stairs",
+    "Stairwell",
+    "StorageRoom",
+    "Tower",
+    "Truck",
+    "TSpawn",
+    "Tunnel",
+    "Tunnels",
+    "Underground",
+    "Underpass",
+    "Upper",
+    "Upstairs",
+    "Vault",
+    "VendingMachines",
+    "Village",
+    "VIPRescueZone",
+    "Wall",
+    "Water",
+    "Window",
+    "Windows",
+    "WineCellar",
+];
+
+/// Normalize a place name for comparison
+///
+/// Strips everything except ascii letters and digits and lowercases the result,
+/// so `"Bombsite A"`, `"bombsite_a"` and `"BombsiteA"` all normalize to `"bombsitea"`
+pub fn normalize_place_name(name: &str) -> String {
+    name.chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Find the well-known spelling for a place name
+///
+/// ## Examples
+///
+/// ```
+/// use sourcenav::canonical_place_name;
+///
+/// assert_eq!(Some("CTSpawn"), canonical_place_name("ct_spawn"));
+/// assert_eq!(None, canonical_place_name("MyCustomPlace"));
+/// ```
+pub fn canonical_place_name(name: &str) -> Option<&'static str> {
+    let key = normalize_place_name(name);
+    normalized_place_names()
+        .binary_search(&key)
+        .ok()
+        .map(|index| PLACE_NAMES[index])
+}
+
+/// The normalized forms of the well-known place names, in the same order as the names
+fn normalized_place_names() -> &'static [String] {
+    static NORMALIZED: OnceLock<Vec<String>> = OnceLock::new();
+    NORMALIZED.get_or_init(|| {
+        PLACE_NAMES
+            .iter()
+            .map(|name| normalize_place_name(name))
+            .collect()
+    })
+}
+
+impl NavPlace {
+    /// Get the well-known spelling of the place name
+    ///
+    /// Falls back to the name from the nav file when the place isn't a well-known place
+    pub fn canonical_name(&self) -> &str {
+        canonical_place_name(&self.name).unwrap_or(&self.name)
+    }
+
+    /// Check if the place is one of the well-known places
+    pub fn is_well_known(&self) -> bool {
+        canonical_place_name(&self.name).is_some()
+    }
+}
+
+#[test]
+fn test_place_names_sorted() {
+    assert!(normalized_place_names()
+        .windows(2)
+        .all(|pair| pair[0] < pair[1]));
+}
+
+#[test]
+fn test_canonical_name() {
+    let place = NavPlace {
+        id: 1,
+        name: "Bombsite A".to_string(),
+    };
+    assert_eq!("BombsiteA", place.canonical_name());
+
+    let place = NavPlace {
+        id: 2,
+        name: "custom_place".to_string(),
+    };
+    assert_eq!("custom_place", place.canonical_name());
+}
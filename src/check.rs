@@ -0,0 +1,250 @@
+use crate::navmesh::{NavAreaId, NavLadderId};
+use crate::NavMesh;
+use std::collections::HashSet;
+use std::fmt;
+
+/// The highest nav version the engine can load
+const MAX_ENGINE_VERSION: u32 = 16;
+
+/// A problem with a nav mesh that the engine would detect when loading the nav file
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConsistencyIssue {
+    /// The version of the nav mesh is newer than what the engine supports
+    UnsupportedVersion(u32),
+    /// The nav was generated for a different version of the map
+    BspSizeMismatch { expected: u32, found: u32 },
+    /// The nav mesh hasn't been analyzed, the engine will warn about missing hiding spots and visibility
+    NotAnalyzed,
+    /// An area has the reserved id `0`
+    ReservedAreaId,
+    /// Multiple areas share the same id
+    DuplicateAreaId(NavAreaId),
+    /// An area is connected to an area that doesn't exist
+    MissingConnection { area: NavAreaId, target: NavAreaId },
+    /// An area has a ladder connection to a ladder that doesn't exist
+    MissingLadder {
+        area: NavAreaId,
+        ladder: NavLadderId,
+    },
+    /// Multiple ladders share the same id
+    DuplicateLadderId(NavLadderId),
+    /// A ladder is connected to an area that doesn't exist
+    MissingLadderArea {
+        ladder: NavLadderId,
+        target: NavAreaId,
+    },
+    /// An area references a place that isn't in the place table
+    UnknownPlace { area: NavAreaId, place: u16 },
+    /// An area has an encounter path that refers to an area that doesn't exist
+    MissingEncounterArea { area: NavAreaId, target: NavAreaId },
+    /// An area inherits it's visibility from an area that doesn't exist
+    MissingVisibilityArea { area: NavAreaId, target: NavAreaId },
+}
+
+impl ConsistencyIssue {
+    /// Whether the engine refuses the nav file (and regenerates it) because of this issue
+    ///
+    /// Non fatal issues only cause warnings when loading the nav
+    pub fn is_fatal(&self) -> bool {
+        !matches!(
+            self,
+            ConsistencyIssue::BspSizeMismatch { .. }
+                | ConsistencyIssue::NotAnalyzed
+                | ConsistencyIssue::UnknownPlace { .. }
+                | ConsistencyIssue::MissingEncounterArea { .. }
+                | ConsistencyIssue::MissingVisibilityArea { .. }
+        )
+    }
+}
+
+impl fmt::Display for ConsistencyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConsistencyIssue::UnsupportedVersion(version) => {
+                write!(f, "Nav version {} is not supported by the engine", version)
+            }
+            ConsistencyIssue::BspSizeMismatch { expected, found } => write!(
+                f,
+                "The nav was built for a different version of the map (bsp size {}, expected {})",
+                found, expected
+            ),
+            ConsistencyIssue::NotAnalyzed => write!(f, "The nav mesh has not been analyzed"),
+            ConsistencyIssue::ReservedAreaId => write!(f, "An area uses the reserved id 0"),
+            ConsistencyIssue::DuplicateAreaId(id) => write!(f, "Duplicate area id {}", id),
+            ConsistencyIssue::MissingConnection { area, target } => write!(
+                f,
+                "Area {} is connected to non existing area {}",
+                area, target
+            ),
+            ConsistencyIssue::MissingLadder { area, ladder } => write!(
+                f,
+                "Area {} is connected to non existing ladder {}",
+                area, ladder
+            ),
+            ConsistencyIssue::DuplicateLadderId(id) => write!(f, "Duplicate ladder id {}", id),
+            ConsistencyIssue::MissingLadderArea { ladder, target } => write!(
+                f,
+                "Ladder {} is connected to non existing area {}",
+                ladder, target
+            ),
+            ConsistencyIssue::UnknownPlace { area, place } => {
+                write!(f, "Area {} has unknown place {}", area, place)
+            }
+            ConsistencyIssue::MissingEncounterArea { area, target } => write!(
+                f,
+                "Area {} has an encounter path with non existing area {}",
+                area, target
+            ),
+            ConsistencyIssue::MissingVisibilityArea { area, target } => write!(
+                f,
+                "Area {} inherits visibility from non existing area {}",
+                area, target
+            ),
+        }
+    }
+}
+
+impl NavMesh {
+    /// Run the same consistency checks the engine performs when loading a nav file
+    ///
+    /// If the size of the bsp file is provided, it will be compared against the size stored in the nav.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let bsp_size = std::fs::metadata("path/to/map.bsp")?.len() as u32;
+    /// let mesh = read_mesh(file)?;
+    /// for issue in mesh.check_consistency(Some(bsp_size)) {
+    ///     println!("{}", issue);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn check_consistency(&self, bsp_size: Option<u32>) -> Vec<ConsistencyIssue> {
+        let mut issues = Vec::new();
+
+        if self.major_version > MAX_ENGINE_VERSION {
+            issues.push(ConsistencyIssue::UnsupportedVersion(self.major_version));
+        }
+
+        match bsp_size {
+            Some(expected) if expected != self.bsp_size => {
+                issues.push(ConsistencyIssue::BspSizeMismatch {
+                    expected,
+                    found: self.bsp_size,
+                });
+            }
+            _ => {}
+        }
+
+        if !self.is_analyzed {
+            issues.push(ConsistencyIssue::NotAnalyzed);
+        }
+
+        let mut ids = HashSet::with_capacity(self.areas.len());
+        for area in &self.areas {
+            if u32::from(area.id) == 0 {
+                issues.push(ConsistencyIssue::ReservedAreaId);
+            }
+            if !ids.insert(area.id) {
+                issues.push(ConsistencyIssue::DuplicateAreaId(area.id));
+            }
+        }
+
+        let mut ladder_ids = HashSet::with_capacity(self.ladders.len());
+        for ladder in &self.ladders {
+            if !ladder_ids.insert(ladder.id) {
+                issues.push(ConsistencyIssue::DuplicateLadderId(ladder.id));
+            }
+            let targets = [
+                ladder.top_forward_area,
+                ladder.top_left_area,
+                ladder.top_right_area,
+                ladder.top_behind_area,
+                ladder.bottom_area,
+            ];
+            for target in targets.iter().flatten() {
+                if !ids.contains(target) {
+                    issues.push(ConsistencyIssue::MissingLadderArea {
+                        ladder: ladder.id,
+                        target: *target,
+                    });
+                }
+            }
+        }
+
+        for area in &self.areas {
+            for target in area.connections.iter() {
+                if !ids.contains(target) {
+                    issues.push(ConsistencyIssue::MissingConnection {
+                        area: area.id,
+                        target: *target,
+                    });
+                }
+            }
+            for ladder in area.ladder_connections.iter() {
+                if !ladder_ids.contains(ladder) {
+                    issues.push(ConsistencyIssue::MissingLadder {
+                        area: area.id,
+                        ladder: *ladder,
+                    });
+                }
+            }
+            if area.place != 0 && self.place(area.place).is_none() {
+                issues.push(ConsistencyIssue::UnknownPlace {
+                    area: area.id,
+                    place: area.place,
+                });
+            }
+            for path in &area.encounter_paths {
+                for target in &[path.from_area_id, path.to_area_id] {
+                    if !ids.contains(target) {
+                        issues.push(ConsistencyIssue::MissingEncounterArea {
+                            area: area.id,
+                            target: *target,
+                        });
+                    }
+                }
+            }
+            let inherit = NavAreaId::from(area.inherit_visibility_from_area_id);
+            if u32::from(inherit) != 0 && !ids.contains(&inherit) {
+                issues.push(ConsistencyIssue::MissingVisibilityArea {
+                    area: area.id,
+                    target: inherit,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Check if the engine will accept the nav file without regenerating it
+    pub fn is_consistent(&self, bsp_size: Option<u32>) -> bool {
+        !self
+            .check_consistency(bsp_size)
+            .iter()
+            .any(ConsistencyIssue::is_fatal)
+    }
+}
+
+#[test]
+fn test_check_consistency() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut mesh = crate::read_mesh(file).unwrap();
+    assert_eq!(
+        Vec::<ConsistencyIssue>::new(),
+        mesh.check_consistency(Some(mesh.bsp_size))
+    );
+
+    let bsp_size = mesh.bsp_size + 1;
+    assert!(mesh.is_consistent(Some(bsp_size)));
+
+    let duplicate = mesh.areas[1].id;
+    mesh.areas[0].id = duplicate;
+    assert!(!mesh.is_consistent(None));
+}
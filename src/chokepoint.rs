@@ -0,0 +1,235 @@
+use crate::navmesh::{NavArea, NavAreaId};
+use crate::NavTree;
+use std::collections::HashMap;
+
+/// An area that splits the areas around it into regions that can't reach each other without it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chokepoint {
+    pub area: NavAreaId,
+    /// The smallest horizontal size of the area, narrow chokepoints are easier to hold
+    pub width: f32,
+    /// The number of areas cut off from the largest remaining region when the area is removed
+    pub separated: usize,
+}
+
+/// The result of searching the areas for articulation points and bridges
+struct CutAnalysis {
+    chokepoints: Vec<Chokepoint>,
+    bridges: Vec<(NavAreaId, NavAreaId)>,
+}
+
+impl NavTree {
+    /// Find the areas that split the map into regions when they are removed
+    ///
+    /// Areas are treated as connected when either area can be reached from the other, so a
+    /// chokepoint is an area that every route between the regions has to go through. The
+    /// chokepoints are sorted by area id.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let mut chokepoints = tree.chokepoints();
+    /// // narrow chokepoints that cut off large parts of the map first
+    /// chokepoints.retain(|chokepoint| chokepoint.separated > 50);
+    /// chokepoints.sort_by(|a, b| a.width.partial_cmp(&b.width).unwrap());
+    /// for chokepoint in chokepoints {
+    ///     println!("area {} is {} units wide", chokepoint.area, chokepoint.width);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn chokepoints(&self) -> Vec<Chokepoint> {
+        self.cut_analysis().chokepoints
+    }
+
+    /// Find the connections between areas that split the map into regions when they are removed
+    ///
+    /// Like [`chokepoints`], areas are treated as connected when either area can be reached from
+    /// the other. Every connection is returned once, with the lowest area id first, sorted by the
+    /// area ids.
+    ///
+    /// [`chokepoints`]: #method.chokepoints
+    pub fn bridges(&self) -> Vec<(NavAreaId, NavAreaId)> {
+        self.cut_analysis().bridges
+    }
+
+    fn cut_analysis(&self) -> CutAnalysis {
+        let mut areas: Vec<&NavArea> = self.areas().collect();
+        areas.sort_by_key(|area| area.id);
+        let position: HashMap<NavAreaId, usize> = areas
+            .iter()
+            .enumerate()
+            .map(|(index, area)| (area.id, index))
+            .collect();
+        let mut edges: Vec<Vec<usize>> = vec![Vec::new(); areas.len()];
+        for (index, area) in areas.iter().enumerate() {
+            for (next, _) in self.traversable_neighbors(area) {
+                match position.get(&next.id) {
+                    Some(next) if *next != index => {
+                        edges[index].push(*next);
+                        edges[*next].push(index);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        for neighbors in edges.iter_mut() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+
+        // depth first search keeping the lowest discovery order reachable from every subtree
+        let mut order: Vec<Option<usize>> = vec![None; areas.len()];
+        let mut low = vec![0; areas.len()];
+        let mut size = vec![1; areas.len()];
+        let mut parent: Vec<Option<usize>> = vec![None; areas.len()];
+        // the sizes of the subtrees that get cut off when an area is removed
+        let mut pieces: Vec<Vec<usize>> = vec![Vec::new(); areas.len()];
+        let mut region_size = vec![0; areas.len()];
+        let mut next_order = 0;
+        let mut bridges = Vec::new();
+
+        for root in 0..areas.len() {
+            if order[root].is_some() {
+                continue;
+            }
+            let mut visited = vec![root];
+            let mut visiting = vec![(root, 0)];
+            order[root] = Some(next_order);
+            low[root] = next_order;
+            next_order += 1;
+
+            while let Some((area, edge)) = visiting.last_mut() {
+                let area = *area;
+                if let Some(next) = edges[area].get(*edge).copied() {
+                    *edge += 1;
+                    match order[next] {
+                        None => {
+                            order[next] = Some(next_order);
+                            low[next] = next_order;
+                            next_order += 1;
+                            parent[next] = Some(area);
+                            visited.push(next);
+                            visiting.push((next, 0));
+                        }
+                        Some(next_order) if parent[area] != Some(next) => {
+                            low[area] = low[area].min(next_order);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                visiting.pop();
+                if let Some(parent) = parent[area] {
+                    size[parent] += size[area];
+                    low[parent] = low[parent].min(low[area]);
+                    let parent_order = order[parent].unwrap_or_default();
+                    if low[area] >= parent_order {
+                        pieces[parent].push(size[area]);
+                    }
+                    if low[area] > parent_order {
+                        let (a, b) = (areas[parent].id, areas[area].id);
+                        bridges.push((a.min(b), a.max(b)));
+                    }
+                }
+            }
+
+            for area in visited {
+                region_size[area] = size[root];
+            }
+        }
+
+        let chokepoints = areas
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| match parent[*index] {
+                Some(_) => !pieces[*index].is_empty(),
+                None => pieces[*index].len() > 1,
+            })
+            .map(|(index, area)| {
+                let others = region_size[index] - 1;
+                let remaining = others - pieces[index].iter().sum::<usize>();
+                let largest = pieces[index].iter().copied().fold(remaining, usize::max);
+                Chokepoint {
+                    area: area.id,
+                    width: area.quad.width().min(area.quad.height()),
+                    separated: others - largest,
+                }
+            })
+            .collect();
+        bridges.sort();
+
+        CutAnalysis {
+            chokepoints,
+            bridges,
+        }
+    }
+}
+
+#[test]
+fn test_chokepoints() {
+    use std::collections::HashSet;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let mut connected: HashMap<NavAreaId, HashSet<NavAreaId>> = HashMap::new();
+    for area in tree.areas() {
+        for (next, _) in tree.traversable_neighbors(area) {
+            connected.entry(area.id).or_default().insert(next.id);
+            connected.entry(next.id).or_default().insert(area.id);
+        }
+    }
+    // find the areas connected to an area, without going through an area or connection
+    let region = |start: NavAreaId, removed: NavAreaId, cut: (NavAreaId, NavAreaId)| {
+        let mut seen: HashSet<NavAreaId> = std::iter::once(start).collect();
+        let mut queue = vec![start];
+        while let Some(id) = queue.pop() {
+            for next in connected.get(&id).into_iter().flatten().copied() {
+                let is_cut = (id, next) == cut || (next, id) == cut;
+                if next != removed && !is_cut && seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        seen
+    };
+    let none = NavAreaId::from(u32::MAX);
+
+    let chokepoints = tree.chokepoints();
+    assert!(!chokepoints.is_empty());
+    assert!(chokepoints
+        .windows(2)
+        .all(|pair| pair[0].area < pair[1].area));
+
+    // removing a chokepoint separates it's neighbors
+    for chokepoint in chokepoints.iter().step_by(chokepoints.len() / 10 + 1) {
+        let area = &tree[chokepoint.area];
+        assert!(chokepoint.width > 0.0);
+        let mut regions: Vec<HashSet<NavAreaId>> = Vec::new();
+        for id in &connected[&area.id] {
+            if !regions.iter().any(|region| region.contains(id)) {
+                regions.push(region(*id, area.id, (none, none)));
+            }
+        }
+        assert!(regions.len() > 1);
+        let others = region(area.id, none, (none, none)).len() - 1;
+        let largest = regions.iter().map(HashSet::len).max().unwrap();
+        assert_eq!(others - largest, chokepoint.separated);
+    }
+
+    // removing a bridge separates it's areas
+    let bridges = tree.bridges();
+    assert!(!bridges.is_empty());
+    for (a, b) in bridges.iter().step_by(bridges.len() / 10 + 1) {
+        assert!(a < b);
+        assert!(connected[a].contains(b));
+        assert!(!region(*a, none, (*a, *b)).contains(b));
+    }
+}
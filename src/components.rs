@@ -0,0 +1,151 @@
+use crate::navmesh::{NavArea, NavAreaId};
+use crate::NavTree;
+use std::collections::HashMap;
+
+impl NavTree {
+    /// Group the areas into sets of areas that can all reach each other
+    ///
+    /// Two areas are in the same group if there is a path from the first to the second area and
+    /// back, following the connections, ladders and off-mesh links of the areas. Areas that can
+    /// only be left by dropping down form their own groups. A well connected map has one large
+    /// group and a few small groups for the areas that can't be returned to, many large groups
+    /// usually mean parts of the map are disconnected.
+    ///
+    /// The groups are sorted from largest to smallest, the areas within a group are sorted by id.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for component in tree.connected_components().iter().filter(|areas| areas.len() > 10) {
+    ///     println!("{} connected areas starting at {}", component.len(), component[0]);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn connected_components(&self) -> Vec<Vec<NavAreaId>> {
+        let mut areas: Vec<&NavArea> = self.areas().collect();
+        areas.sort_by_key(|area| area.id);
+        let position: HashMap<NavAreaId, usize> = areas
+            .iter()
+            .enumerate()
+            .map(|(index, area)| (area.id, index))
+            .collect();
+        let edges: Vec<Vec<usize>> = areas
+            .iter()
+            .map(|area| {
+                self.traversable_neighbors(area)
+                    .filter_map(|(next, _)| position.get(&next.id).copied())
+                    .collect()
+            })
+            .collect();
+
+        // tarjan's algorithm, with an explicit stack of the areas being visited
+        let mut order: Vec<Option<usize>> = vec![None; areas.len()];
+        let mut low = vec![0; areas.len()];
+        let mut on_stack = vec![false; areas.len()];
+        let mut stack = Vec::new();
+        let mut visiting: Vec<(usize, usize)> = Vec::new();
+        let mut next_order = 0;
+        let mut components = Vec::new();
+
+        for root in 0..areas.len() {
+            if order[root].is_some() {
+                continue;
+            }
+            order[root] = Some(next_order);
+            low[root] = next_order;
+            next_order += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            visiting.push((root, 0));
+
+            while let Some((area, edge)) = visiting.last_mut() {
+                let area = *area;
+                if let Some(next) = edges[area].get(*edge).copied() {
+                    *edge += 1;
+                    match order[next] {
+                        None => {
+                            order[next] = Some(next_order);
+                            low[next] = next_order;
+                            next_order += 1;
+                            stack.push(next);
+                            on_stack[next] = true;
+                            visiting.push((next, 0));
+                        }
+                        Some(next_order) if on_stack[next] => {
+                            low[area] = low[area].min(next_order);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+
+                visiting.pop();
+                if let Some((parent, _)) = visiting.last() {
+                    low[*parent] = low[*parent].min(low[area]);
+                }
+                if Some(low[area]) == order[area] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(areas[member].id);
+                        if member == area {
+                            break;
+                        }
+                    }
+                    component.sort();
+                    components.push(component);
+                }
+            }
+        }
+
+        components.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].cmp(&b[0])));
+        components
+    }
+}
+
+#[test]
+fn test_connected_components() {
+    use crate::DistanceCost;
+    use std::collections::HashSet;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file).unwrap();
+
+    let components = tree.connected_components();
+    assert_eq!(tree.len(), components.iter().map(Vec::len).sum::<usize>());
+    let unique: HashSet<NavAreaId> = components.iter().flatten().copied().collect();
+    assert_eq!(tree.len(), unique.len());
+    assert!(components
+        .windows(2)
+        .all(|pair| pair[0].len() >= pair[1].len()));
+
+    // all areas of a component can reach each other, and nothing outside of it can be returned from
+    for component in components.iter().step_by(7).take(20) {
+        let reachable = tree.reachable_from(component[0], &DistanceCost);
+        assert!(component.iter().all(|id| reachable.contains(id)));
+        for id in component.iter().step_by(13).take(5) {
+            assert!(tree
+                .reachable_from(*id, &DistanceCost)
+                .contains(&component[0]));
+        }
+        let members: HashSet<NavAreaId> = component.iter().copied().collect();
+        for id in reachable.iter().filter(|id| !members.contains(id)).take(5) {
+            assert!(!tree
+                .reachable_from(*id, &DistanceCost)
+                .contains(&component[0]));
+        }
+    }
+
+    // a two way link joins the components it connects
+    let largest = components[0][0];
+    let other = components[1][0];
+    tree.add_area_link(largest, other, 1.0, true).unwrap();
+    let joined = tree.connected_components();
+    assert_eq!(components[0].len() + components[1].len(), joined[0].len());
+}
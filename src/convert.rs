@@ -0,0 +1,105 @@
+use crate::navmesh::LightIntensity;
+use crate::{NavMesh, WriteError};
+
+impl NavMesh {
+    /// Convert the navigation mesh to a different major version
+    ///
+    /// Data that can't be stored in the target version is removed and data that is new in the
+    /// target version is filled with the defaults the engine uses.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let converted = mesh.convert_to_version(10)?;
+    /// std::fs::write("path/to/converted.nav", converted.to_bytes(10)?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convert_to_version(&self, version: u32) -> Result<NavMesh, WriteError> {
+        if !(6..=16).contains(&version) {
+            return Err(WriteError::UnsupportedVersion(version));
+        }
+
+        let from = self.major_version;
+        let mut mesh = self.clone();
+        mesh.major_version = version;
+
+        if version < 10 {
+            mesh.minor_version = 0;
+        }
+        if version < 12 {
+            mesh.has_unnamed_areas = false;
+        }
+        if version < 14 {
+            mesh.is_analyzed = false;
+        }
+
+        let flag_mask = if version <= 8 {
+            0xFF
+        } else if version <= 12 {
+            0xFFFF
+        } else {
+            0xFFFF_FFFF
+        };
+
+        for area in mesh.areas.iter_mut() {
+            area.flags &= flag_mask;
+
+            if version >= 15 {
+                area.approach_areas.clear();
+            }
+            if version < 7 {
+                area.ladder_connections = Default::default();
+            }
+            if version < 8 {
+                area.earliest_occupy_first_team = 0.0;
+                area.earliest_occupy_second_team = 0.0;
+            }
+            if version < 11 {
+                area.light_intensity = LightIntensity::default();
+            } else if from < 11 {
+                area.light_intensity = LightIntensity {
+                    north_west: 1.0,
+                    north_east: 1.0,
+                    south_west: 1.0,
+                    south_east: 1.0,
+                };
+            }
+            if version < 16 {
+                area.visible_areas.clear();
+                area.inherit_visibility_from_area_id = 0;
+            }
+        }
+
+        if version != 6 {
+            for ladder in mesh.ladders.iter_mut() {
+                ladder.is_dangling = false;
+            }
+        }
+
+        Ok(mesh)
+    }
+}
+
+#[test]
+fn test_convert() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    for version in 6..=16 {
+        let converted = mesh.convert_to_version(version).unwrap();
+        let written = converted.to_bytes(version).unwrap();
+        let parsed = crate::read_mesh(written).unwrap();
+
+        assert_eq!(converted, parsed);
+        assert_eq!(version, parsed.major_version);
+        assert_eq!(mesh.areas.len(), parsed.areas.len());
+    }
+
+    assert!(mesh.convert_to_version(17).is_err());
+}
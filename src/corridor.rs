@@ -0,0 +1,195 @@
+use crate::funnel::funnel;
+use crate::navmesh::{NavAreaId, NavQuad, Vector3};
+use crate::{NavPath, NavTree};
+
+/// The areas of a path with the edges between them, used to keep a moving agent on the path
+///
+/// The corridor keeps track of the position of the agent and the area of the path it is in,
+/// areas behind the agent are no longer part of the corridor.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, Vector3};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let from = Vector3(150.0, -312.0, 64.0);
+/// let to = Vector3(1200.0, 200.0, 128.0);
+/// if let Some(mut corridor) = tree.find_path_corridor(from, to) {
+///     // the agent got pushed around a bit
+///     let position = corridor.advance(Vector3(170.0, -300.0, 64.0));
+///     let next_corner = corridor.points()[1];
+///     println!("walk from {:?} to {:?}", position, next_corner);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathCorridor {
+    areas: Vec<NavAreaId>,
+    quads: Vec<NavQuad>,
+    /// The shared edge between every area and the next area
+    portals: Vec<(Vector3, Vector3)>,
+    position: Vector3,
+    target: Vector3,
+    /// The index of the area the agent is in
+    current: usize,
+}
+
+impl PathCorridor {
+    /// Create a corridor for a path between two points
+    ///
+    /// Returns `None` if any of the areas of the path doesn't exist in the tree.
+    pub fn new(tree: &NavTree, path: &NavPath) -> Option<Self> {
+        let quads = path
+            .areas
+            .iter()
+            .map(|id| Some(tree.area(*id)?.quad.clone()))
+            .collect::<Option<Vec<_>>>()?;
+        let portals = tree.portals(&path.areas);
+        if quads.is_empty() || portals.len() + 1 != quads.len() {
+            return None;
+        }
+
+        Some(PathCorridor {
+            areas: path.areas.clone(),
+            quads,
+            portals,
+            position: *path.points.first()?,
+            target: *path.points.last()?,
+            current: 0,
+        })
+    }
+
+    /// The areas from the area the agent is in to the area of the target
+    pub fn areas(&self) -> &[NavAreaId] {
+        &self.areas[self.current..]
+    }
+
+    /// The shared edges between the remaining areas
+    pub fn portals(&self) -> &[(Vector3, Vector3)] {
+        &self.portals[self.current..]
+    }
+
+    /// The current position of the agent
+    pub fn position(&self) -> Vector3 {
+        self.position
+    }
+
+    /// The end of the path
+    pub fn target(&self) -> Vector3 {
+        self.target
+    }
+
+    /// Whether the agent is in the area of the target
+    pub fn is_finished(&self) -> bool {
+        self.current + 1 == self.areas.len()
+    }
+
+    /// Get the point inside the remaining areas of the corridor closest to a point
+    pub fn clamp(&self, point: Vector3) -> Vector3 {
+        self.closest(point).1
+    }
+
+    /// Move the agent to a new position, dropping the areas it moved past
+    ///
+    /// If the new position is outside of the corridor, the agent is moved to the closest point
+    /// inside the corridor instead. Returns the new position of the agent.
+    pub fn advance(&mut self, point: Vector3) -> Vector3 {
+        let inside = (self.current..self.quads.len())
+            .rev()
+            .find(|index| self.quads[*index].contains(point.0, point.1));
+        let (index, position) = match inside {
+            Some(index) => {
+                let quad = &self.quads[index];
+                (
+                    index,
+                    Vector3(point.0, point.1, quad.interpolated_z(point.0, point.1)),
+                )
+            }
+            None => self.closest(point),
+        };
+        self.current = index;
+        self.position = position;
+        position
+    }
+
+    /// The shortest line from the position of the agent to the target through the corridor
+    pub fn points(&self) -> Vec<Vector3> {
+        let mut portals = Vec::with_capacity(self.portals().len() + 2);
+        portals.push((self.position, self.position));
+        portals.extend_from_slice(self.portals());
+        portals.push((self.target, self.target));
+        funnel(&portals)
+    }
+
+    /// Get the index of the remaining area closest to a point and the closest point in that area
+    fn closest(&self, point: Vector3) -> (usize, Vector3) {
+        (self.current..self.quads.len())
+            .map(|index| (index, self.quads[index].closest_point(point.0, point.1)))
+            .min_by(|a, b| {
+                a.1.distance(point)
+                    .partial_cmp(&b.1.distance(point))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or((self.current, self.position))
+    }
+}
+
+impl NavTree {
+    /// Find a path between two points on the mesh as a corridor
+    ///
+    /// See [`find_path_points`] for how the path is found and [`PathCorridor`] for how to follow
+    /// it.
+    ///
+    /// [`find_path_points`]: #method.find_path_points
+    /// [`PathCorridor`]: ./struct.PathCorridor.html
+    pub fn find_path_corridor(&self, from: Vector3, to: Vector3) -> Option<PathCorridor> {
+        PathCorridor::new(self, &self.find_path_points(from, to)?)
+    }
+}
+
+#[test]
+fn test_path_corridor() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let from = tree.nearest_area(-1000.0, -1030.0).unwrap().0.center();
+    let to = tree.nearest_area(1500.0, 200.0).unwrap().0.center();
+    let path = tree.find_path_points(from, to).unwrap();
+    let mut corridor = tree.find_path_corridor(from, to).unwrap();
+    assert_eq!(path.areas, corridor.areas());
+    assert_eq!(path.points, corridor.points());
+    assert_eq!(corridor.areas().len(), corridor.portals().len() + 1);
+    assert!(!corridor.is_finished());
+
+    // points far away are clamped onto the corridor
+    let far = Vector3(from.0 + 100_000.0, from.1, from.2);
+    let clamped = corridor.clamp(far);
+    assert!(corridor
+        .areas()
+        .iter()
+        .any(|id| tree[*id].quad.distance_2d(clamped.0, clamped.1) < 0.01));
+
+    // moving into a later area drops the areas before it
+    let middle = path.areas.len() / 2;
+    let center = tree[path.areas[middle]].center();
+    let position = corridor.advance(center);
+    assert_eq!(center.0, position.0);
+    assert_eq!(center.1, position.1);
+    assert_eq!(&path.areas[middle..], corridor.areas());
+    assert_eq!(Some(&position), corridor.points().first());
+    assert_eq!(Some(&to), corridor.points().last());
+
+    // areas that were dropped can't be moved back into
+    let position = corridor.advance(from);
+    assert!(corridor
+        .areas()
+        .iter()
+        .any(|id| tree[*id].quad.distance_2d(position.0, position.1) < 0.01));
+
+    corridor.advance(to);
+    assert!(corridor.is_finished());
+}
@@ -0,0 +1,528 @@
+use crate::navmesh::{NavArea, NavAreaId};
+use crate::{
+    CustomAreaData, EdgeKind, NavAttributeFlags, NavGame, OffMeshLink, Team, TfAttributeFlags,
+};
+use std::collections::HashMap;
+
+/// The cost of moving between areas, used by all path searches
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, EdgeKind, NavArea, NavAttributeFlags, NavCost};
+///
+/// struct AvoidCrouch;
+///
+/// impl NavCost for AvoidCrouch {
+///     fn edge_cost(&self, from: &NavArea, to: &NavArea, _kind: EdgeKind) -> Option<f32> {
+///         let distance = from.center().distance(to.center());
+///         if to.attributes().contains(NavAttributeFlags::CROUCH) {
+///             Some(distance * 3.0)
+///         } else {
+///             Some(distance)
+///         }
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let path = tree.find_path_with_cost(1.into(), 250.into(), &AvoidCrouch);
+/// # Ok(())
+/// # }
+/// ```
+pub trait NavCost {
+    /// The cost of moving from an area to a neighboring area, or `None` if the move isn't allowed
+    ///
+    /// Costs must not be negative.
+    fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32>;
+
+    /// The cost of moving from an area to another area through an off-mesh link, or `None` if
+    /// the link can't be used
+    ///
+    /// Defaults to the cost of the link.
+    fn link_cost(&self, link: &OffMeshLink, _from: &NavArea, _to: &NavArea) -> Option<f32> {
+        Some(link.cost)
+    }
+
+    /// An estimate of the cost of moving from an area to the goal area
+    ///
+    /// The estimate guides the search towards the goal and must never be higher than the actual
+    /// cost of the cheapest path for the search to find that path. The default estimate is the
+    /// distance between the centers of the areas, which needs to be overwritten when moving
+    /// between areas can cost less than the distance between them.
+    fn estimate(&self, from: &NavArea, goal: &NavArea) -> f32 {
+        from.center().distance(goal.center())
+    }
+}
+
+impl<C: NavCost + ?Sized> NavCost for &C {
+    fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32> {
+        (**self).edge_cost(from, to, kind)
+    }
+
+    fn link_cost(&self, link: &OffMeshLink, from: &NavArea, to: &NavArea) -> Option<f32> {
+        (**self).link_cost(link, from, to)
+    }
+
+    fn estimate(&self, from: &NavArea, goal: &NavArea) -> f32 {
+        (**self).estimate(from, goal)
+    }
+}
+
+/// The distance between the centers of the areas, allowing every move between areas
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DistanceCost;
+
+impl NavCost for DistanceCost {
+    fn edge_cost(&self, from: &NavArea, to: &NavArea, _kind: EdgeKind) -> Option<f32> {
+        Some(from.center().distance(to.center()))
+    }
+}
+
+/// A cost that follows the attributes of the areas, similar to how the bots of the game move
+///
+/// Moving into an area costs the distance between the centers of the areas, multiplied by the
+/// penalty for every attribute of the area that is entered. Areas blocked by a nav blocker can't
+/// be entered.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, AttributeCost};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let cost = AttributeCost {
+///     avoid: 100.0,
+///     ..AttributeCost::default()
+/// };
+/// let path = tree.find_path_with_cost(1.into(), 250.into(), &cost);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AttributeCost {
+    /// The penalty for areas that have to be crossed while crouching, defaults to `3.0`
+    pub crouch: f32,
+    /// The penalty for areas that require a jump, defaults to `2.0`
+    pub jump: f32,
+    /// The penalty for areas that should be avoided, defaults to `20.0`
+    pub avoid: f32,
+}
+
+impl Default for AttributeCost {
+    fn default() -> Self {
+        AttributeCost {
+            crouch: 3.0,
+            jump: 2.0,
+            avoid: 20.0,
+        }
+    }
+}
+
+impl AttributeCost {
+    /// The combined penalty for entering an area, or `None` if the area is blocked
+    pub fn penalty(&self, area: &NavArea) -> Option<f32> {
+        let attributes = area.attributes();
+        if attributes.contains(NavAttributeFlags::NAV_BLOCKER) {
+            return None;
+        }
+
+        let mut penalty = 1.0;
+        if attributes.contains(NavAttributeFlags::CROUCH) {
+            penalty *= self.crouch;
+        }
+        if attributes.contains(NavAttributeFlags::JUMP) {
+            penalty *= self.jump;
+        }
+        if attributes.contains(NavAttributeFlags::AVOID) {
+            penalty *= self.avoid;
+        }
+        Some(penalty)
+    }
+}
+
+impl NavCost for AttributeCost {
+    fn edge_cost(&self, from: &NavArea, to: &NavArea, _kind: EdgeKind) -> Option<f32> {
+        Some(from.center().distance(to.center()) * self.penalty(to)?)
+    }
+
+    fn link_cost(&self, link: &OffMeshLink, _from: &NavArea, to: &NavArea) -> Option<f32> {
+        self.penalty(to)?;
+        Some(link.cost)
+    }
+
+    fn estimate(&self, from: &NavArea, goal: &NavArea) -> f32 {
+        // penalties below 1 make moves cheaper than the distance
+        let lowest = self.crouch.min(self.jump).min(self.avoid).min(1.0);
+        from.center().distance(goal.center()) * lowest
+    }
+}
+
+/// Extra costs for entering specific areas, added on top of another cost
+///
+/// The extra cost of an area can describe anything that makes an area less attractive, like the
+/// chance of being seen by an enemy or the number of players that died in the area. Overlays can
+/// be stacked to combine multiple sources of extra costs.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, CostOverlay, DistanceCost};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let mut danger = CostOverlay::new(DistanceCost);
+/// // an enemy sniper is watching these areas
+/// for id in &[12, 13, 14] {
+///     danger.add((*id).into(), 500.0);
+/// }
+/// let path = tree.find_path_with_cost(1.into(), 250.into(), &danger);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CostOverlay<C> {
+    cost: C,
+    extra: HashMap<NavAreaId, f32>,
+}
+
+impl<C> CostOverlay<C> {
+    /// Create an overlay without any extra costs on top of a cost
+    pub fn new(cost: C) -> Self {
+        CostOverlay {
+            cost,
+            extra: HashMap::new(),
+        }
+    }
+
+    /// Get the cost the overlay adds to
+    pub fn inner(&self) -> &C {
+        &self.cost
+    }
+
+    /// Get the extra cost for entering an area
+    pub fn get(&self, id: NavAreaId) -> f32 {
+        self.extra.get(&id).copied().unwrap_or_default()
+    }
+
+    /// Set the extra cost for entering an area
+    ///
+    /// Extra costs must not be negative.
+    pub fn set(&mut self, id: NavAreaId, extra: f32) {
+        self.extra.insert(id, extra);
+    }
+
+    /// Add to the extra cost for entering an area
+    pub fn add(&mut self, id: NavAreaId, extra: f32) {
+        *self.extra.entry(id).or_default() += extra;
+    }
+
+    /// Remove the extra cost for entering an area
+    pub fn remove(&mut self, id: NavAreaId) {
+        self.extra.remove(&id);
+    }
+
+    /// Remove all extra costs
+    pub fn clear(&mut self) {
+        self.extra.clear();
+    }
+
+    /// Get all areas with an extra cost
+    pub fn iter(&self) -> impl Iterator<Item = (NavAreaId, f32)> + '_ {
+        self.extra.iter().map(|(id, extra)| (*id, *extra))
+    }
+}
+
+impl<C: NavCost> NavCost for CostOverlay<C> {
+    fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32> {
+        Some(self.cost.edge_cost(from, to, kind)? + self.get(to.id))
+    }
+
+    fn link_cost(&self, link: &OffMeshLink, from: &NavArea, to: &NavArea) -> Option<f32> {
+        Some(self.cost.link_cost(link, from, to)? + self.get(to.id))
+    }
+
+    fn estimate(&self, from: &NavArea, goal: &NavArea) -> f32 {
+        self.cost.estimate(from, goal)
+    }
+}
+
+/// A cost for the areas a team can enter, on top of another cost
+///
+/// For Team Fortress 2, the spawn rooms of the other team can't be entered, and while the setup
+/// time is running neither can the areas behind the setup gates of the team itself. When a round
+/// time is set, areas the team can't have reached at that time according to the earliest occupy
+/// times of the areas can't be entered either.
+///
+/// The engine only saves the persistent TF2 attributes (`TF_NAV_PERSISTENT_ATTRIBUTES`) in the nav
+/// file. The spawn room, setup gate and blocked flags are set by the game at runtime from the
+/// entities of the map, so they are never present in a nav file read from disk. For the cost to
+/// avoid these areas, the flags have to be written into the custom data of the areas first, as
+/// little endian bytes of the [`TfAttributeFlags`]. Set `area.custom_data` on the mesh before
+/// building the tree, or replace the areas in an existing tree with [`NavTree::replace`].
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{
+///     get_area_tree_for_game, CustomAreaData, DistanceCost, NavGame, Team, TeamCost,
+///     TfAttributeFlags,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let mut tree = get_area_tree_for_game(file, NavGame::TeamFortress2)?;
+///
+/// // mark the area behind the gate of the BLU team, as found from the map entities
+/// let handle = tree.handle(12.into()).unwrap();
+/// let mut area = tree.get(handle).unwrap().clone();
+/// let flags = TfAttributeFlags::decode(&area.custom_data, tree.game()).unwrap_or_default()
+///     | TfAttributeFlags::BLUE_SETUP_GATE;
+/// area.custom_data = flags.0.to_le_bytes().to_vec();
+/// tree.replace(handle, area)?;
+///
+/// let blu = TeamCost {
+///     setup: true,
+///     ..TeamCost::new(DistanceCost, Team::Second, tree.game())
+/// };
+/// let path = tree.find_path_with_cost(1.into(), 250.into(), &blu);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`TfAttributeFlags`]: ./struct.TfAttributeFlags.html
+/// [`NavTree::replace`]: ./struct.NavTree.html#method.replace
+#[derive(Debug, Clone)]
+pub struct TeamCost<C> {
+    pub cost: C,
+    pub team: Team,
+    /// The game the areas were parsed for, used to decode the game specific team attributes
+    pub game: NavGame,
+    /// Whether the setup time is running
+    pub setup: bool,
+    /// The number of seconds since the start of the round
+    pub round_time: Option<f32>,
+}
+
+impl<C> TeamCost<C> {
+    /// Create a team cost outside of the setup time, without a round time
+    pub fn new(cost: C, team: Team, game: NavGame) -> Self {
+        TeamCost {
+            cost,
+            team,
+            game,
+            setup: false,
+            round_time: None,
+        }
+    }
+
+    /// Whether the team can enter an area
+    pub fn can_enter(&self, area: &NavArea) -> bool {
+        if let Some(round_time) = self.round_time {
+            let earliest_occupy = match self.team {
+                Team::First => area.earliest_occupy_first_team,
+                Team::Second => area.earliest_occupy_second_team,
+            };
+            if earliest_occupy > round_time {
+                return false;
+            }
+        }
+
+        let flags = match TfAttributeFlags::decode(&area.custom_data, self.game) {
+            Some(flags) => flags,
+            None => return true,
+        };
+        let (enemy_spawn, own_gate) = match self.team {
+            Team::First => (
+                TfAttributeFlags::BLUE_SPAWN_ROOM,
+                TfAttributeFlags::RED_SETUP_GATE,
+            ),
+            Team::Second => (
+                TfAttributeFlags::RED_SPAWN_ROOM,
+                TfAttributeFlags::BLUE_SETUP_GATE,
+            ),
+        };
+        !flags.intersects(TfAttributeFlags::BLOCKED)
+            && !flags.intersects(enemy_spawn)
+            && !(self.setup && flags.intersects(own_gate))
+    }
+}
+
+impl<C: NavCost> NavCost for TeamCost<C> {
+    fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32> {
+        if self.can_enter(to) {
+            self.cost.edge_cost(from, to, kind)
+        } else {
+            None
+        }
+    }
+
+    fn link_cost(&self, link: &OffMeshLink, from: &NavArea, to: &NavArea) -> Option<f32> {
+        if self.can_enter(to) {
+            self.cost.link_cost(link, from, to)
+        } else {
+            None
+        }
+    }
+
+    fn estimate(&self, from: &NavArea, goal: &NavArea) -> f32 {
+        self.cost.estimate(from, goal)
+    }
+}
+
+#[test]
+fn test_attribute_cost() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file).unwrap();
+    let cost = AttributeCost::default();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let path = tree.find_path_with_cost(start, goal, &cost).unwrap();
+
+    let from = &tree[path[0]];
+    let mut to = tree[path[1]].clone();
+    to.flags = 0;
+    let plain = cost.edge_cost(from, &to, EdgeKind::Walk).unwrap();
+    to.flags = (NavAttributeFlags::CROUCH | NavAttributeFlags::AVOID).0;
+    let avoid = cost.edge_cost(from, &to, EdgeKind::Walk).unwrap();
+    assert!((plain * 60.0 - avoid).abs() < 0.01);
+    to.flags = NavAttributeFlags::NAV_BLOCKER.0;
+    assert_eq!(None, cost.edge_cost(from, &to, EdgeKind::Walk));
+
+    // blocking an area in the middle of the path makes the path go around it
+    let blocked = path[path.len() / 2];
+    let mut area = tree[blocked].clone();
+    area.flags |= NavAttributeFlags::NAV_BLOCKER.0;
+    let handle = tree.handle(blocked).unwrap();
+    tree.replace(handle, area).unwrap();
+
+    if let Some(detour) = tree.find_path_with_cost(start, goal, &cost) {
+        assert!(!detour.contains(&blocked));
+    }
+}
+
+#[test]
+fn test_cost_overlay() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let path = tree.find_path(start, goal).unwrap();
+    let dangerous = path[path.len() / 2];
+
+    let mut overlay = CostOverlay::new(DistanceCost);
+    overlay.add(dangerous, 100.0);
+    overlay.add(dangerous, 100.0);
+    assert_eq!(200.0, overlay.get(dangerous));
+    let from = &tree[path[path.len() / 2 - 1]];
+    let to = &tree[dangerous];
+    let plain = DistanceCost.edge_cost(from, to, EdgeKind::Walk).unwrap();
+    let extra = overlay.edge_cost(from, to, EdgeKind::Walk).unwrap();
+    assert!((plain + 200.0 - extra).abs() < 0.01);
+
+    // overlays stack
+    let mut stacked = CostOverlay::new(&overlay);
+    stacked.set(dangerous, 50.0);
+    let stacked_cost = stacked.edge_cost(from, to, EdgeKind::Walk).unwrap();
+    assert!((plain + 250.0 - stacked_cost).abs() < 0.01);
+
+    // a high enough extra cost makes the path avoid the area if there is another way
+    struct Avoid(NavAreaId);
+
+    impl NavCost for Avoid {
+        fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32> {
+            if to.id == self.0 {
+                None
+            } else {
+                DistanceCost.edge_cost(from, to, kind)
+            }
+        }
+    }
+
+    overlay.set(dangerous, 1_000_000.0);
+    let detour = tree.find_path_with_cost(start, goal, &overlay).unwrap();
+    let avoidable = tree
+        .find_path_with_cost(start, goal, &Avoid(dangerous))
+        .is_some();
+    assert_eq!(avoidable, !detour.contains(&dangerous));
+    overlay.clear();
+    assert_eq!(0, overlay.iter().count());
+}
+
+#[test]
+fn test_team_cost() {
+    use crate::navmesh::{NavDirection, NavQuad, Vector3};
+
+    // a row of areas: the red spawn, open ground, the blu setup gate and the blu spawn
+    let mut builder = crate::NavMeshBuilder::new(NavGame::TeamFortress2);
+    let quad = |x: f32| NavQuad {
+        north_west: Vector3(x, 0.0, 0.0),
+        south_east: Vector3(x + 100.0, 100.0, 0.0),
+        north_east_z: 0.0,
+        south_west_z: 0.0,
+    };
+    let ids: Vec<_> = (0..4)
+        .map(|index| builder.add_area(quad(index as f32 * 100.0)).unwrap())
+        .collect();
+    for pair in ids.windows(2) {
+        builder
+            .connect(pair[0], pair[1], NavDirection::East)
+            .unwrap();
+    }
+    let mut mesh = builder.build();
+    let flags = [
+        TfAttributeFlags::RED_SPAWN_ROOM,
+        TfAttributeFlags::NONE,
+        TfAttributeFlags::BLUE_SETUP_GATE,
+        TfAttributeFlags::BLUE_SPAWN_ROOM,
+    ];
+    for (area, flags) in mesh.areas.iter_mut().zip(flags.iter()) {
+        area.custom_data = flags.0.to_le_bytes().to_vec();
+    }
+    mesh.areas[0].earliest_occupy_first_team = -1.0;
+    mesh.areas[1].earliest_occupy_first_team = 30.0;
+    mesh.areas[2].earliest_occupy_first_team = f32::MAX;
+    let tree =
+        crate::get_area_tree_for_game(mesh.to_bytes(16).unwrap(), NavGame::TeamFortress2).unwrap();
+    let area = |index: usize| tree.area(ids[index]).unwrap();
+
+    let red = TeamCost::new(DistanceCost, Team::First, tree.game());
+    let blu = TeamCost::new(DistanceCost, Team::Second, tree.game());
+    let blu_setup = TeamCost {
+        setup: true,
+        ..blu.clone()
+    };
+
+    assert!(red.can_enter(area(0)));
+    assert!(red.can_enter(area(1)));
+    assert!(red.can_enter(area(2)));
+    assert!(!red.can_enter(area(3)));
+
+    assert!(!blu.can_enter(area(0)));
+    assert!(blu.can_enter(area(1)));
+    assert!(blu.can_enter(area(2)));
+    assert!(blu.can_enter(area(3)));
+    assert!(!blu_setup.can_enter(area(2)));
+
+    // the red spawn can't be reached by blu
+    let reachable = tree.reachable_from(ids[3], &blu);
+    assert!(reachable.contains(&ids[1]));
+    assert!(!reachable.contains(&ids[0]));
+
+    // areas can only be entered once the earliest occupy time has passed
+    let at = |round_time: f32| TeamCost {
+        round_time: Some(round_time),
+        ..TeamCost::new(DistanceCost, Team::First, NavGame::Unknown)
+    };
+    assert!(at(0.0).can_enter(area(0)));
+    assert!(!at(10.0).can_enter(area(1)));
+    assert!(at(40.0).can_enter(area(1)));
+    assert!(!at(1.0e9).can_enter(area(2)));
+    assert!(at(0.0).can_enter(area(3)));
+}
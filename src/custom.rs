@@ -0,0 +1,178 @@
+use std::ops::BitOr;
+
+/// The game a nav file was made for
+///
+/// Nav files don't store which game they belong to, so this has to be provided when parsing
+/// a nav file with game specific data.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NavGame {
+    /// The game is unknown, no game specific data is stored with the areas
+    #[default]
+    Unknown,
+    TeamFortress2,
+    CounterStrike,
+    LeftForDead2,
+    /// A game or mod not known to this parser with a fixed amount of custom data per area
+    Custom {
+        area_data_size: usize,
+    },
+}
+
+impl NavGame {
+    /// The number of bytes of game specific data stored after every area
+    ///
+    /// Returns `None` for games where the layout of the data hasn't been verified, use
+    /// [`NavGame::Custom`] to read the nav files of these games.
+    ///
+    /// [`NavGame::Custom`]: #variant.Custom
+    pub fn area_data_size(self) -> Option<usize> {
+        match self {
+            NavGame::Unknown => Some(0),
+            NavGame::TeamFortress2 => Some(4),
+            NavGame::Custom { area_data_size } => Some(area_data_size),
+            NavGame::CounterStrike | NavGame::LeftForDead2 => None,
+        }
+    }
+}
+
+/// Game specific data stored with every navigation area
+///
+/// Implement this to decode the custom data of a game or mod not supported by this crate.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{read_mesh_for_game, CustomAreaData, NavGame};
+///
+/// // a mod that stores a single u32 with every area
+/// const MY_MOD: NavGame = NavGame::Custom { area_data_size: 4 };
+///
+/// struct SpawnAttributes(u32);
+///
+/// impl CustomAreaData for SpawnAttributes {
+///     fn decode(data: &[u8], game: NavGame) -> Option<Self> {
+///         match (game, data) {
+///             (MY_MOD, [a, b, c, d]) => {
+///                 Some(SpawnAttributes(u32::from_le_bytes([*a, *b, *c, *d])))
+///             }
+///             _ => None,
+///         }
+///     }
+/// }
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let mesh = read_mesh_for_game(file, MY_MOD)?;
+/// for area in &mesh.areas {
+///     let attributes: Option<SpawnAttributes> = mesh.custom_area_data(area);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub trait CustomAreaData: Sized {
+    /// Decode the raw custom data bytes of an area
+    fn decode(data: &[u8], game: NavGame) -> Option<Self>;
+}
+
+impl CustomAreaData for u32 {
+    fn decode(data: &[u8], _game: NavGame) -> Option<Self> {
+        match data {
+            [a, b, c, d] => Some(u32::from_le_bytes([*a, *b, *c, *d])),
+            _ => None,
+        }
+    }
+}
+
+/// The Team Fortress 2 specific attributes of an area
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{read_mesh_for_game, NavGame, TfAttributeFlags};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let mesh = read_mesh_for_game(file, NavGame::TeamFortress2)?;
+/// let red_spawn = mesh
+///     .areas
+///     .iter()
+///     .filter_map(|area| mesh.custom_area_data::<TfAttributeFlags>(area))
+///     .filter(|flags| flags.contains(TfAttributeFlags::RED_SPAWN_ROOM))
+///     .count();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct TfAttributeFlags(pub u32);
+
+impl TfAttributeFlags {
+    pub const NONE: TfAttributeFlags = TfAttributeFlags(0);
+    /// The area is blocked for both teams
+    ///
+    /// Set by the game when the map is loaded, this flag isn't stored in nav files
+    pub const BLOCKED: TfAttributeFlags = TfAttributeFlags(0x1);
+    /// The area is part of the spawn room of the RED team
+    ///
+    /// Set by the game when the map is loaded, this flag isn't stored in nav files
+    pub const RED_SPAWN_ROOM: TfAttributeFlags = TfAttributeFlags(0x2);
+    /// The area is part of the spawn room of the BLU team
+    ///
+    /// Set by the game when the map is loaded, this flag isn't stored in nav files
+    pub const BLUE_SPAWN_ROOM: TfAttributeFlags = TfAttributeFlags(0x4);
+    /// The area is an exit of a spawn room
+    pub const SPAWN_ROOM_EXIT: TfAttributeFlags = TfAttributeFlags(0x8);
+    /// The area has ammo
+    pub const AMMO: TfAttributeFlags = TfAttributeFlags(0x10);
+    /// The area has health
+    pub const HEALTH: TfAttributeFlags = TfAttributeFlags(0x20);
+    /// The area is part of a control point
+    pub const CONTROL_POINT: TfAttributeFlags = TfAttributeFlags(0x40);
+    /// The area is in range of a BLU sentry
+    pub const BLUE_SENTRY: TfAttributeFlags = TfAttributeFlags(0x80);
+    /// The area is in range of a RED sentry
+    pub const RED_SENTRY: TfAttributeFlags = TfAttributeFlags(0x100);
+    /// The area is behind the setup gate of the BLU team
+    ///
+    /// Set by the game when the map is loaded, this flag isn't stored in nav files
+    pub const BLUE_SETUP_GATE: TfAttributeFlags = TfAttributeFlags(0x800);
+    /// The area is behind the setup gate of the RED team
+    ///
+    /// Set by the game when the map is loaded, this flag isn't stored in nav files
+    pub const RED_SETUP_GATE: TfAttributeFlags = TfAttributeFlags(0x1000);
+    /// The area is blocked after the control point is captured
+    pub const BLOCKED_AFTER_POINT_CAPTURE: TfAttributeFlags = TfAttributeFlags(0x2000);
+    /// The area is blocked until the control point is captured
+    pub const BLOCKED_UNTIL_POINT_CAPTURE: TfAttributeFlags = TfAttributeFlags(0x4000);
+    /// The area has a door only the BLU team can pass
+    pub const BLUE_ONE_WAY_DOOR: TfAttributeFlags = TfAttributeFlags(0x8000);
+    /// The area has a door only the RED team can pass
+    pub const RED_ONE_WAY_DOOR: TfAttributeFlags = TfAttributeFlags(0x10000);
+
+    /// Whether all flags of `other` are set
+    pub fn contains(self, other: TfAttributeFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any flag of `other` is set
+    pub fn intersects(self, other: TfAttributeFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl BitOr for TfAttributeFlags {
+    type Output = TfAttributeFlags;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        TfAttributeFlags(self.0 | rhs.0)
+    }
+}
+
+impl CustomAreaData for TfAttributeFlags {
+    fn decode(data: &[u8], game: NavGame) -> Option<Self> {
+        match game {
+            NavGame::TeamFortress2 => u32::decode(data, game).map(TfAttributeFlags),
+            _ => None,
+        }
+    }
+}
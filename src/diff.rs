@@ -0,0 +1,182 @@
+use crate::navmesh::{Connections, NavAreaId, NavDirection, NavQuad};
+use crate::NavMesh;
+use std::collections::HashMap;
+
+/// The differences between two versions of a nav file, see [`nav_diff`]
+///
+/// [`nav_diff`]: ./fn.nav_diff.html
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NavDiff {
+    /// Areas that only exist in the new nav
+    pub added: Vec<NavAreaId>,
+    /// Areas that only exist in the old nav
+    pub removed: Vec<NavAreaId>,
+    /// Areas that exist in both navs but are changed
+    pub modified: Vec<AreaDiff>,
+}
+
+/// The changes to a single area
+///
+/// Changed values are stored as `(old, new)` pairs
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AreaDiff {
+    pub id: NavAreaId,
+    pub quad: Option<(NavQuad, NavQuad)>,
+    pub flags: Option<(u32, u32)>,
+    /// The place names of the area
+    pub place: Option<(Option<String>, Option<String>)>,
+    pub added_connections: Vec<(NavDirection, NavAreaId)>,
+    pub removed_connections: Vec<(NavDirection, NavAreaId)>,
+}
+
+impl NavDiff {
+    /// Whether both navs have the same areas
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+fn connection_list(connections: &Connections) -> Vec<(NavDirection, NavAreaId)> {
+    NavDirection::ALL
+        .iter()
+        .flat_map(|direction| {
+            connections[*direction]
+                .iter()
+                .map(move |target| (*direction, *target))
+        })
+        .collect()
+}
+
+/// Compare the areas of two navigation meshes
+///
+/// Areas are matched by their id, the geometry, flags, place and connections of the areas that
+/// exist in both meshes are compared. All lists in the result are sorted by area id.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{nav_diff, read_mesh};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let old = read_mesh(std::fs::read("path/to/old.nav")?)?;
+/// let new = read_mesh(std::fs::read("path/to/new.nav")?)?;
+///
+/// let diff = nav_diff(&old, &new);
+/// for area in &diff.modified {
+///     if let Some((old_flags, new_flags)) = area.flags {
+///         println!("flags of {} changed from {} to {}", area.id, old_flags, new_flags);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn nav_diff(old: &NavMesh, new: &NavMesh) -> NavDiff {
+    let old_areas: HashMap<_, _> = old.areas.iter().map(|area| (area.id, area)).collect();
+    let new_areas: HashMap<_, _> = new.areas.iter().map(|area| (area.id, area)).collect();
+    let place_name = |mesh: &NavMesh, place| mesh.place(place).map(|place| place.name.clone());
+
+    let mut diff = NavDiff::default();
+    for id in old_areas.keys() {
+        if !new_areas.contains_key(id) {
+            diff.removed.push(*id);
+        }
+    }
+
+    for (id, area) in &new_areas {
+        let old_area = match old_areas.get(id) {
+            Some(old_area) => old_area,
+            None => {
+                diff.added.push(*id);
+                continue;
+            }
+        };
+
+        let old_connections = connection_list(&old_area.connections);
+        let new_connections = connection_list(&area.connections);
+        let (old_place, new_place) = (place_name(old, old_area.place), place_name(new, area.place));
+
+        let area_diff = AreaDiff {
+            id: *id,
+            quad: if old_area.quad != area.quad {
+                Some((old_area.quad.clone(), area.quad.clone()))
+            } else {
+                None
+            },
+            flags: if old_area.flags != area.flags {
+                Some((old_area.flags, area.flags))
+            } else {
+                None
+            },
+            place: if old_place != new_place {
+                Some((old_place, new_place))
+            } else {
+                None
+            },
+            added_connections: new_connections
+                .iter()
+                .filter(|connection| !old_connections.contains(connection))
+                .copied()
+                .collect(),
+            removed_connections: old_connections
+                .iter()
+                .filter(|connection| !new_connections.contains(connection))
+                .copied()
+                .collect(),
+        };
+
+        if area_diff.quad.is_some()
+            || area_diff.flags.is_some()
+            || area_diff.place.is_some()
+            || !area_diff.added_connections.is_empty()
+            || !area_diff.removed_connections.is_empty()
+        {
+            diff.modified.push(area_diff);
+        }
+    }
+
+    diff.added.sort();
+    diff.removed.sort();
+    diff.modified.sort_by_key(|area| area.id);
+    diff
+}
+
+#[test]
+fn test_diff() {
+    use crate::NavMeshBuilder;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let old = crate::read_mesh(file).unwrap();
+    assert!(nav_diff(&old, &old).is_empty());
+
+    let first = old.areas[0].id;
+    let target = old.areas[0].connections.iter().next().copied().unwrap();
+    let removed = old.areas[5].id;
+
+    let mut builder = NavMeshBuilder::from_mesh(old.clone());
+    builder.set_flags(first, 0x2).unwrap();
+    builder.disconnect_one_way(first, target).unwrap();
+    builder.remove_area(removed).unwrap();
+    let new = builder.build();
+
+    let diff = nav_diff(&old, &new);
+    assert_eq!(vec![removed], diff.removed);
+    assert!(diff.added.is_empty());
+
+    let first_diff = diff.modified.iter().find(|area| area.id == first).unwrap();
+    assert_eq!(Some((old.areas[0].flags, 0x2)), first_diff.flags);
+    assert_eq!(1, first_diff.removed_connections.len());
+    assert_eq!(target, first_diff.removed_connections[0].1);
+    assert!(first_diff.quad.is_none());
+
+    // the areas connected to the removed area lost their connection
+    assert!(diff
+        .modified
+        .iter()
+        .filter(|area| area.id != first)
+        .all(|area| area
+            .removed_connections
+            .iter()
+            .all(|(_, to)| *to == removed)));
+}
@@ -0,0 +1,122 @@
+use crate::navmesh::NavAreaId;
+use crate::NavMesh;
+use std::collections::HashMap;
+
+/// The connection graph of a mesh in compressed sparse row form, see [`NavMesh::to_csr`]
+///
+/// The connections of the area at index `i` are stored in `targets[offsets[i]..offsets[i + 1]]`
+/// with the matching weights in `weights`.
+///
+/// [`NavMesh::to_csr`]: ./struct.NavMesh.html#method.to_csr
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NavCsr {
+    /// The id of the area at every index
+    pub ids: Vec<NavAreaId>,
+    /// The index of every area id
+    pub index: HashMap<NavAreaId, u32>,
+    /// The start of the connections of every area in `targets`, with one extra offset at the end
+    pub offsets: Vec<u32>,
+    /// The indexes of the connected areas
+    pub targets: Vec<u32>,
+    /// The distance between the centers of the connected areas
+    pub weights: Vec<f32>,
+}
+
+impl NavCsr {
+    /// Get the target indexes and weights of the connections of an area
+    pub fn connections(&self, index: u32) -> impl Iterator<Item = (u32, f32)> + '_ {
+        let range = match (
+            self.offsets.get(index as usize),
+            self.offsets.get(index as usize + 1),
+        ) {
+            (Some(start), Some(end)) => *start as usize..*end as usize,
+            _ => 0..0,
+        };
+        self.targets[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.weights[range].iter().copied())
+    }
+}
+
+impl NavMesh {
+    /// Get the connection graph of the areas as contiguous arrays
+    ///
+    /// Areas are indexed in the order they are stored in the mesh, connections to unknown areas
+    /// are skipped.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let csr = mesh.to_csr();
+    /// for (target, distance) in csr.connections(0) {
+    ///     println!("{} is {} units away", csr.ids[target as usize], distance);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_csr(&self) -> NavCsr {
+        let ids: Vec<NavAreaId> = self.areas.iter().map(|area| area.id).collect();
+        let index: HashMap<NavAreaId, u32> = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index as u32))
+            .collect();
+        let centers: Vec<_> = self.areas.iter().map(|area| area.quad.center()).collect();
+
+        let mut offsets = Vec::with_capacity(ids.len() + 1);
+        let mut targets = Vec::new();
+        let mut weights = Vec::new();
+        offsets.push(0);
+
+        for (area_index, area) in self.areas.iter().enumerate() {
+            let from = centers[area_index];
+            for target in area.connections.iter() {
+                if let Some(target_index) = index.get(target) {
+                    let to = centers[*target_index as usize];
+                    targets.push(*target_index);
+                    weights.push(
+                        ((to.0 - from.0).powi(2)
+                            + (to.1 - from.1).powi(2)
+                            + (to.2 - from.2).powi(2))
+                        .sqrt(),
+                    );
+                }
+            }
+            offsets.push(targets.len() as u32);
+        }
+
+        NavCsr {
+            ids,
+            index,
+            offsets,
+            targets,
+            weights,
+        }
+    }
+}
+
+#[test]
+fn test_csr() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let csr = mesh.to_csr();
+    assert_eq!(1931, csr.offsets.len());
+    assert_eq!(csr.targets.len(), csr.weights.len());
+    assert_eq!(csr.targets.len() as u32, *csr.offsets.last().unwrap());
+
+    let first = &mesh.areas[0];
+    let targets: Vec<_> = csr
+        .connections(csr.index[&first.id])
+        .map(|(target, _)| csr.ids[target as usize])
+        .collect();
+    let expected: Vec<_> = first.connections.iter().copied().collect();
+    assert_eq!(expected, targets);
+    assert!(csr.weights.iter().all(|weight| *weight > 0.0));
+}
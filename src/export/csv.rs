@@ -0,0 +1,94 @@
+use crate::NavMesh;
+use std::io::{self, Write};
+
+const HEADER: &str =
+    "id,min_x,min_y,max_x,max_y,north_west_z,north_east_z,south_east_z,south_west_z,\
+flags,place,connections,hiding_spots,encounter_paths,ladders,visible_areas";
+
+/// Quote a field if it contains characters with special meaning
+fn field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+impl NavMesh {
+    /// Write a CSV table with one row per area
+    ///
+    /// The table has the following columns:
+    ///
+    /// `id`, `min_x`, `min_y`, `max_x`, `max_y`, `north_west_z`, `north_east_z`, `south_east_z`,
+    /// `south_west_z`, `flags`, `place`, `connections`, `hiding_spots`, `encounter_paths`,
+    /// `ladders` and `visible_areas`
+    ///
+    /// Where `place` is the name of the place, or empty for areas without place. The last five
+    /// columns contain the number of items of each kind.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// mesh.to_csv(File::create("path/to/areas.csv")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_csv(&self, mut out: impl Write) -> io::Result<()> {
+        writeln!(out, "{}", HEADER)?;
+
+        for area in &self.areas {
+            let quad = &area.quad;
+            let place = self
+                .area_place(area)
+                .map(|place| field(&place.name))
+                .unwrap_or_default();
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+                area.id,
+                quad.north_west.0,
+                quad.north_west.1,
+                quad.south_east.0,
+                quad.south_east.1,
+                quad.north_west.2,
+                quad.north_east_z,
+                quad.south_east.2,
+                quad.south_west_z,
+                area.flags,
+                place,
+                area.connections.iter().count(),
+                area.hiding_spots.len(),
+                area.encounter_paths.len(),
+                area.ladder_connections.iter().count(),
+                area.visible_areas.len(),
+            )?;
+        }
+
+        out.flush()
+    }
+}
+
+#[test]
+fn test_csv() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let mut out = Vec::new();
+    mesh.to_csv(&mut out).unwrap();
+    let csv = String::from_utf8(out).unwrap();
+
+    let lines: Vec<_> = csv.lines().collect();
+    assert_eq!(1931, lines.len());
+    assert_eq!(HEADER, lines[0]);
+    assert!(lines.iter().all(|line| line.split(',').count() == 16));
+    assert!(lines[1].starts_with(&format!("{},", mesh.areas[0].id)));
+
+    assert_eq!("Bridge", field("Bridge"));
+    assert_eq!("\"Upper, \"\"A\"\"\"", field("Upper, \"A\""));
+}
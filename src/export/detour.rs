@@ -0,0 +1,370 @@
+use super::y_up;
+use crate::navmesh::{NavArea, NavAreaId, NavDirection, NavLadderId};
+use crate::{NavMesh, WriteError};
+use std::collections::HashMap;
+
+const DT_NAVMESH_MAGIC: u32 =
+    (b'D' as u32) << 24 | (b'N' as u32) << 16 | (b'A' as u32) << 8 | b'V' as u32;
+const DT_NAVMESH_VERSION: u32 = 7;
+const DT_VERTS_PER_POLYGON: usize = 6;
+const DT_POLYTYPE_OFFMESH_CONNECTION: u8 = 1;
+const DT_OFFMESH_CON_BIDIR: u8 = 1;
+const DT_DETAIL_EDGE_BOUNDARY: u8 = 1;
+const DT_EXT_LINK: u16 = 0x8000;
+
+/// A navigation mesh converted into a single Detour tile
+///
+/// All positions are in hammer units using the y-up coordinate system of Recast, a hammer
+/// position `(x, y, z)` becomes `(x, z, -y)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetourTile {
+    pub vertices: Vec<[f32; 3]>,
+    pub polys: Vec<DetourPoly>,
+    pub off_mesh_connections: Vec<DetourOffMeshConnection>,
+    /// The height of the agent, written into the tile header
+    pub walkable_height: f32,
+    /// The radius of the agent, written into the tile header
+    pub walkable_radius: f32,
+    /// The maximum step height of the agent, written into the tile header
+    pub walkable_climb: f32,
+}
+
+/// The polygon for a single navigation area
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetourPoly {
+    pub area: NavAreaId,
+    /// The indices of the corners into the tile vertices, in the order north west, north east,
+    /// south east and south west
+    pub vertices: [u16; 4],
+    /// The index of the polygon sharing an edge plus one, or `0` for edges without neighbour
+    ///
+    /// The edges are in the order north, east, south, west
+    pub neighbours: [u16; 4],
+}
+
+/// A connection between areas that doesn't follow from the polygon edges
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetourOffMeshConnection {
+    pub from: NavAreaId,
+    pub to: NavAreaId,
+    /// The ladder the connection goes trough, if any
+    pub ladder: Option<NavLadderId>,
+    pub start: [f32; 3],
+    pub end: [f32; 3],
+    pub radius: f32,
+    pub bidirectional: bool,
+}
+
+/// Check if the target area covers the full side of the area in a direction
+fn covers_side(area: &NavArea, target: &NavArea, direction: NavDirection) -> bool {
+    const EPSILON: f32 = 1.0;
+    let (from, to) = (&area.quad, &target.quad);
+    match direction {
+        NavDirection::North | NavDirection::South => {
+            to.north_west.0 <= from.north_west.0 + EPSILON
+                && to.south_east.0 >= from.south_east.0 - EPSILON
+        }
+        NavDirection::East | NavDirection::West => {
+            to.north_west.1 <= from.north_west.1 + EPSILON
+                && to.south_east.1 >= from.south_east.1 - EPSILON
+        }
+    }
+}
+
+impl NavMesh {
+    /// Convert the navigation mesh into a Detour tile
+    ///
+    /// Every area becomes a polygon, an area side connected to a single area that covers the
+    /// full side becomes a shared polygon edge. Other connections and ladders become off-mesh
+    /// connections between the area centers.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let tile = mesh.to_detour_tile();
+    /// std::fs::write("path/to/navfile.tile", tile.to_bytes()?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_detour_tile(&self) -> DetourTile {
+        let index: HashMap<NavAreaId, usize> = self
+            .areas
+            .iter()
+            .enumerate()
+            .map(|(index, area)| (area.id, index))
+            .collect();
+
+        let mut vertices = Vec::with_capacity(self.areas.len() * 4);
+        let mut polys = Vec::with_capacity(self.areas.len());
+        let mut off_mesh_connections = Vec::new();
+
+        for area in &self.areas {
+            let first = vertices.len() as u16;
+            vertices.extend(area.quad.corners().iter().map(|corner| y_up(*corner)));
+
+            let mut neighbours = [0; 4];
+            for (side, direction) in NavDirection::ALL.iter().enumerate() {
+                let targets = &area.connections[*direction];
+                for target in targets {
+                    let target_index = match index.get(target) {
+                        Some(target_index) => *target_index,
+                        None => continue,
+                    };
+                    let target_area = &self.areas[target_index];
+                    if targets.len() == 1 && covers_side(area, target_area, *direction) {
+                        neighbours[side] = target_index as u16 + 1;
+                    } else {
+                        off_mesh_connections.push(DetourOffMeshConnection {
+                            from: area.id,
+                            to: *target,
+                            ladder: None,
+                            start: y_up(area.quad.center()),
+                            end: y_up(target_area.quad.center()),
+                            radius: 1.0,
+                            bidirectional: false,
+                        });
+                    }
+                }
+            }
+
+            polys.push(DetourPoly {
+                area: area.id,
+                vertices: [first, first + 1, first + 2, first + 3],
+                neighbours,
+            });
+        }
+
+        for ladder in &self.ladders {
+            let bottom = match ladder.bottom_area.and_then(|id| index.get(&id)) {
+                Some(bottom) => &self.areas[*bottom],
+                None => continue,
+            };
+            let tops = [
+                ladder.top_forward_area,
+                ladder.top_left_area,
+                ladder.top_right_area,
+                ladder.top_behind_area,
+            ];
+            for top in tops.iter().flatten().filter_map(|id| index.get(id)) {
+                let top = &self.areas[*top];
+                off_mesh_connections.push(DetourOffMeshConnection {
+                    from: bottom.id,
+                    to: top.id,
+                    ladder: Some(ladder.id),
+                    start: y_up(bottom.quad.center()),
+                    end: y_up(top.quad.center()),
+                    radius: 1.0,
+                    bidirectional: true,
+                });
+            }
+        }
+
+        DetourTile {
+            vertices,
+            polys,
+            off_mesh_connections,
+            walkable_height: 72.0,
+            walkable_radius: 24.0,
+            walkable_climb: 18.0,
+        }
+    }
+}
+
+impl DetourTile {
+    /// Serialize the tile in the binary format expected by `dtNavMesh::addTile`
+    ///
+    /// The data is written for a Detour build using 32 bit polygon references. All polygons get
+    /// the polygon flags `1` and area `0`. No bounding volume tree is written, Detour falls back to
+    /// checking all polygons of the tile.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, WriteError> {
+        let ground_count = self.polys.len();
+        let off_mesh_count = self.off_mesh_connections.len();
+        let poly_count = ground_count + off_mesh_count;
+        let vertex_count = self.vertices.len() + off_mesh_count * 2;
+        if poly_count >= DT_EXT_LINK as usize {
+            return Err(WriteError::TooMany {
+                what: "polygons",
+                count: poly_count,
+            });
+        }
+        if vertex_count > u16::MAX as usize {
+            return Err(WriteError::TooMany {
+                what: "vertices",
+                count: vertex_count,
+            });
+        }
+
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        let off_mesh_vertices = self
+            .off_mesh_connections
+            .iter()
+            .flat_map(|connection| vec![connection.start, connection.end]);
+        let all_vertices: Vec<[f32; 3]> = self
+            .vertices
+            .iter()
+            .copied()
+            .chain(off_mesh_vertices)
+            .collect();
+        for vertex in &all_vertices {
+            for axis in 0..3 {
+                min[axis] = min[axis].min(vertex[axis]);
+                max[axis] = max[axis].max(vertex[axis]);
+            }
+        }
+        if all_vertices.is_empty() {
+            min = [0.0; 3];
+            max = [0.0; 3];
+        }
+
+        // every edge and both ends of the off-mesh connections can require a link
+        let max_link_count = ground_count * 4 + off_mesh_count * 4;
+
+        let mut out = Vec::new();
+        let int = |out: &mut Vec<u8>, value: u32| out.extend_from_slice(&value.to_le_bytes());
+        let float = |out: &mut Vec<u8>, value: f32| out.extend_from_slice(&value.to_le_bytes());
+
+        // dtMeshHeader
+        int(&mut out, DT_NAVMESH_MAGIC);
+        int(&mut out, DT_NAVMESH_VERSION);
+        int(&mut out, 0); // x
+        int(&mut out, 0); // y
+        int(&mut out, 0); // layer
+        int(&mut out, 0); // user id
+        int(&mut out, poly_count as u32);
+        int(&mut out, vertex_count as u32);
+        int(&mut out, max_link_count as u32);
+        int(&mut out, ground_count as u32); // detail meshes
+        int(&mut out, 0); // detail vertices
+        int(&mut out, ground_count as u32 * 2); // detail triangles
+        int(&mut out, 0); // bv tree nodes
+        int(&mut out, off_mesh_count as u32);
+        int(&mut out, ground_count as u32); // off-mesh base
+        float(&mut out, self.walkable_height);
+        float(&mut out, self.walkable_radius);
+        float(&mut out, self.walkable_climb);
+        for value in min.iter().chain(max.iter()) {
+            float(&mut out, *value);
+        }
+        float(&mut out, 1.0); // bv quantization factor
+
+        for vertex in &all_vertices {
+            for value in vertex {
+                float(&mut out, *value);
+            }
+        }
+
+        // dtPoly
+        let write_poly = |out: &mut Vec<u8>,
+                          vertices: &[u16],
+                          neighbours: &[u16],
+                          area_and_type: u8| {
+            int(out, 0); // first link, filled in by detour
+            for index in 0..DT_VERTS_PER_POLYGON {
+                out.extend_from_slice(&vertices.get(index).copied().unwrap_or(0).to_le_bytes());
+            }
+            for index in 0..DT_VERTS_PER_POLYGON {
+                out.extend_from_slice(&neighbours.get(index).copied().unwrap_or(0).to_le_bytes());
+            }
+            out.extend_from_slice(&1u16.to_le_bytes()); // flags
+            out.push(vertices.len() as u8);
+            out.push(area_and_type);
+        };
+        for poly in &self.polys {
+            write_poly(&mut out, &poly.vertices, &poly.neighbours, 0);
+        }
+        for index in 0..off_mesh_count {
+            let first = (self.vertices.len() + index * 2) as u16;
+            write_poly(
+                &mut out,
+                &[first, first + 1],
+                &[],
+                DT_POLYTYPE_OFFMESH_CONNECTION << 6,
+            );
+        }
+
+        // dtLink, filled in by detour
+        out.resize(out.len() + max_link_count * 12, 0);
+
+        // dtPolyDetail, using the polygon vertices split into two triangles
+        for index in 0..ground_count {
+            int(&mut out, 0); // vertex base
+            int(&mut out, index as u32 * 2); // triangle base
+            out.extend_from_slice(&[0, 2, 0, 0]); // vertex count, triangle count, padding
+        }
+
+        // detail triangles with the flags marking the edges that are on the polygon boundary
+        for _ in 0..ground_count {
+            let boundary = DT_DETAIL_EDGE_BOUNDARY;
+            out.extend_from_slice(&[0, 1, 2, boundary | boundary << 2]);
+            out.extend_from_slice(&[0, 2, 3, boundary << 2 | boundary << 4]);
+        }
+
+        // dtOffMeshConnection
+        for (index, connection) in self.off_mesh_connections.iter().enumerate() {
+            for value in connection.start.iter().chain(connection.end.iter()) {
+                float(&mut out, *value);
+            }
+            float(&mut out, connection.radius);
+            out.extend_from_slice(&((ground_count + index) as u16).to_le_bytes());
+            out.push(if connection.bidirectional {
+                DT_OFFMESH_CON_BIDIR
+            } else {
+                0
+            });
+            out.push(0xff); // side, not on a tile border
+            let user_id = match connection.ladder {
+                Some(ladder) => ladder.into(),
+                None => connection.from.into(),
+            };
+            int(&mut out, user_id);
+        }
+
+        Ok(out)
+    }
+}
+
+#[test]
+fn test_detour_tile() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let tile = mesh.to_detour_tile();
+    assert_eq!(1930, tile.polys.len());
+
+    let connection_count = mesh
+        .areas
+        .iter()
+        .map(|area| area.connections.iter().count())
+        .sum::<usize>();
+    let edge_count = tile
+        .polys
+        .iter()
+        .flat_map(|poly| poly.neighbours.iter())
+        .filter(|neighbour| **neighbour != 0)
+        .count();
+    assert!(edge_count > 0);
+    assert_eq!(
+        connection_count,
+        edge_count + tile.off_mesh_connections.len()
+    );
+
+    let data = tile.to_bytes().unwrap();
+    assert_eq!(&DT_NAVMESH_MAGIC.to_le_bytes(), &data[0..4]);
+
+    let polys = 1930 + tile.off_mesh_connections.len();
+    let vertices = 1930 * 4 + tile.off_mesh_connections.len() * 2;
+    let expected_length = 100
+        + vertices * 12
+        + polys * 32
+        + polys * 4 * 12
+        + 1930 * 12
+        + 1930 * 2 * 4
+        + tile.off_mesh_connections.len() * 36;
+    assert_eq!(expected_length, data.len());
+}
@@ -0,0 +1,141 @@
+use crate::navmesh::NavDirection;
+use crate::NavMesh;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+
+/// Options for [`NavMesh::export_dot_with_options`]
+///
+/// [`NavMesh::export_dot_with_options`]: ./struct.NavMesh.html#method.export_dot_with_options
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DotOptions {
+    /// Collapse all areas in a place into a single node
+    ///
+    /// Areas without a place are collapsed into a node labeled `unnamed`
+    pub collapse_places: bool,
+}
+
+fn quote(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+impl NavMesh {
+    /// Export the connections between the areas as a Graphviz DOT graph
+    ///
+    /// Every area is a node labeled with it's id and place, every connection is an edge labeled
+    /// with the direction of the connection.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// mesh.export_dot(File::create("path/to/navfile.dot")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_dot(&self, out: impl Write) -> io::Result<()> {
+        self.export_dot_with_options(out, DotOptions::default())
+    }
+
+    /// Export the connections between the areas or places as a Graphviz DOT graph
+    pub fn export_dot_with_options(
+        &self,
+        mut out: impl Write,
+        options: DotOptions,
+    ) -> io::Result<()> {
+        writeln!(out, "digraph navmesh {{")?;
+
+        if options.collapse_places {
+            let place_name = |place: u16| {
+                self.place(place)
+                    .map(|place| place.name.as_str())
+                    .unwrap_or("unnamed")
+            };
+            let view = self.view();
+
+            let nodes: BTreeSet<u16> = self.areas.iter().map(|area| area.place).collect();
+            for place in &nodes {
+                writeln!(
+                    out,
+                    "    place_{} [label={}];",
+                    place,
+                    quote(place_name(*place))
+                )?;
+            }
+
+            let edges: BTreeSet<(u16, u16)> = self
+                .areas
+                .iter()
+                .flat_map(|area| {
+                    let view = &view;
+                    area.connections
+                        .iter()
+                        .filter_map(move |target| Some((area.place, view.area(*target)?.place)))
+                })
+                .filter(|(from, to)| from != to)
+                .collect();
+            for (from, to) in edges {
+                writeln!(out, "    place_{} -> place_{};", from, to)?;
+            }
+        } else {
+            for area in &self.areas {
+                let label = match self.area_place(area) {
+                    Some(place) => format!("{}\n{}", area.id, place.name),
+                    None => area.id.to_string(),
+                };
+                writeln!(out, "    area_{} [label={}];", area.id, quote(&label))?;
+            }
+
+            for area in &self.areas {
+                for direction in NavDirection::ALL.iter() {
+                    for target in &area.connections[*direction] {
+                        writeln!(
+                            out,
+                            "    area_{} -> area_{} [label={}];",
+                            area.id,
+                            target,
+                            direction.name()
+                        )?;
+                    }
+                }
+            }
+        }
+
+        writeln!(out, "}}")?;
+        out.flush()
+    }
+}
+
+#[test]
+fn test_dot() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let mut out = Vec::new();
+    mesh.export_dot(&mut out).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+
+    let connection_count = mesh
+        .areas
+        .iter()
+        .map(|area| area.connections.iter().count())
+        .sum::<usize>();
+    assert!(dot.starts_with("digraph navmesh {"));
+    assert_eq!(connection_count, dot.matches(" -> ").count());
+
+    // badwater has no places, so all areas collapse into a single node
+    let mut out = Vec::new();
+    let options = DotOptions {
+        collapse_places: true,
+    };
+    mesh.export_dot_with_options(&mut out, options).unwrap();
+    let dot = String::from_utf8(out).unwrap();
+    assert_eq!(
+        "digraph navmesh {\n    place_0 [label=\"unnamed\"];\n}\n",
+        dot
+    );
+}
@@ -0,0 +1,120 @@
+use crate::navmesh::{NavArea, NavDirection, Vector3};
+use crate::NavMesh;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+fn position(vector: Vector3) -> Value {
+    json!([vector.0, vector.1, vector.2])
+}
+
+impl NavMesh {
+    fn area_feature(&self, area: &NavArea) -> Value {
+        let corners = area.quad.corners();
+        let ring: Vec<Value> = corners
+            .iter()
+            .chain(corners.first())
+            .map(|corner| position(*corner))
+            .collect();
+
+        json!({
+            "type": "Feature",
+            "id": u32::from(area.id),
+            "geometry": {
+                "type": "Polygon",
+                "coordinates": [ring],
+            },
+            "properties": {
+                "kind": "area",
+                "id": u32::from(area.id),
+                "flags": area.flags,
+                "place": self.area_place(area).map(|place| place.name.as_str()),
+            },
+        })
+    }
+
+    /// Export the areas and their connections as a GeoJSON `FeatureCollection`
+    ///
+    /// Every area is a `Polygon` feature with the `id`, `flags` and `place` name of the area as
+    /// properties, every connection is a `LineString` between the centers of the connected areas
+    /// with `from`, `to` and `direction` properties. The `kind` property is set to either `"area"`
+    /// or `"connection"`.
+    ///
+    /// Coordinates are in hammer units and include the height as third value.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// std::fs::write("path/to/navfile.geojson", mesh.to_geojson()?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_geojson(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.geojson())
+    }
+
+    fn geojson(&self) -> Value {
+        let centers: HashMap<_, _> = self
+            .areas
+            .iter()
+            .map(|area| (area.id, area.quad.center()))
+            .collect();
+
+        let areas = self.areas.iter().map(|area| self.area_feature(area));
+        let connections = self.areas.iter().flat_map(|area| {
+            let centers = &centers;
+            NavDirection::ALL.iter().flat_map(move |direction| {
+                area.connections[*direction]
+                    .iter()
+                    .filter_map(move |target| {
+                        let end = centers.get(target)?;
+                        Some(json!({
+                            "type": "Feature",
+                            "geometry": {
+                                "type": "LineString",
+                                "coordinates": [position(centers[&area.id]), position(*end)],
+                            },
+                            "properties": {
+                                "kind": "connection",
+                                "from": u32::from(area.id),
+                                "to": u32::from(*target),
+                                "direction": direction.name(),
+                            },
+                        }))
+                    })
+            })
+        });
+
+        json!({
+            "type": "FeatureCollection",
+            "features": areas.chain(connections).collect::<Vec<_>>(),
+        })
+    }
+}
+
+#[test]
+fn test_geojson() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let json: Value = serde_json::from_str(&mesh.to_geojson().unwrap()).unwrap();
+    assert_eq!("FeatureCollection", json["type"]);
+
+    let features = json["features"].as_array().unwrap();
+    let connection_count = mesh
+        .areas
+        .iter()
+        .map(|area| area.connections.iter().count())
+        .sum::<usize>();
+    assert_eq!(1930 + connection_count, features.len());
+
+    let ring = features[0]["geometry"]["coordinates"][0]
+        .as_array()
+        .unwrap();
+    assert_eq!(5, ring.len());
+    assert_eq!(ring[0], ring[4]);
+}
@@ -0,0 +1,150 @@
+use super::{key_color, y_up};
+use crate::navmesh::NavArea;
+use crate::NavMesh;
+use serde_json::json;
+use std::io::{self, Write};
+
+/// How the areas are colored in a glTF export
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum GltfColoring {
+    /// Every place gets it's own color, areas without a place are grey
+    Place,
+    /// Every combination of attribute flags gets it's own color, areas without flags are grey
+    Flags,
+}
+
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+
+fn area_color(area: &NavArea, coloring: GltfColoring) -> [f32; 3] {
+    match coloring {
+        GltfColoring::Place => key_color(area.place as u32),
+        GltfColoring::Flags => key_color(area.flags),
+    }
+}
+
+fn pad(data: &mut Vec<u8>, with: u8) {
+    let padding = (4 - data.len() % 4) % 4;
+    data.resize(data.len() + padding, with);
+}
+
+impl NavMesh {
+    /// Export the areas as a binary glTF 2.0 (`.glb`) model
+    ///
+    /// The areas are written as a single triangle mesh with vertex colors based on the chosen
+    /// coloring. Coordinates are in hammer units, converted to the y-up axis of glTF.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{read_mesh, GltfColoring};
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// mesh.export_glb(File::create("path/to/navfile.glb")?, GltfColoring::Place)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_glb(&self, mut out: impl Write, coloring: GltfColoring) -> io::Result<()> {
+        let vertex_count = self.areas.len() * 4;
+        let mut positions = Vec::with_capacity(vertex_count * 12);
+        let mut colors = Vec::with_capacity(vertex_count * 12);
+        let mut indices = Vec::with_capacity(self.areas.len() * 6 * 4);
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+
+        for (index, area) in self.areas.iter().enumerate() {
+            let color = area_color(area, coloring);
+            for corner in area.quad.corners().iter() {
+                let position = y_up(*corner);
+                for axis in 0..3 {
+                    min[axis] = min[axis].min(position[axis]);
+                    max[axis] = max[axis].max(position[axis]);
+                    positions.extend_from_slice(&position[axis].to_le_bytes());
+                    colors.extend_from_slice(&color[axis].to_le_bytes());
+                }
+            }
+            let first = index as u32 * 4;
+            for offset in [0, 1, 2, 0, 2, 3].iter() {
+                indices.extend_from_slice(&(first + offset).to_le_bytes());
+            }
+        }
+        if self.areas.is_empty() {
+            min = [0.0; 3];
+            max = [0.0; 3];
+        }
+
+        let mut buffer = positions;
+        let colors_offset = buffer.len();
+        buffer.extend_from_slice(&colors);
+        let indices_offset = buffer.len();
+        buffer.extend_from_slice(&indices);
+        pad(&mut buffer, 0);
+
+        let document = json!({
+            "asset": { "version": "2.0", "generator": "sourcenav" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0, "name": "navmesh" }],
+            "meshes": [{
+                "primitives": [{
+                    "attributes": { "POSITION": 0, "COLOR_0": 1 },
+                    "indices": 2,
+                }],
+            }],
+            "buffers": [{ "byteLength": buffer.len() }],
+            "bufferViews": [
+                { "buffer": 0, "byteOffset": 0, "byteLength": colors_offset, "target": ARRAY_BUFFER },
+                { "buffer": 0, "byteOffset": colors_offset, "byteLength": colors.len(), "target": ARRAY_BUFFER },
+                { "buffer": 0, "byteOffset": indices_offset, "byteLength": indices.len(), "target": ELEMENT_ARRAY_BUFFER },
+            ],
+            "accessors": [
+                { "bufferView": 0, "componentType": FLOAT, "count": vertex_count, "type": "VEC3", "min": min, "max": max },
+                { "bufferView": 1, "componentType": FLOAT, "count": vertex_count, "type": "VEC3" },
+                { "bufferView": 2, "componentType": UNSIGNED_INT, "count": self.areas.len() * 6, "type": "SCALAR" },
+            ],
+        });
+        let mut document = serde_json::to_vec(&document)?;
+        pad(&mut document, b' ');
+
+        let length = 12 + 8 + document.len() + 8 + buffer.len();
+        out.write_all(b"glTF")?;
+        out.write_all(&2u32.to_le_bytes())?;
+        out.write_all(&(length as u32).to_le_bytes())?;
+        out.write_all(&(document.len() as u32).to_le_bytes())?;
+        out.write_all(b"JSON")?;
+        out.write_all(&document)?;
+        out.write_all(&(buffer.len() as u32).to_le_bytes())?;
+        out.write_all(b"BIN\0")?;
+        out.write_all(&buffer)?;
+        out.flush()
+    }
+}
+
+#[test]
+fn test_glb() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let mut out = Vec::new();
+    mesh.export_glb(&mut out, GltfColoring::Flags).unwrap();
+
+    let read_u32 = |offset: usize| {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(&out[offset..offset + 4]);
+        u32::from_le_bytes(bytes) as usize
+    };
+
+    assert_eq!(b"glTF", &out[0..4]);
+    assert_eq!(out.len(), read_u32(8));
+
+    let json_length = read_u32(12);
+    let document: serde_json::Value = serde_json::from_slice(&out[20..20 + json_length]).unwrap();
+    assert_eq!(1930 * 4, document["accessors"][0]["count"]);
+    assert_eq!(1930 * 6, document["accessors"][2]["count"]);
+    assert_eq!(out.len() - 20 - json_length - 8, read_u32(20 + json_length));
+}
@@ -0,0 +1,135 @@
+use super::y_up;
+use crate::NavMesh;
+use std::collections::HashMap;
+use std::io::{self, Write};
+
+/// Options for [`NavMesh::export_godot_with_options`]
+///
+/// [`NavMesh::export_godot_with_options`]: ./struct.NavMesh.html#method.export_godot_with_options
+#[derive(Debug, Clone, Copy)]
+pub struct GodotOptions {
+    /// The scale applied to all coordinates, defaults to converting hammer units into meters
+    pub scale: f32,
+}
+
+impl Default for GodotOptions {
+    fn default() -> Self {
+        GodotOptions { scale: 0.0254 }
+    }
+}
+
+impl NavMesh {
+    /// Export the areas as a Godot 4 `NavigationMesh` resource in the text based `.tres` format
+    ///
+    /// Every area becomes a polygon, corners at the same position are shared between polygons.
+    /// Godot only links polygons that share an edge, areas that only touch a part of each others
+    /// side are not linked.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// mesh.export_godot(File::create("path/to/navfile.tres")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_godot(&self, out: impl Write) -> io::Result<()> {
+        self.export_godot_with_options(out, GodotOptions::default())
+    }
+
+    /// Export the areas as a Godot 4 `NavigationMesh` resource with custom options
+    pub fn export_godot_with_options(
+        &self,
+        mut out: impl Write,
+        options: GodotOptions,
+    ) -> io::Result<()> {
+        let mut vertices = Vec::new();
+        let mut vertex_index = HashMap::new();
+        let mut polygons = Vec::with_capacity(self.areas.len());
+
+        for area in &self.areas {
+            let corners = area.quad.corners();
+            // godot expects the polygons to be clockwise when seen from above
+            let polygon: Vec<usize> = corners
+                .iter()
+                .rev()
+                .map(|corner| {
+                    let position = y_up(*corner);
+                    let key = [
+                        position[0].to_bits(),
+                        position[1].to_bits(),
+                        position[2].to_bits(),
+                    ];
+                    *vertex_index.entry(key).or_insert_with(|| {
+                        vertices.push(position);
+                        vertices.len() - 1
+                    })
+                })
+                .collect();
+            polygons.push(polygon);
+        }
+
+        writeln!(out, "[gd_resource type=\"NavigationMesh\" format=3]")?;
+        writeln!(out)?;
+        writeln!(out, "[resource]")?;
+        write!(out, "vertices = PackedVector3Array(")?;
+        for (index, vertex) in vertices.iter().enumerate() {
+            if index > 0 {
+                write!(out, ", ")?;
+            }
+            write!(
+                out,
+                "{}, {}, {}",
+                vertex[0] * options.scale,
+                vertex[1] * options.scale,
+                vertex[2] * options.scale
+            )?;
+        }
+        writeln!(out, ")")?;
+        write!(out, "polygons = [")?;
+        for (index, polygon) in polygons.iter().enumerate() {
+            if index > 0 {
+                write!(out, ", ")?;
+            }
+            write!(
+                out,
+                "PackedInt32Array({}, {}, {}, {})",
+                polygon[0], polygon[1], polygon[2], polygon[3]
+            )?;
+        }
+        writeln!(out, "]")?;
+
+        out.flush()
+    }
+}
+
+#[test]
+fn test_godot() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let mut out = Vec::new();
+    mesh.export_godot(&mut out).unwrap();
+    let resource = String::from_utf8(out).unwrap();
+
+    let mut lines = resource.lines();
+    assert_eq!(
+        Some("[gd_resource type=\"NavigationMesh\" format=3]"),
+        lines.next()
+    );
+    let polygons = lines.rfind(|line| line.starts_with("polygons = ")).unwrap();
+    assert_eq!(1930, polygons.matches("PackedInt32Array").count());
+
+    let vertices = resource
+        .lines()
+        .find(|line| line.starts_with("vertices = "))
+        .unwrap();
+    let vertex_count = vertices.matches(',').count() / 3 + 1;
+    // neighbouring areas share corners
+    assert!(vertex_count < 1930 * 4);
+}
@@ -0,0 +1,284 @@
+use crate::{NavMesh, NavTree};
+
+/// A raster of the heights of the areas, see [`NavMesh::heightmap`]
+///
+/// Rows are stored from top to bottom, with the positive y axis pointing up like in
+/// [`NavMesh::render_image`]
+///
+/// [`NavMesh::heightmap`]: ./struct.NavMesh.html#method.heightmap
+/// [`NavMesh::render_image`]: ./struct.NavMesh.html#method.render_image
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heightmap {
+    pub width: u32,
+    pub height: u32,
+    /// The x coordinate of the left edge and y coordinate of the top edge of the raster
+    pub origin: (f32, f32),
+    /// The size of a pixel in hammer units
+    pub resolution: f32,
+    /// The height at the center of every pixel, `None` for pixels that aren't covered by any area
+    pub heights: Vec<Option<f32>>,
+}
+
+impl Heightmap {
+    /// Get the height of a pixel
+    pub fn get(&self, x: u32, y: u32) -> Option<f32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.heights[y as usize * self.width as usize + x as usize]
+    }
+
+    /// The lowest and highest height in the raster
+    pub fn range(&self) -> Option<(f32, f32)> {
+        self.heights.iter().flatten().fold(None, |range, height| {
+            Some(match range {
+                Some((min, max)) => (f32::min(min, *height), f32::max(max, *height)),
+                None => (*height, *height),
+            })
+        })
+    }
+
+    /// Convert the heightmap into a 16 bit grayscale image
+    ///
+    /// Heights are scaled to use the full range of the image, uncovered pixels are black and the
+    /// lowest height has the value `1`.
+    #[cfg(feature = "image")]
+    pub fn to_image(&self) -> image::ImageBuffer<image::Luma<u16>, Vec<u16>> {
+        let (min, max) = self.range().unwrap_or((0.0, 0.0));
+        let size = (max - min).max(1.0);
+        let pixels = self
+            .heights
+            .iter()
+            .map(|height| match height {
+                Some(height) => 1 + ((height - min) / size * (u16::MAX - 1) as f32) as u16,
+                None => 0,
+            })
+            .collect();
+        image::ImageBuffer::from_raw(self.width, self.height, pixels)
+            .expect("heightmap contains a height for every pixel")
+    }
+}
+
+impl NavMesh {
+    /// Rasterize the interpolated heights of the areas into a heightmap
+    ///
+    /// `resolution` is the size of a pixel in hammer units, the raster covers the bounds of all
+    /// areas. Where areas overlap, the height of the highest area is used.
+    ///
+    /// Returns `None` if the resolution isn't a positive number.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let heightmap = mesh.heightmap(16.0).unwrap();
+    /// println!("{}x{} pixels", heightmap.width, heightmap.height);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn heightmap(&self, resolution: f32) -> Option<Heightmap> {
+        if resolution.is_nan() || resolution <= 0.0 {
+            return None;
+        }
+
+        let mut min = (f32::INFINITY, f32::INFINITY);
+        let mut max = (f32::NEG_INFINITY, f32::NEG_INFINITY);
+        for area in &self.areas {
+            min.0 = min.0.min(area.quad.north_west.0);
+            min.1 = min.1.min(area.quad.north_west.1);
+            max.0 = max.0.max(area.quad.south_east.0);
+            max.1 = max.1.max(area.quad.south_east.1);
+        }
+
+        if self.areas.is_empty() {
+            return Some(Heightmap {
+                width: 0,
+                height: 0,
+                origin: (0.0, 0.0),
+                resolution,
+                heights: Vec::new(),
+            });
+        }
+
+        let width = ((max.0 - min.0) / resolution).ceil().max(1.0) as u32;
+        let height = ((max.1 - min.1) / resolution).ceil().max(1.0) as u32;
+        let mut heights = vec![None; width as usize * height as usize];
+
+        for area in &self.areas {
+            let quad = &area.quad;
+            let column = |x: f32| (x - min.0) / resolution - 0.5;
+            let row = |y: f32| (max.1 - y) / resolution - 0.5;
+
+            let start_x = column(quad.north_west.0).ceil().max(0.0) as u32;
+            let end_x = (column(quad.south_east.0).floor() as i64).min(width as i64 - 1);
+            let start_y = row(quad.south_east.1).ceil().max(0.0) as u32;
+            let end_y = (row(quad.north_west.1).floor() as i64).min(height as i64 - 1);
+
+            for py in start_y as i64..=end_y {
+                for px in start_x as i64..=end_x {
+                    let x = min.0 + (px as f32 + 0.5) * resolution;
+                    let y = max.1 - (py as f32 + 0.5) * resolution;
+                    let z = quad.interpolated_z(x, y);
+                    let pixel = &mut heights[(py * width as i64 + px) as usize];
+                    if !pixel.is_some_and(|existing| existing >= z) {
+                        *pixel = Some(z);
+                    }
+                }
+            }
+        }
+
+        Some(Heightmap {
+            width,
+            height,
+            origin: (min.0, max.1),
+            resolution,
+            heights,
+        })
+    }
+}
+
+impl NavTree {
+    /// Sample the interpolated heights of the areas on a regular grid
+    ///
+    /// The grid covers the rectangle given by it's minimum and maximum x/y coordinates, with
+    /// samples `resolution` hammer units apart taken at the center of every cell. Where areas
+    /// overlap, the height of the highest area is used.
+    ///
+    /// Returns `None` if the resolution isn't a positive number.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let grid = tree.sample_grid((-1024.0, -1024.0), (1024.0, 1024.0), 32.0).unwrap();
+    /// if let Some(height) = grid.get(0, 0) {
+    ///     println!("the top left of the grid is at {}", height);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn sample_grid(
+        &self,
+        min: (f32, f32),
+        max: (f32, f32),
+        resolution: f32,
+    ) -> Option<Heightmap> {
+        if resolution.is_nan() || resolution <= 0.0 {
+            return None;
+        }
+        if max.0 <= min.0 || max.1 <= min.1 {
+            return Some(Heightmap {
+                width: 0,
+                height: 0,
+                origin: (min.0, max.1),
+                resolution,
+                heights: Vec::new(),
+            });
+        }
+
+        let width = ((max.0 - min.0) / resolution).ceil() as u32;
+        let height = ((max.1 - min.1) / resolution).ceil() as u32;
+        let mut heights = Vec::with_capacity(width as usize * height as usize);
+        for row in 0..height {
+            let y = max.1 - (row as f32 + 0.5) * resolution;
+            for column in 0..width {
+                let x = min.0 + (column as f32 + 0.5) * resolution;
+                heights.push(
+                    self.query_radius(x, y, 0.0)
+                        .map(|area| area.quad.interpolated_z(x, y))
+                        .fold(None, |highest: Option<f32>, z| {
+                            Some(highest.map_or(z, |highest| highest.max(z)))
+                        }),
+                );
+            }
+        }
+
+        Some(Heightmap {
+            width,
+            height,
+            origin: (min.0, max.1),
+            resolution,
+            heights,
+        })
+    }
+}
+
+#[test]
+fn test_heightmap() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let heightmap = mesh.heightmap(32.0).unwrap();
+    assert_eq!(
+        heightmap.width as usize * heightmap.height as usize,
+        heightmap.heights.len()
+    );
+    assert_eq!(None, mesh.heightmap(0.0));
+    assert_eq!(None, mesh.heightmap(-16.0));
+    assert_eq!(None, mesh.heightmap(f32::NAN));
+
+    let covered = heightmap.heights.iter().flatten().count();
+    assert!(covered > 0);
+    assert!(covered < heightmap.heights.len());
+
+    let (min, max) = heightmap.range().unwrap();
+    let area_min = mesh
+        .areas
+        .iter()
+        .flat_map(|area| area.quad.corners().to_vec())
+        .map(|corner| corner.2)
+        .fold(f32::INFINITY, f32::min);
+    assert!(min >= area_min);
+    assert!(max > min);
+
+    #[cfg(feature = "image")]
+    {
+        let image = heightmap.to_image();
+        assert_eq!((heightmap.width, heightmap.height), image.dimensions());
+    }
+}
+
+#[test]
+fn test_sample_grid() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file.clone()).unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let heightmap = mesh.heightmap(32.0).unwrap();
+    let min = (
+        heightmap.origin.0,
+        heightmap.origin.1 - heightmap.height as f32 * 32.0,
+    );
+    let max = (
+        heightmap.origin.0 + heightmap.width as f32 * 32.0,
+        heightmap.origin.1,
+    );
+    let grid = tree.sample_grid(min, max, 32.0).unwrap();
+    assert_eq!(heightmap.width, grid.width);
+    assert_eq!(heightmap.height, grid.height);
+    assert_eq!(heightmap.origin, grid.origin);
+
+    // both rasterize the same areas at the same sample positions
+    for (a, b) in heightmap.heights.iter().zip(&grid.heights) {
+        match (a, b) {
+            (Some(a), Some(b)) => assert!((a - b).abs() < 0.01),
+            (a, b) => assert_eq!(a.is_some(), b.is_some()),
+        }
+    }
+
+    assert_eq!(
+        0,
+        tree.sample_grid((0.0, 0.0), (0.0, 100.0), 8.0)
+            .unwrap()
+            .width
+    );
+    assert_eq!(None, tree.sample_grid(min, max, 0.0));
+}
@@ -0,0 +1,114 @@
+use super::key_color;
+use crate::NavMesh;
+use image::{Rgba, RgbaImage};
+
+impl NavMesh {
+    /// Render a top down view of the areas
+    ///
+    /// The mesh is scaled to fit the requested image size while keeping it's aspect ratio, with
+    /// the positive y axis pointing up like the map overviews used by the games. Areas are shaded
+    /// by their height, areas with attribute flags set are tinted with a color for their flags.
+    /// Pixels that aren't covered by any area are transparent.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// mesh.render_image(1024, 1024).save("path/to/navfile.png")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn render_image(&self, width: u32, height: u32) -> RgbaImage {
+        let mut image = RgbaImage::new(width, height);
+        if self.areas.is_empty() || width == 0 || height == 0 {
+            return image;
+        }
+
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for corner in self
+            .areas
+            .iter()
+            .flat_map(|area| area.quad.corners().to_vec())
+        {
+            let corner = [corner.0, corner.1, corner.2];
+            for axis in 0..3 {
+                min[axis] = min[axis].min(corner[axis]);
+                max[axis] = max[axis].max(corner[axis]);
+            }
+        }
+
+        let size_x = (max[0] - min[0]).max(1.0);
+        let size_y = (max[1] - min[1]).max(1.0);
+        let size_z = (max[2] - min[2]).max(1.0);
+        let scale = (width as f32 / size_x).min(height as f32 / size_y);
+        let offset_x = (width as f32 - size_x * scale) / 2.0;
+        let offset_y = (height as f32 - size_y * scale) / 2.0;
+
+        // draw the higher areas last so they cover the areas below them
+        let mut areas: Vec<_> = self.areas.iter().collect();
+        areas.sort_by(|a, b| {
+            let a = a.quad.north_west.2.max(a.quad.south_east.2);
+            let b = b.quad.north_west.2.max(b.quad.south_east.2);
+            a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        for area in areas {
+            let quad = &area.quad;
+            let pixel_x = |x: f32| (x - min[0]) * scale + offset_x;
+            let pixel_y = |y: f32| (max[1] - y) * scale + offset_y;
+
+            let start_x = pixel_x(quad.north_west.0).floor().max(0.0) as u32;
+            let end_x = (pixel_x(quad.south_east.0).ceil() as u32).min(width);
+            let start_y = pixel_y(quad.south_east.1).floor().max(0.0) as u32;
+            let end_y = (pixel_y(quad.north_west.1).ceil() as u32).min(height);
+
+            let tint = if area.flags == 0 {
+                None
+            } else {
+                Some(key_color(area.flags))
+            };
+
+            for py in start_y..end_y.max(start_y + 1).min(height) {
+                for px in start_x..end_x.max(start_x + 1).min(width) {
+                    let x = (px as f32 + 0.5 - offset_x) / scale + min[0];
+                    let y = max[1] - (py as f32 + 0.5 - offset_y) / scale;
+                    let shade = 0.25 + 0.75 * (quad.interpolated_z(x, y) - min[2]) / size_z;
+                    let color = match tint {
+                        Some(tint) => [
+                            (shade + tint[0]) / 2.0,
+                            (shade + tint[1]) / 2.0,
+                            (shade + tint[2]) / 2.0,
+                        ],
+                        None => [shade; 3],
+                    };
+                    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0) as u8;
+                    image.put_pixel(
+                        px,
+                        py,
+                        Rgba([channel(color[0]), channel(color[1]), channel(color[2]), 255]),
+                    );
+                }
+            }
+        }
+
+        image
+    }
+}
+
+#[test]
+fn test_render_image() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let image = mesh.render_image(256, 128);
+    assert_eq!((256, 128), image.dimensions());
+
+    let covered = image.pixels().filter(|pixel| pixel[3] == 255).count();
+    assert!(covered > 0);
+    assert!(covered < 256 * 128);
+}
@@ -0,0 +1,328 @@
+use crate::navmesh::{LadderDirection, NavArea, NavDirection, NavLadder, Vector3};
+use crate::NavMesh;
+use serde::Serialize;
+use std::io::Write;
+
+/// The version of the schema produced by [`NavMesh::to_json`]
+///
+/// Only incremented for changes that break existing consumers, new fields can be added without
+/// changing the version.
+///
+/// [`NavMesh::to_json`]: ./struct.NavMesh.html#method.to_json
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct JsonMesh<'a> {
+    schema_version: u32,
+    major_version: u32,
+    minor_version: u32,
+    bsp_size: u32,
+    is_analyzed: bool,
+    places: Vec<JsonPlace<'a>>,
+    areas: Vec<JsonArea>,
+    ladders: Vec<JsonLadder>,
+}
+
+#[derive(Serialize)]
+struct JsonPlace<'a> {
+    id: u16,
+    name: &'a str,
+}
+
+#[derive(Serialize)]
+struct JsonArea {
+    id: u32,
+    flags: u32,
+    place: Option<u16>,
+    corners: [[f32; 3]; 4],
+    connections: JsonConnections,
+    ladders: JsonLadderConnections,
+    hiding_spots: Vec<JsonHidingSpot>,
+    encounter_paths: Vec<JsonEncounterPath>,
+    earliest_occupy: [f32; 2],
+    light_intensity: [f32; 4],
+    visible_areas: Vec<JsonVisibleArea>,
+    inherit_visibility_from: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct JsonConnections {
+    north: Vec<u32>,
+    east: Vec<u32>,
+    south: Vec<u32>,
+    west: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct JsonLadderConnections {
+    up: Vec<u32>,
+    down: Vec<u32>,
+}
+
+#[derive(Serialize)]
+struct JsonHidingSpot {
+    id: u32,
+    position: [f32; 3],
+    flags: u8,
+}
+
+#[derive(Serialize)]
+struct JsonEncounterPath {
+    from: u32,
+    from_direction: Option<&'static str>,
+    to: u32,
+    to_direction: Option<&'static str>,
+    spots: Vec<JsonEncounterSpot>,
+}
+
+#[derive(Serialize)]
+struct JsonEncounterSpot {
+    order: u32,
+    distance: f32,
+}
+
+#[derive(Serialize)]
+struct JsonVisibleArea {
+    id: u32,
+    attributes: u8,
+}
+
+#[derive(Serialize)]
+struct JsonLadder {
+    id: u32,
+    width: f32,
+    length: f32,
+    top: [f32; 3],
+    bottom: [f32; 3],
+    direction: &'static str,
+    top_forward_area: Option<u32>,
+    top_left_area: Option<u32>,
+    top_right_area: Option<u32>,
+    top_behind_area: Option<u32>,
+    bottom_area: Option<u32>,
+}
+
+fn point(vector: Vector3) -> [f32; 3] {
+    [vector.0, vector.1, vector.2]
+}
+
+fn ids<T: Copy + Into<u32>>(ids: &[T]) -> Vec<u32> {
+    ids.iter().map(|id| (*id).into()).collect()
+}
+
+fn direction_name(direction: u8) -> Option<&'static str> {
+    NavDirection::from_index(direction as u32).map(NavDirection::name)
+}
+
+impl From<&NavArea> for JsonArea {
+    fn from(area: &NavArea) -> Self {
+        let corners = area.quad.corners();
+        let light = &area.light_intensity;
+
+        JsonArea {
+            id: area.id.into(),
+            flags: area.flags,
+            place: if area.place == 0 {
+                None
+            } else {
+                Some(area.place)
+            },
+            corners: [
+                point(corners[0]),
+                point(corners[1]),
+                point(corners[2]),
+                point(corners[3]),
+            ],
+            connections: JsonConnections {
+                north: ids(&area.connections[NavDirection::North]),
+                east: ids(&area.connections[NavDirection::East]),
+                south: ids(&area.connections[NavDirection::South]),
+                west: ids(&area.connections[NavDirection::West]),
+            },
+            ladders: JsonLadderConnections {
+                up: ids(&area.ladder_connections[LadderDirection::Up]),
+                down: ids(&area.ladder_connections[LadderDirection::Down]),
+            },
+            hiding_spots: area
+                .hiding_spots
+                .iter()
+                .map(|spot| JsonHidingSpot {
+                    id: spot.id,
+                    position: point(spot.location),
+                    flags: spot.flags,
+                })
+                .collect(),
+            encounter_paths: area
+                .encounter_paths
+                .iter()
+                .map(|path| JsonEncounterPath {
+                    from: path.from_area_id.into(),
+                    from_direction: direction_name(path.from_direction),
+                    to: path.to_area_id.into(),
+                    to_direction: direction_name(path.to_direction),
+                    spots: path
+                        .spots
+                        .iter()
+                        .map(|spot| JsonEncounterSpot {
+                            order: spot.order,
+                            distance: spot.distance as f32 / 255.0,
+                        })
+                        .collect(),
+                })
+                .collect(),
+            earliest_occupy: [
+                area.earliest_occupy_first_team,
+                area.earliest_occupy_second_team,
+            ],
+            light_intensity: [
+                light.north_west,
+                light.north_east,
+                light.south_east,
+                light.south_west,
+            ],
+            visible_areas: area
+                .visible_areas
+                .iter()
+                .map(|visible| JsonVisibleArea {
+                    id: visible.id.into(),
+                    attributes: visible.attributes,
+                })
+                .collect(),
+            inherit_visibility_from: match area.inherit_visibility_from_area_id {
+                0 => None,
+                id => Some(id),
+            },
+        }
+    }
+}
+
+impl From<&NavLadder> for JsonLadder {
+    fn from(ladder: &NavLadder) -> Self {
+        JsonLadder {
+            id: ladder.id.into(),
+            width: ladder.width,
+            length: ladder.length,
+            top: point(ladder.top),
+            bottom: point(ladder.bottom),
+            direction: ladder.direction.name(),
+            top_forward_area: ladder.top_forward_area.map(u32::from),
+            top_left_area: ladder.top_left_area.map(u32::from),
+            top_right_area: ladder.top_right_area.map(u32::from),
+            top_behind_area: ladder.top_behind_area.map(u32::from),
+            bottom_area: ladder.bottom_area.map(u32::from),
+        }
+    }
+}
+
+impl NavMesh {
+    fn json_mesh(&self) -> JsonMesh<'_> {
+        JsonMesh {
+            schema_version: JSON_SCHEMA_VERSION,
+            major_version: self.major_version,
+            minor_version: self.minor_version,
+            bsp_size: self.bsp_size,
+            is_analyzed: self.is_analyzed,
+            places: self
+                .places
+                .iter()
+                .map(|place| JsonPlace {
+                    id: place.id,
+                    name: &place.name,
+                })
+                .collect(),
+            areas: self.areas.iter().map(JsonArea::from).collect(),
+            ladders: self.ladders.iter().map(JsonLadder::from).collect(),
+        }
+    }
+
+    /// Export the navigation mesh as json
+    ///
+    /// Unlike the `serde` implementations, which follow the rust types, the json produced here
+    /// follows a fixed schema meant for consumers written in other languages:
+    ///
+    /// ```text
+    /// {
+    ///   "schema_version": 1,
+    ///   "major_version": 16, "minor_version": 2, "bsp_size": 16699088, "is_analyzed": true,
+    ///   "places": [{ "id": 1, "name": "BombsiteA" }],
+    ///   "areas": [{
+    ///     "id": 1,
+    ///     "flags": 0,
+    ///     "place": 1,                       // place id, or null
+    ///     "corners": [[x, y, z], ...],      // north west, north east, south east, south west
+    ///     "connections": { "north": [2], "east": [], "south": [], "west": [3] },
+    ///     "ladders": { "up": [1], "down": [] },
+    ///     "hiding_spots": [{ "id": 1, "position": [x, y, z], "flags": 0 }],
+    ///     "encounter_paths": [{
+    ///       "from": 2, "from_direction": "north", // direction name, or null if invalid
+    ///       "to": 3, "to_direction": "west",
+    ///       "spots": [{ "order": 1, "distance": 0.5 }] // distance as fraction of the path
+    ///     }],
+    ///     "earliest_occupy": [first_team, second_team],
+    ///     "light_intensity": [north_west, north_east, south_east, south_west],
+    ///     "visible_areas": [{ "id": 4, "attributes": 2 }],
+    ///     "inherit_visibility_from": 5      // area id, or null
+    ///   }],
+    ///   "ladders": [{
+    ///     "id": 1, "width": 20.0, "length": 128.0,
+    ///     "top": [x, y, z], "bottom": [x, y, z],
+    ///     "direction": "east",
+    ///     "top_forward_area": 1, "top_left_area": null, "top_right_area": null,
+    ///     "top_behind_area": null, "bottom_area": 6
+    ///   }]
+    /// }
+    /// ```
+    ///
+    /// All ids are numbers and all directions are lowercase strings. Game specific data is not
+    /// included. See [`JSON_SCHEMA_VERSION`] for the compatibility guarantees of the schema.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// std::fs::write("path/to/navfile.json", mesh.to_json()?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`JSON_SCHEMA_VERSION`]: ./constant.JSON_SCHEMA_VERSION.html
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(&self.json_mesh())
+    }
+
+    /// Write the navigation mesh as json, see [`to_json`] for the schema
+    ///
+    /// [`to_json`]: #method.to_json
+    pub fn write_json(&self, out: impl Write) -> Result<(), serde_json::Error> {
+        serde_json::to_writer(out, &self.json_mesh())
+    }
+}
+
+#[test]
+fn test_json() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let json: serde_json::Value = serde_json::from_str(&mesh.to_json().unwrap()).unwrap();
+
+    assert_eq!(JSON_SCHEMA_VERSION, json["schema_version"]);
+    assert_eq!(16, json["major_version"]);
+
+    let areas = json["areas"].as_array().unwrap();
+    assert_eq!(1930, areas.len());
+
+    let area = &mesh.areas[0];
+    assert_eq!(u32::from(area.id), areas[0]["id"]);
+    assert_eq!(area.quad.north_west.0 as f64, areas[0]["corners"][0][0]);
+    assert_eq!(
+        area.connections[NavDirection::North].len(),
+        areas[0]["connections"]["north"].as_array().unwrap().len()
+    );
+
+    // badwater has no place table
+    assert!(areas[0]["place"].is_null());
+}
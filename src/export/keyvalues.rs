@@ -0,0 +1,182 @@
+use crate::navmesh::{LadderDirection, NavDirection, Vector3};
+use crate::NavMesh;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+struct KeyValuesWriter<W: Write> {
+    out: W,
+    depth: usize,
+}
+
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn vector(vector: &Vector3) -> String {
+    format!("{} {} {}", vector.0, vector.1, vector.2)
+}
+
+fn list<T: Display>(items: &[T]) -> String {
+    items
+        .iter()
+        .map(|item| item.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl<W: Write> KeyValuesWriter<W> {
+    fn indent(&mut self) -> io::Result<()> {
+        for _ in 0..self.depth {
+            self.out.write_all(b"\t")?;
+        }
+        Ok(())
+    }
+
+    fn value(&mut self, key: &str, value: impl Display) -> io::Result<()> {
+        self.indent()?;
+        writeln!(self.out, "{}\t{}", quote(key), quote(&value.to_string()))
+    }
+
+    fn open(&mut self, key: &str) -> io::Result<()> {
+        self.indent()?;
+        writeln!(self.out, "{}", quote(key))?;
+        self.indent()?;
+        writeln!(self.out, "{{")?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    fn close(&mut self) -> io::Result<()> {
+        self.depth -= 1;
+        self.indent()?;
+        writeln!(self.out, "}}")
+    }
+}
+
+impl NavMesh {
+    /// Export the navigation mesh as Valve KeyValues text
+    ///
+    /// The output is meant to be read by humans, to review the changes between nav files with
+    /// normal text tools. Every area is written as a block keyed by it's id, with the
+    /// connections listed per direction.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// mesh.export_keyvalues(File::create("path/to/navfile.txt")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_keyvalues(&self, out: impl Write) -> io::Result<()> {
+        let mut writer = KeyValuesWriter { out, depth: 0 };
+
+        writer.open("NavMesh")?;
+        writer.value("MajorVersion", self.major_version)?;
+        writer.value("MinorVersion", self.minor_version)?;
+        writer.value("BspSize", self.bsp_size)?;
+        writer.value("Analyzed", self.is_analyzed as u8)?;
+
+        writer.open("Places")?;
+        for place in &self.places {
+            writer.value(&place.id.to_string(), &place.name)?;
+        }
+        writer.close()?;
+
+        writer.open("Areas")?;
+        for area in &self.areas {
+            writer.open(&area.id.to_string())?;
+            writer.value("Flags", format!("{:#x}", area.flags))?;
+            if let Some(place) = self.area_place(area) {
+                writer.value("Place", &place.name)?;
+            }
+            writer.value("NorthWest", vector(&area.quad.north_west))?;
+            writer.value("SouthEast", vector(&area.quad.south_east))?;
+            writer.value("NorthEastZ", area.quad.north_east_z)?;
+            writer.value("SouthWestZ", area.quad.south_west_z)?;
+
+            writer.open("Connections")?;
+            for direction in NavDirection::ALL.iter() {
+                let connections = &area.connections[*direction];
+                if !connections.is_empty() {
+                    writer.value(direction.name(), list(connections))?;
+                }
+            }
+            writer.close()?;
+
+            if area.ladder_connections.iter().next().is_some() {
+                writer.open("Ladders")?;
+                for direction in [LadderDirection::Up, LadderDirection::Down].iter() {
+                    let ladders = &area.ladder_connections[*direction];
+                    if !ladders.is_empty() {
+                        writer.value(direction.name(), list(ladders))?;
+                    }
+                }
+                writer.close()?;
+            }
+
+            if !area.hiding_spots.is_empty() {
+                writer.open("HidingSpots")?;
+                for spot in &area.hiding_spots {
+                    writer.open(&spot.id.to_string())?;
+                    writer.value("Position", vector(&spot.location))?;
+                    writer.value("Flags", format!("{:#x}", spot.flags))?;
+                    writer.close()?;
+                }
+                writer.close()?;
+            }
+
+            writer.value("EncounterPaths", area.encounter_paths.len())?;
+            writer.value(
+                "EarliestOccupy",
+                format!(
+                    "{} {}",
+                    area.earliest_occupy_first_team, area.earliest_occupy_second_team
+                ),
+            )?;
+            if !area.visible_areas.is_empty() {
+                let visible: Vec<_> = area
+                    .visible_areas
+                    .iter()
+                    .map(|visible| visible.id)
+                    .collect();
+                writer.value("VisibleAreas", list(&visible))?;
+            }
+            writer.close()?;
+        }
+        writer.close()?;
+
+        writer.open("Ladders")?;
+        for ladder in &self.ladders {
+            writer.open(&ladder.id.to_string())?;
+            writer.value("Top", vector(&ladder.top))?;
+            writer.value("Bottom", vector(&ladder.bottom))?;
+            writer.value("Width", ladder.width)?;
+            writer.value("Direction", ladder.direction.name())?;
+            writer.close()?;
+        }
+        writer.close()?;
+
+        writer.close()?;
+        writer.out.flush()
+    }
+}
+
+#[test]
+fn test_keyvalues() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let mut out = Vec::new();
+    mesh.export_keyvalues(&mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+
+    assert!(text.starts_with("\"NavMesh\"\n{\n\t\"MajorVersion\"\t\"16\"\n"));
+    assert_eq!(text.matches('{').count(), text.matches('}').count());
+    assert_eq!(1930, text.matches("\t\t\"NorthWest\"").count());
+}
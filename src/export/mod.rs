@@ -0,0 +1,64 @@
+//! Exporters for formats used by other tools
+
+mod csr;
+mod csv;
+mod detour;
+mod dot;
+#[cfg(feature = "json")]
+mod geojson;
+#[cfg(feature = "gltf")]
+mod gltf;
+#[cfg(feature = "godot")]
+mod godot;
+mod heightmap;
+#[cfg(feature = "image")]
+mod image;
+#[cfg(feature = "json")]
+mod json;
+mod keyvalues;
+mod obj;
+#[cfg(feature = "petgraph")]
+mod petgraph;
+mod triangles;
+
+#[cfg(feature = "petgraph")]
+pub use self::petgraph::NavEdge;
+pub use csr::NavCsr;
+pub use detour::{DetourOffMeshConnection, DetourPoly, DetourTile};
+pub use dot::DotOptions;
+#[cfg(feature = "gltf")]
+pub use gltf::GltfColoring;
+#[cfg(feature = "godot")]
+pub use godot::GodotOptions;
+pub use heightmap::Heightmap;
+#[cfg(feature = "json")]
+pub use json::JSON_SCHEMA_VERSION;
+pub use obj::ObjOptions;
+pub use triangles::NavTriangles;
+
+use crate::navmesh::Vector3;
+
+/// Convert from the z-up hammer coordinates into a y-up coordinate system
+pub(crate) fn y_up(vector: Vector3) -> [f32; 3] {
+    [vector.0, vector.2, -vector.1]
+}
+
+/// Spread colors for consecutive keys around the color wheel, with grey for `0`
+#[cfg(any(feature = "gltf", feature = "image"))]
+pub(crate) fn key_color(key: u32) -> [f32; 3] {
+    if key == 0 {
+        return [0.5, 0.5, 0.5];
+    }
+    let hue = (key as f32 * 0.618_034).fract() * 6.0;
+    let x = 1.0 - (hue % 2.0 - 1.0).abs();
+    let (r, g, b) = match hue as u32 {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    // keep the colors away from full saturation
+    [0.2 + r * 0.7, 0.2 + g * 0.7, 0.2 + b * 0.7]
+}
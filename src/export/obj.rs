@@ -0,0 +1,119 @@
+use crate::navmesh::{NavDirection, NavLadder, Vector3};
+use crate::NavMesh;
+use std::io::{self, Write};
+
+/// Options for [`NavMesh::export_obj_with_options`]
+///
+/// [`NavMesh::export_obj_with_options`]: ./struct.NavMesh.html#method.export_obj_with_options
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ObjOptions {
+    /// Include a quad for every ladder
+    pub ladders: bool,
+}
+
+struct ObjWriter<W: Write> {
+    out: W,
+    vertex_count: usize,
+}
+
+impl<W: Write> ObjWriter<W> {
+    fn face(&mut self, name: &str, corners: &[Vector3; 4]) -> io::Result<()> {
+        writeln!(self.out, "g {}", name)?;
+        for corner in corners {
+            writeln!(self.out, "v {} {} {}", corner.0, corner.1, corner.2)?;
+        }
+        let first = self.vertex_count + 1;
+        writeln!(
+            self.out,
+            "f {} {} {} {}",
+            first,
+            first + 1,
+            first + 2,
+            first + 3
+        )?;
+        self.vertex_count += 4;
+        Ok(())
+    }
+}
+
+fn ladder_corners(ladder: &NavLadder) -> [Vector3; 4] {
+    // the ladder spans it's width perpendicular to the direction it's facing
+    let half = ladder.width / 2.0;
+    let (dx, dy) = match ladder.direction {
+        NavDirection::North | NavDirection::South => (half, 0.0),
+        NavDirection::East | NavDirection::West => (0.0, half),
+    };
+    let (top, bottom) = (ladder.top, ladder.bottom);
+    [
+        Vector3(bottom.0 - dx, bottom.1 - dy, bottom.2),
+        Vector3(bottom.0 + dx, bottom.1 + dy, bottom.2),
+        Vector3(top.0 + dx, top.1 + dy, top.2),
+        Vector3(top.0 - dx, top.1 - dy, top.2),
+    ]
+}
+
+impl NavMesh {
+    /// Export the areas as a Wavefront OBJ model
+    ///
+    /// Every area is written as a quad in it's own group named `area_<id>`, using the hammer
+    /// coordinates with the z axis pointing up.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// mesh.export_obj(File::create("path/to/navfile.obj")?)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_obj(&self, out: impl Write) -> io::Result<()> {
+        self.export_obj_with_options(out, ObjOptions::default())
+    }
+
+    /// Export the navigation mesh as a Wavefront OBJ model
+    ///
+    /// Ladders are written in groups named `ladder_<id>` when enabled in the options.
+    pub fn export_obj_with_options(&self, out: impl Write, options: ObjOptions) -> io::Result<()> {
+        let mut writer = ObjWriter {
+            out,
+            vertex_count: 0,
+        };
+
+        for area in &self.areas {
+            writer.face(&format!("area_{}", area.id), &area.quad.corners())?;
+        }
+
+        if options.ladders {
+            for ladder in &self.ladders {
+                writer.face(&format!("ladder_{}", ladder.id), &ladder_corners(ladder))?;
+            }
+        }
+
+        writer.out.flush()
+    }
+}
+
+#[test]
+fn test_obj() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let mut out = Vec::new();
+    mesh.export_obj(&mut out).unwrap();
+    let obj = String::from_utf8(out).unwrap();
+
+    assert_eq!(
+        1930 * 4,
+        obj.lines().filter(|l| l.starts_with("v ")).count()
+    );
+    assert_eq!(1930, obj.lines().filter(|l| l.starts_with("f ")).count());
+    assert_eq!(
+        Some("f 7717 7718 7719 7720"),
+        obj.lines().rfind(|l| l.starts_with("f "))
+    );
+}
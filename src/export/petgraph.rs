@@ -0,0 +1,119 @@
+use crate::navmesh::{LadderDirection, NavAreaId, NavDirection};
+use crate::{EdgeKind, NavMesh};
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::collections::HashMap;
+
+/// A connection between two areas in a graph made by [`NavMesh::to_petgraph`]
+///
+/// [`NavMesh::to_petgraph`]: ./struct.NavMesh.html#method.to_petgraph
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NavEdge {
+    /// The side of the area the connection leaves from, `None` for ladders
+    pub direction: Option<NavDirection>,
+    /// The distance between the centers of the connected areas
+    pub length: f32,
+    /// How the connected area is reached
+    pub kind: EdgeKind,
+}
+
+impl NavMesh {
+    /// Get the connection graph of the areas as a `petgraph` graph
+    ///
+    /// Every area is a node with the area id as weight, the node at index `i` is the area at
+    /// index `i` of the mesh. Connections and ladders are directed edges from the area they can
+    /// be used from, connections to unknown areas are skipped.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    /// use petgraph::algo::dijkstra;
+    /// use petgraph::graph::NodeIndex;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let graph = mesh.to_petgraph();
+    /// let distances = dijkstra(&graph, NodeIndex::new(0), None, |edge| edge.weight().length);
+    /// for (node, distance) in distances {
+    ///     println!("area {} is {} units away", graph[node], distance);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_petgraph(&self) -> DiGraph<NavAreaId, NavEdge> {
+        let mut graph = DiGraph::with_capacity(self.areas.len(), 0);
+        let index: HashMap<NavAreaId, NodeIndex> = self
+            .areas
+            .iter()
+            .map(|area| (area.id, graph.add_node(area.id)))
+            .collect();
+        let centers: Vec<_> = self.areas.iter().map(|area| area.quad.center()).collect();
+
+        for (area_index, area) in self.areas.iter().enumerate() {
+            let from = NodeIndex::new(area_index);
+            let mut add_edge = |target: NavAreaId, direction, kind| {
+                if let Some(to) = index.get(&target) {
+                    let length = centers[area_index].distance(centers[to.index()]);
+                    graph.add_edge(
+                        from,
+                        *to,
+                        NavEdge {
+                            direction,
+                            length,
+                            kind,
+                        },
+                    );
+                }
+            };
+
+            for direction in NavDirection::ALL.iter().copied() {
+                for target in &area.connections[direction] {
+                    add_edge(*target, Some(direction), EdgeKind::Walk);
+                }
+            }
+            for direction in LadderDirection::ALL.iter().copied() {
+                let kind = match direction {
+                    LadderDirection::Up => EdgeKind::LadderUp,
+                    LadderDirection::Down => EdgeKind::LadderDown,
+                };
+                for ladder in &area.ladder_connections[direction] {
+                    if let Some(ladder) = self.ladder(*ladder) {
+                        for target in ladder.areas(direction) {
+                            add_edge(target, None, kind);
+                        }
+                    }
+                }
+            }
+        }
+
+        graph
+    }
+}
+
+#[test]
+fn test_petgraph() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let graph = mesh.to_petgraph();
+    let csr = mesh.to_csr();
+    assert_eq!(mesh.areas.len(), graph.node_count());
+    assert_eq!(csr.targets.len(), graph.edge_count());
+
+    for (index, area) in mesh.areas.iter().enumerate() {
+        assert_eq!(area.id, graph[NodeIndex::new(index)]);
+    }
+    for edge in graph.raw_edges() {
+        let from = &mesh.areas[edge.source().index()];
+        let to = &mesh.areas[edge.target().index()];
+        let direction = edge.weight.direction.unwrap();
+        assert!(from.connections[direction].contains(&to.id));
+        assert_eq!(EdgeKind::Walk, edge.weight.kind);
+        assert!((from.quad.center().distance(to.quad.center()) - edge.weight.length).abs() < 0.01);
+    }
+
+    // the graph works with the petgraph algorithms
+    let components = petgraph::algo::connected_components(&graph);
+    assert!(components >= 1);
+}
@@ -0,0 +1,105 @@
+use crate::navmesh::{NavAreaId, Vector3};
+use crate::NavMesh;
+
+/// Indexed triangle buffers for the surface of a mesh, see [`NavMesh::triangulate`]
+///
+/// Every area has 4 vertices, in the order of [`NavQuad::corners`], with the area id stored for
+/// every vertex. Coordinates are in the z-up hammer coordinate system.
+///
+/// [`NavMesh::triangulate`]: ./struct.NavMesh.html#method.triangulate
+/// [`NavQuad::corners`]: ./struct.NavQuad.html#method.corners
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NavTriangles {
+    pub positions: Vec<[f32; 3]>,
+    /// The upwards facing normal of every vertex
+    pub normals: Vec<[f32; 3]>,
+    pub area_ids: Vec<NavAreaId>,
+    /// The vertex indices of the triangles, with counter clockwise winding when seen from above
+    pub indices: Vec<u32>,
+}
+
+fn sub(a: Vector3, b: Vector3) -> [f32; 3] {
+    [a.0 - b.0, a.1 - b.1, a.2 - b.2]
+}
+
+fn normal(corner: Vector3, next: Vector3, previous: Vector3) -> [f32; 3] {
+    let (a, b) = (sub(next, corner), sub(previous, corner));
+    let cross = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    let length = (cross[0].powi(2) + cross[1].powi(2) + cross[2].powi(2)).sqrt();
+    if length > 0.0 {
+        [cross[0] / length, cross[1] / length, cross[2] / length]
+    } else {
+        [0.0, 0.0, 1.0]
+    }
+}
+
+impl NavMesh {
+    /// Split the areas into triangles for rendering
+    ///
+    /// Every area is split into two triangles along the diagonal from the north west to the
+    /// south east corner.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let triangles = mesh.triangulate();
+    /// println!("{} triangles", triangles.indices.len() / 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn triangulate(&self) -> NavTriangles {
+        let vertex_count = self.areas.len() * 4;
+        let mut triangles = NavTriangles {
+            positions: Vec::with_capacity(vertex_count),
+            normals: Vec::with_capacity(vertex_count),
+            area_ids: Vec::with_capacity(vertex_count),
+            indices: Vec::with_capacity(self.areas.len() * 6),
+        };
+
+        for (index, area) in self.areas.iter().enumerate() {
+            let corners = area.quad.corners();
+            for (corner_index, corner) in corners.iter().enumerate() {
+                triangles.positions.push([corner.0, corner.1, corner.2]);
+                triangles.normals.push(normal(
+                    *corner,
+                    corners[(corner_index + 1) % 4],
+                    corners[(corner_index + 3) % 4],
+                ));
+                triangles.area_ids.push(area.id);
+            }
+
+            let first = index as u32 * 4;
+            triangles
+                .indices
+                .extend([0, 1, 2, 0, 2, 3].iter().map(|offset| first + offset));
+        }
+
+        triangles
+    }
+}
+
+#[test]
+fn test_triangulate() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let triangles = mesh.triangulate();
+    assert_eq!(1930 * 4, triangles.positions.len());
+    assert_eq!(1930 * 4, triangles.normals.len());
+    assert_eq!(1930 * 6, triangles.indices.len());
+    assert_eq!(mesh.areas[1].id, triangles.area_ids[4]);
+    assert!(triangles
+        .indices
+        .iter()
+        .all(|index| (*index as usize) < triangles.positions.len()));
+    assert!(triangles.normals.iter().all(|normal| normal[2] > 0.0));
+}
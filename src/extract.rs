@@ -0,0 +1,100 @@
+use crate::navmesh::{NavAreaId, NavPlace};
+use crate::{NavMesh, NavMeshBuilder};
+use std::collections::HashSet;
+
+impl NavMesh {
+    /// Extract all areas of a place into a new mesh
+    ///
+    /// Connections, ladders and visibility data referencing areas outside of the place are
+    /// removed, the extracted mesh only contains the single place.
+    ///
+    /// Returns `None` if no place with the name exists
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// if let Some(spawn) = mesh.extract_place("BlueSpawn") {
+    ///     println!("the blue spawn consists of {} areas", spawn.areas.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn extract_place(&self, name: &str) -> Option<NavMesh> {
+        let place = self.places.iter().find(|place| place.name == name)?;
+
+        let outside: Vec<NavAreaId> = self
+            .areas
+            .iter()
+            .filter(|area| area.place != place.id)
+            .map(|area| area.id)
+            .collect();
+        let mut builder = NavMeshBuilder::from_mesh(self.clone());
+        builder
+            .remove_areas(&outside)
+            .expect("all removed areas exist in the mesh");
+        let mut mesh = builder.build();
+
+        mesh.ladders.retain(|ladder| {
+            [
+                ladder.top_forward_area,
+                ladder.top_left_area,
+                ladder.top_right_area,
+                ladder.top_behind_area,
+                ladder.bottom_area,
+            ]
+            .iter()
+            .any(Option::is_some)
+        });
+        let ladders: HashSet<_> = mesh.ladders.iter().map(|ladder| ladder.id).collect();
+
+        for area in &mut mesh.areas {
+            area.place = 1;
+            for direction in area.ladder_connections.0.iter_mut() {
+                direction.retain(|ladder| ladders.contains(ladder));
+            }
+        }
+        mesh.places = vec![NavPlace {
+            id: 1,
+            name: place.name.clone(),
+        }];
+        mesh.has_unnamed_areas = false;
+
+        Some(mesh)
+    }
+}
+
+#[test]
+fn test_extract_place() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    assert!(mesh.extract_place("Spawn").is_none());
+
+    let first = &mesh.areas[0];
+    let mut inside = vec![first.id];
+    inside.extend(first.connections.iter().copied());
+
+    let mut builder = NavMeshBuilder::from_mesh(mesh.clone());
+    for id in &inside {
+        builder.set_place(*id, Some("Spawn")).unwrap();
+    }
+    let mesh = builder.build();
+
+    let spawn = mesh.extract_place("Spawn").unwrap();
+    assert_eq!(inside.len(), spawn.areas.len());
+    assert_eq!(1, spawn.places.len());
+    assert!(spawn.areas.iter().all(|area| area.place == 1));
+    assert!(spawn
+        .areas
+        .iter()
+        .flat_map(|area| area.connections.iter())
+        .all(|target| inside.contains(target)));
+    assert_eq!(
+        first.connections.iter().count(),
+        spawn.areas[0].connections.iter().count()
+    );
+}
@@ -0,0 +1,160 @@
+use crate::navmesh::NavArea;
+use std::fmt;
+use std::ops::{BitAnd, BitOr, BitOrAssign};
+
+/// The attribute flags of a navigation area
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{read_mesh, NavAttributeFlags};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let mesh = read_mesh(file)?;
+/// let crouch = mesh
+///     .areas
+///     .iter()
+///     .filter(|area| area.attributes().contains(NavAttributeFlags::CROUCH))
+///     .count();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NavAttributeFlags(pub u32);
+
+impl NavAttributeFlags {
+    pub const NONE: NavAttributeFlags = NavAttributeFlags(0);
+    /// Must crouch to use this area
+    pub const CROUCH: NavAttributeFlags = NavAttributeFlags(0x1);
+    /// Must jump to traverse this area
+    pub const JUMP: NavAttributeFlags = NavAttributeFlags(0x2);
+    /// Don't do strafing or other movement while in this area
+    pub const PRECISE: NavAttributeFlags = NavAttributeFlags(0x4);
+    /// Don't jump in this area
+    pub const NO_JUMP: NavAttributeFlags = NavAttributeFlags(0x8);
+    /// Must stop when entering this area
+    pub const STOP: NavAttributeFlags = NavAttributeFlags(0x10);
+    /// Must run to traverse this area
+    pub const RUN: NavAttributeFlags = NavAttributeFlags(0x20);
+    /// Must walk to traverse this area
+    pub const WALK: NavAttributeFlags = NavAttributeFlags(0x40);
+    /// Avoid this area unless alternatives are too dangerous
+    pub const AVOID: NavAttributeFlags = NavAttributeFlags(0x80);
+    /// Area may become blocked, and should be periodically checked
+    pub const TRANSIENT: NavAttributeFlags = NavAttributeFlags(0x100);
+    /// Area should not be considered for hiding spot generation
+    pub const DONT_HIDE: NavAttributeFlags = NavAttributeFlags(0x200);
+    /// Bots hiding in this area should stand
+    pub const STAND: NavAttributeFlags = NavAttributeFlags(0x400);
+    /// Hostages shouldn't use this area
+    pub const NO_HOSTAGES: NavAttributeFlags = NavAttributeFlags(0x800);
+    /// This area represents stairs
+    pub const STAIRS: NavAttributeFlags = NavAttributeFlags(0x1000);
+    /// Don't merge this area with adjacent areas
+    pub const NO_MERGE: NavAttributeFlags = NavAttributeFlags(0x2000);
+    /// This area is the top of an obstacle
+    pub const OBSTACLE_TOP: NavAttributeFlags = NavAttributeFlags(0x4000);
+    /// This area is adjacent to a drop of at least the crouch height
+    pub const CLIFF: NavAttributeFlags = NavAttributeFlags(0x8000);
+    /// This area has an elevator on it
+    pub const HAS_ELEVATOR: NavAttributeFlags = NavAttributeFlags(0x4000_0000);
+    /// This area is blocked by a nav blocker entity
+    pub const NAV_BLOCKER: NavAttributeFlags = NavAttributeFlags(0x8000_0000);
+
+    /// Whether all flags of `other` are set
+    pub fn contains(self, other: NavAttributeFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether any flag of `other` is set
+    pub fn intersects(self, other: NavAttributeFlags) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    /// Whether no flags are set
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl BitOr for NavAttributeFlags {
+    type Output = NavAttributeFlags;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        NavAttributeFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for NavAttributeFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0
+    }
+}
+
+impl BitAnd for NavAttributeFlags {
+    type Output = NavAttributeFlags;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        NavAttributeFlags(self.0 & rhs.0)
+    }
+}
+
+impl From<u32> for NavAttributeFlags {
+    fn from(flags: u32) -> Self {
+        NavAttributeFlags(flags)
+    }
+}
+
+impl From<NavAttributeFlags> for u32 {
+    fn from(flags: NavAttributeFlags) -> Self {
+        flags.0
+    }
+}
+
+impl fmt::Display for NavAttributeFlags {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#X}", self.0)
+    }
+}
+
+/// A filter for areas based on their attribute flags
+///
+/// An area matches the filter if all `required` flags and none of the `excluded` flags are set
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct NavFlagFilter {
+    pub required: NavAttributeFlags,
+    pub excluded: NavAttributeFlags,
+}
+
+impl NavFlagFilter {
+    /// Only match areas with all of the flags set
+    pub fn with(flags: NavAttributeFlags) -> Self {
+        NavFlagFilter {
+            required: flags,
+            excluded: NavAttributeFlags::NONE,
+        }
+    }
+
+    /// Only match areas with none of the flags set
+    pub fn without(flags: NavAttributeFlags) -> Self {
+        NavFlagFilter {
+            required: NavAttributeFlags::NONE,
+            excluded: flags,
+        }
+    }
+
+    /// Whether an area matches the filter
+    pub fn matches(&self, area: &NavArea) -> bool {
+        let flags = area.attributes();
+        flags.contains(self.required) && !flags.intersects(self.excluded)
+    }
+}
+
+impl NavArea {
+    /// Get the attribute flags of the area
+    pub fn attributes(&self) -> NavAttributeFlags {
+        NavAttributeFlags(self.flags)
+    }
+}
@@ -0,0 +1,223 @@
+use crate::navmesh::{NavArea, Vector3};
+use crate::NavTree;
+
+fn dot(a: Vector3, b: Vector3) -> f32 {
+    a.0 * b.0 + a.1 * b.1 + a.2 * b.2
+}
+
+fn cross(a: Vector3, b: Vector3) -> Vector3 {
+    Vector3(
+        a.1 * b.2 - a.2 * b.1,
+        a.2 * b.0 - a.0 * b.2,
+        a.0 * b.1 - a.1 * b.0,
+    )
+}
+
+/// A plane, given by it's normal and signed distance from the origin
+///
+/// Points on the side the normal points towards are considered inside the plane
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Plane {
+    pub normal: Vector3,
+    pub distance: f32,
+}
+
+impl Plane {
+    pub fn new(normal: Vector3, distance: f32) -> Self {
+        Plane { normal, distance }
+    }
+
+    /// Create a plane through a point, facing in the direction of the normal
+    pub fn from_point(normal: Vector3, point: Vector3) -> Self {
+        Plane {
+            normal,
+            distance: -dot(normal, point),
+        }
+    }
+
+    /// Get the signed distance from the plane to a point, scaled by the length of the normal
+    ///
+    /// The distance is positive for points inside the plane
+    pub fn signed_distance(&self, point: Vector3) -> f32 {
+        dot(self.normal, point) + self.distance
+    }
+}
+
+/// The view frustum of a camera, given by it's six planes with their normals pointing inwards
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frustum {
+    pub left: Plane,
+    pub right: Plane,
+    pub bottom: Plane,
+    pub top: Plane,
+    pub near: Plane,
+    pub far: Plane,
+}
+
+/// Find the point where three planes meet
+fn intersection(a: &Plane, b: &Plane, c: &Plane) -> Option<Vector3> {
+    let bc = cross(b.normal, c.normal);
+    let denominator = dot(a.normal, bc);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let ca = cross(c.normal, a.normal);
+    let ab = cross(a.normal, b.normal);
+    Some(Vector3(
+        -(a.distance * bc.0 + b.distance * ca.0 + c.distance * ab.0) / denominator,
+        -(a.distance * bc.1 + b.distance * ca.1 + c.distance * ab.1) / denominator,
+        -(a.distance * bc.2 + b.distance * ca.2 + c.distance * ab.2) / denominator,
+    ))
+}
+
+impl Frustum {
+    /// Get all six planes of the frustum
+    pub fn planes(&self) -> [Plane; 6] {
+        [
+            self.left,
+            self.right,
+            self.bottom,
+            self.top,
+            self.near,
+            self.far,
+        ]
+    }
+
+    /// Get the eight corners of the frustum
+    ///
+    /// Returns `None` if the frustum is unbounded
+    pub fn corners(&self) -> Option<[Vector3; 8]> {
+        let mut corners = [Vector3::default(); 8];
+        let mut index = 0;
+        for depth in &[self.near, self.far] {
+            for side in &[self.left, self.right] {
+                for height in &[self.bottom, self.top] {
+                    corners[index] = intersection(depth, side, height)?;
+                    index += 1;
+                }
+            }
+        }
+        Some(corners)
+    }
+
+    /// Whether an area might be inside the frustum
+    ///
+    /// Areas are only rejected when all corners of the area are outside one of the planes, so
+    /// some areas near the edges of the frustum might be included while being outside it.
+    pub fn intersects(&self, area: &NavArea) -> bool {
+        let corners = area.quad.corners();
+        self.planes().iter().all(|plane| {
+            corners
+                .iter()
+                .any(|corner| plane.signed_distance(*corner) >= 0.0)
+        })
+    }
+}
+
+impl NavTree {
+    /// Find all navigation areas intersecting the view frustum of a camera
+    ///
+    /// Like [`Frustum::intersects`], areas close to the edges of the frustum might be included
+    /// while not being visible.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Frustum, Plane, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// // a camera at the origin, looking along the x axis with a 90 degree field of view
+    /// let camera = Vector3(0.0, 0.0, 0.0);
+    /// let frustum = Frustum {
+    ///     left: Plane::from_point(Vector3(1.0, 1.0, 0.0), camera),
+    ///     right: Plane::from_point(Vector3(1.0, -1.0, 0.0), camera),
+    ///     bottom: Plane::from_point(Vector3(1.0, 0.0, 1.0), camera),
+    ///     top: Plane::from_point(Vector3(1.0, 0.0, -1.0), camera),
+    ///     near: Plane::from_point(Vector3(1.0, 0.0, 0.0), Vector3(1.0, 0.0, 0.0)),
+    ///     far: Plane::from_point(Vector3(-1.0, 0.0, 0.0), Vector3(4096.0, 0.0, 0.0)),
+    /// };
+    /// for area in tree.query_frustum(&frustum) {
+    ///     println!("area {} is in view", area.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Frustum::intersects`]: ./struct.Frustum.html#method.intersects
+    pub fn query_frustum<'a>(
+        &'a self,
+        frustum: &'a Frustum,
+    ) -> impl Iterator<Item = &'a NavArea> + 'a {
+        let (min, max) = match frustum.corners() {
+            Some(corners) => corners.iter().fold(
+                ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN)),
+                |(min, max), corner| {
+                    (
+                        (min.0.min(corner.0), min.1.min(corner.1)),
+                        (max.0.max(corner.0), max.1.max(corner.1)),
+                    )
+                },
+            ),
+            None => {
+                let (min, max) = self.bounds();
+                ((min.0, min.1), (max.0, max.1))
+            }
+        };
+
+        self.query_rect((min.0 - 1.0, min.1 - 1.0), (max.0 + 1.0, max.1 + 1.0))
+            .filter(move |area| frustum.intersects(area))
+    }
+}
+
+#[test]
+fn test_query_frustum() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    // a box shaped frustum
+    let (min, max) = (Vector3(-500.0, -500.0, 0.0), Vector3(500.0, 500.0, 200.0));
+    let frustum = Frustum {
+        left: Plane::from_point(Vector3(1.0, 0.0, 0.0), min),
+        right: Plane::from_point(Vector3(-1.0, 0.0, 0.0), max),
+        bottom: Plane::from_point(Vector3(0.0, 0.0, 1.0), min),
+        top: Plane::from_point(Vector3(0.0, 0.0, -1.0), max),
+        near: Plane::from_point(Vector3(0.0, 1.0, 0.0), min),
+        far: Plane::from_point(Vector3(0.0, -1.0, 0.0), max),
+    };
+    let corners = frustum.corners().unwrap();
+    assert!(corners.contains(&min));
+    assert!(corners.contains(&max));
+
+    let mut found: Vec<_> = tree.query_frustum(&frustum).map(|area| area.id).collect();
+    let mut expected: Vec<_> = tree
+        .areas()
+        .filter(|area| {
+            let corners = area.quad.corners();
+            let (low, high) = corners
+                .iter()
+                .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), corner| {
+                    (low.min(corner.2), high.max(corner.2))
+                });
+            area.quad.south_east.0 >= min.0
+                && area.quad.north_west.0 <= max.0
+                && area.quad.south_east.1 >= min.1
+                && area.quad.north_west.1 <= max.1
+                && high >= min.2
+                && low <= max.2
+        })
+        .map(|area| area.id)
+        .collect();
+    found.sort();
+    expected.sort();
+    assert!(!found.is_empty());
+    assert_eq!(expected, found);
+
+    // a frustum above the map
+    let above = Frustum {
+        bottom: Plane::from_point(Vector3(0.0, 0.0, 1.0), Vector3(0.0, 0.0, 100_000.0)),
+        ..frustum
+    };
+    assert_eq!(0, tree.query_frustum(&above).count());
+}
@@ -0,0 +1,189 @@
+use crate::navmesh::{NavAreaId, Vector3};
+use crate::NavTree;
+
+/// Twice the signed area of the triangle, positive when `c` is left of the line from `a` to `b`
+fn cross(a: Vector3, b: Vector3, c: Vector3) -> f32 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn same_point(a: Vector3, b: Vector3) -> bool {
+    (a.0 - b.0).abs() < 0.001 && (a.1 - b.1).abs() < 0.001
+}
+
+/// Find the shortest path through a list of portals, given as their left and right ends
+///
+/// The first and last portal are expected to be the start and end point of the path, this is
+/// the "simple stupid funnel algorithm", which shrinks a funnel from the last corner of the path
+/// for every portal and adds a corner whenever one side of the funnel crosses the other.
+pub(crate) fn funnel(portals: &[(Vector3, Vector3)]) -> Vec<Vector3> {
+    let mut points = Vec::new();
+    let (mut apex, mut left, mut right) = match portals.first() {
+        Some((start, _)) => (*start, *start, *start),
+        None => return points,
+    };
+    let (mut left_index, mut right_index) = (0, 0);
+    points.push(apex);
+
+    let mut index = 1;
+    while index < portals.len() {
+        let (portal_left, portal_right) = portals[index];
+
+        // try to narrow the right side of the funnel
+        if cross(apex, right, portal_right) >= 0.0 {
+            if same_point(apex, right) || cross(apex, left, portal_right) < 0.0 {
+                right = portal_right;
+                right_index = index;
+            } else {
+                // the right side crosses the left side, the left side becomes a corner
+                points.push(left);
+                apex = left;
+                right = apex;
+                right_index = left_index;
+                index = left_index + 1;
+                continue;
+            }
+        }
+
+        // try to narrow the left side of the funnel
+        if cross(apex, left, portal_left) <= 0.0 {
+            if same_point(apex, left) || cross(apex, right, portal_left) > 0.0 {
+                left = portal_left;
+                left_index = index;
+            } else {
+                // the left side crosses the right side, the right side becomes a corner
+                points.push(right);
+                apex = right;
+                left = apex;
+                left_index = right_index;
+                index = right_index + 1;
+                continue;
+            }
+        }
+
+        index += 1;
+    }
+
+    if let Some((end, _)) = portals.last() {
+        if !points.last().is_some_and(|last| same_point(*last, *end)) {
+            points.push(*end);
+        }
+    }
+    points
+}
+
+impl NavTree {
+    /// Turn a path through areas into the shortest line between two points through the areas
+    ///
+    /// The line only turns at the corners of the shared edges between the areas. Areas that
+    /// aren't next to each other, like areas connected by a ladder, are passed through their
+    /// center.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let from = tree[1.into()].center();
+    /// let to = tree[250.into()].center();
+    /// if let Some(areas) = tree.find_path(1.into(), 250.into()) {
+    ///     let waypoints = tree.smooth_path(from, to, &areas);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn smooth_path(&self, from: Vector3, to: Vector3, areas: &[NavAreaId]) -> Vec<Vector3> {
+        let mut portals = Vec::with_capacity(areas.len() + 1);
+        portals.push((from, from));
+        portals.extend(self.portals(areas));
+        portals.push((to, to));
+        funnel(&portals)
+    }
+
+    /// Get the shared edges between consecutive areas, for areas that don't share an edge the
+    /// center of the next area is used
+    pub(crate) fn portals(&self, areas: &[NavAreaId]) -> Vec<(Vector3, Vector3)> {
+        areas
+            .windows(2)
+            .filter_map(|pair| match self.portal(pair[0], pair[1]) {
+                Some(portal) => Some(portal),
+                None => self
+                    .area(pair[1])
+                    .map(|area| (area.center(), area.center())),
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn test_funnel() {
+    let point = |x: f32, y: f32| Vector3(x, y, 0.0);
+
+    // a straight corridor along the positive y axis, with left towards negative x
+    let straight = [
+        (point(0.0, 0.0), point(0.0, 0.0)),
+        (point(-10.0, 10.0), point(10.0, 10.0)),
+        (point(-10.0, 20.0), point(10.0, 20.0)),
+        (point(0.0, 30.0), point(0.0, 30.0)),
+    ];
+    assert_eq!(vec![point(0.0, 0.0), point(0.0, 30.0)], funnel(&straight));
+
+    // a corridor turning right, the path cuts the inner corner
+    let turn = [
+        (point(0.0, 0.0), point(0.0, 0.0)),
+        (point(-10.0, 10.0), point(10.0, 10.0)),
+        (point(10.0, 30.0), point(10.0, 10.0)),
+        (point(40.0, 20.0), point(40.0, 20.0)),
+    ];
+    assert_eq!(
+        vec![point(0.0, 0.0), point(10.0, 10.0), point(40.0, 20.0)],
+        funnel(&turn)
+    );
+}
+
+#[test]
+fn test_smooth_path() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let from = tree.nearest_area(-1000.0, -1030.0).unwrap().0.center();
+    let to = tree.nearest_area(1500.0, 200.0).unwrap().0.center();
+    let areas = tree.find_path_points(from, to).unwrap().areas;
+    let smooth = tree.smooth_path(from, to, &areas);
+
+    assert_eq!(Some(&from), smooth.first());
+    assert_eq!(Some(&to), smooth.last());
+    assert!(smooth.len() <= areas.len() + 1);
+
+    // the path through the middle of the shared edges
+    let portals: Vec<_> = areas
+        .windows(2)
+        .map(|pair| tree.portal(pair[0], pair[1]).unwrap())
+        .collect();
+    let mut middle = vec![from];
+    middle.extend(portals.iter().map(|(left, right)| {
+        Vector3(
+            (left.0 + right.0) / 2.0,
+            (left.1 + right.1) / 2.0,
+            (left.2 + right.2) / 2.0,
+        )
+    }));
+    middle.push(to);
+
+    let length = |points: &[Vector3]| -> f32 {
+        points
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum()
+    };
+    assert!(length(&smooth) <= length(&middle) + 0.01);
+
+    // every corner of the path is an end of a shared edge
+    for corner in &smooth[1..smooth.len() - 1] {
+        assert!(portals
+            .iter()
+            .any(|(left, right)| same_point(*corner, *left) || same_point(*corner, *right)));
+    }
+}
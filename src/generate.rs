@@ -0,0 +1,574 @@
+use crate::navmesh::{NavAreaId, NavDirection, NavQuad, Vector3};
+use crate::{NavGame, NavMesh, NavMeshBuilder};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Options for [`generate_mesh`]
+///
+/// [`generate_mesh`]: ./fn.generate_mesh.html
+#[derive(Debug, Clone)]
+pub struct GeneratorOptions {
+    /// The x/y position to start sampling from
+    pub start: (f32, f32),
+    /// The size of the grid cells that are sampled
+    pub cell_size: f32,
+    /// The maximum height difference between the corners of a cell for it to be walkable
+    pub max_step: f32,
+    /// The number of passes that merge neighbouring cells into larger areas
+    pub merge_passes: usize,
+    /// The maximum height difference between a sampled point and the merged area
+    pub merge_tolerance: f32,
+    /// The maximum number of cells to sample before stopping
+    pub max_cells: usize,
+    /// The game to generate the mesh for
+    pub game: NavGame,
+}
+
+impl GeneratorOptions {
+    /// Create options with defaults matching the engine's nav generation, starting at a position
+    pub fn new(start_x: f32, start_y: f32) -> Self {
+        GeneratorOptions {
+            start: (start_x, start_y),
+            cell_size: 25.0,
+            max_step: 18.0,
+            merge_passes: 8,
+            merge_tolerance: 2.0,
+            max_cells: 100_000,
+            game: NavGame::Unknown,
+        }
+    }
+}
+
+/// A rectangle of grid cells, with the end coordinates exclusive
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+struct CellRect {
+    min: (i32, i32),
+    max: (i32, i32),
+}
+
+struct Generator<F> {
+    sampler: F,
+    options: GeneratorOptions,
+    samples: HashMap<(i32, i32), Option<f32>>,
+}
+
+impl<F: FnMut(f32, f32) -> Option<f32>> Generator<F> {
+    fn sample(&mut self, point: (i32, i32)) -> Option<f32> {
+        let size = self.options.cell_size;
+        let sampler = &mut self.sampler;
+        *self
+            .samples
+            .entry(point)
+            .or_insert_with(|| sampler(point.0 as f32 * size, point.1 as f32 * size))
+    }
+
+    fn is_walkable(&mut self, cell: (i32, i32)) -> bool {
+        let corners = [
+            self.sample(cell),
+            self.sample((cell.0 + 1, cell.1)),
+            self.sample((cell.0 + 1, cell.1 + 1)),
+            self.sample((cell.0, cell.1 + 1)),
+        ];
+        if corners.iter().any(Option::is_none) {
+            return false;
+        }
+        let heights = corners.iter().flatten();
+        let min = heights.clone().fold(f32::INFINITY, |a, b| a.min(*b));
+        let max = heights.fold(f32::NEG_INFINITY, |a, b| a.max(*b));
+        max - min <= self.options.max_step
+    }
+
+    /// Find all walkable cells reachable from the start position
+    fn flood(&mut self) -> HashSet<(i32, i32)> {
+        let size = self.options.cell_size;
+        let start = (
+            (self.options.start.0 / size).floor() as i32,
+            (self.options.start.1 / size).floor() as i32,
+        );
+
+        let mut cells = HashSet::new();
+        let mut queue = VecDeque::new();
+        if self.is_walkable(start) {
+            cells.insert(start);
+            queue.push_back(start);
+        }
+
+        while let Some(cell) = queue.pop_front() {
+            for (dx, dy) in [(1, 0), (-1, 0), (0, 1), (0, -1)].iter() {
+                if cells.len() >= self.options.max_cells {
+                    return cells;
+                }
+                let next = (cell.0 + dx, cell.1 + dy);
+                if !cells.contains(&next) && self.is_walkable(next) {
+                    cells.insert(next);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        cells
+    }
+
+    fn quad(&mut self, rect: CellRect) -> NavQuad {
+        let size = self.options.cell_size;
+        let (min, max) = (rect.min, rect.max);
+        let mut height = |point| self.sample(point).unwrap_or_default();
+        NavQuad {
+            north_west: Vector3(min.0 as f32 * size, min.1 as f32 * size, height(min)),
+            south_east: Vector3(max.0 as f32 * size, max.1 as f32 * size, height(max)),
+            north_east_z: height((max.0, min.1)),
+            south_west_z: height((min.0, max.1)),
+        }
+    }
+
+    /// Check if all samples inside a rectangle lie on the surface of the area
+    fn is_flat(&mut self, rect: CellRect) -> bool {
+        let quad = self.quad(rect);
+        let size = self.options.cell_size;
+        for x in rect.min.0..=rect.max.0 {
+            for y in rect.min.1..=rect.max.1 {
+                let sampled = match self.sample((x, y)) {
+                    Some(sampled) => sampled,
+                    None => return false,
+                };
+                let expected = quad.interpolated_z(x as f32 * size, y as f32 * size);
+                if (sampled - expected).abs() > self.options.merge_tolerance {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Merge pairs of neighbouring rectangles with matching edges
+    fn merge_pass(&mut self, rects: Vec<CellRect>) -> Vec<CellRect> {
+        let mut rects = rects;
+        for &horizontal in [true, false].iter() {
+            let mut by_min: HashMap<(i32, i32), usize> = rects
+                .iter()
+                .enumerate()
+                .map(|(index, rect)| (rect.min, index))
+                .collect();
+            let mut merged: Vec<Option<CellRect>> = rects.iter().copied().map(Some).collect();
+
+            for index in 0..merged.len() {
+                let rect = match merged[index] {
+                    Some(rect) => rect,
+                    None => continue,
+                };
+                let neighbour_min = if horizontal {
+                    (rect.max.0, rect.min.1)
+                } else {
+                    (rect.min.0, rect.max.1)
+                };
+                let neighbour_index = match by_min.get(&neighbour_min) {
+                    Some(neighbour_index) => *neighbour_index,
+                    None => continue,
+                };
+                let neighbour = match merged[neighbour_index] {
+                    Some(neighbour) => neighbour,
+                    None => continue,
+                };
+                let matches = if horizontal {
+                    neighbour.max.1 == rect.max.1
+                } else {
+                    neighbour.max.0 == rect.max.0
+                };
+                let union = CellRect {
+                    min: rect.min,
+                    max: neighbour.max,
+                };
+                if matches && self.is_flat(union) {
+                    merged[index] = Some(union);
+                    merged[neighbour_index] = None;
+                    by_min.remove(&neighbour_min);
+                }
+            }
+
+            rects = merged.into_iter().flatten().collect();
+        }
+        rects
+    }
+}
+
+/// Generate a navigation mesh by sampling the ground height
+///
+/// Starting at the start position, the sampler is called for the corners of a grid of cells to
+/// get the height of the walkable ground at that position, or `None` if the position isn't
+/// walkable. All walkable cells reachable from the start are merged into larger areas where
+/// the ground is flat enough and connected to their neighbours.
+///
+/// Since the sampler returns a single height for every position, areas above each other are
+/// not supported.
+///
+/// ## Examples
+///
+/// ```
+/// use sourcenav::{generate_mesh, GeneratorOptions};
+///
+/// // a flat 500x500 floor
+/// let mesh = generate_mesh(
+///     |x, y| if (0.0..=500.0).contains(&x) && (0.0..=500.0).contains(&y) { Some(0.0) } else { None },
+///     GeneratorOptions::new(10.0, 10.0),
+/// );
+/// assert_eq!(1, mesh.areas.len());
+/// ```
+pub fn generate_mesh(
+    sampler: impl FnMut(f32, f32) -> Option<f32>,
+    options: GeneratorOptions,
+) -> NavMesh {
+    let mut generator = Generator {
+        sampler,
+        options,
+        samples: HashMap::new(),
+    };
+
+    let mut cells: Vec<(i32, i32)> = generator.flood().into_iter().collect();
+    cells.sort_unstable();
+    let mut rects: Vec<CellRect> = cells
+        .into_iter()
+        .map(|cell| CellRect {
+            min: cell,
+            max: (cell.0 + 1, cell.1 + 1),
+        })
+        .collect();
+
+    for _ in 0..generator.options.merge_passes {
+        let count = rects.len();
+        rects = generator.merge_pass(rects);
+        if rects.len() == count {
+            break;
+        }
+    }
+
+    let mut owners = HashMap::new();
+    for (index, rect) in rects.iter().enumerate() {
+        for x in rect.min.0..rect.max.0 {
+            for y in rect.min.1..rect.max.1 {
+                owners.insert((x, y), index);
+            }
+        }
+    }
+
+    let mut builder = NavMeshBuilder::new(generator.options.game);
+    let id = |index: usize| NavAreaId::from(index as u32 + 1);
+    for (index, rect) in rects.iter().enumerate() {
+        let quad = generator.quad(*rect);
+        // ids are unique and all cells have a size
+        let _ = builder.add_area_with_id(id(index), quad);
+    }
+
+    for (index, rect) in rects.iter().enumerate() {
+        let borders = [
+            (
+                NavDirection::North,
+                (rect.min.0..rect.max.0)
+                    .map(|x| (x, rect.min.1 - 1))
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                NavDirection::East,
+                (rect.min.1..rect.max.1).map(|y| (rect.max.0, y)).collect(),
+            ),
+            (
+                NavDirection::South,
+                (rect.min.0..rect.max.0).map(|x| (x, rect.max.1)).collect(),
+            ),
+            (
+                NavDirection::West,
+                (rect.min.1..rect.max.1)
+                    .map(|y| (rect.min.0 - 1, y))
+                    .collect(),
+            ),
+        ];
+        for (direction, cells) in borders.iter() {
+            let mut neighbours: Vec<usize> = cells
+                .iter()
+                .filter_map(|cell| owners.get(cell).copied())
+                .collect();
+            neighbours.dedup();
+            for neighbour in neighbours {
+                let _ = builder.connect_one_way(id(index), id(neighbour), *direction);
+            }
+        }
+    }
+
+    builder.build()
+}
+
+/// The minimum z component of the normal of a walkable surface, matching `nav_slope_limit`
+const SLOPE_LIMIT: f32 = 0.7;
+
+/// Generate a navigation mesh from level geometry
+///
+/// The geometry is given as a list of triangles, for example the brush faces and displacements
+/// of a map. Triangles that are too steep to walk on are ignored, for every position the
+/// highest walkable surface is used. See [`generate_mesh`] for how the areas are generated.
+///
+/// ## Examples
+///
+/// ```
+/// use sourcenav::{generate_mesh_from_triangles, GeneratorOptions, Vector3};
+///
+/// let floor = [
+///     [Vector3(0.0, 0.0, 0.0), Vector3(500.0, 0.0, 0.0), Vector3(500.0, 500.0, 0.0)],
+///     [Vector3(0.0, 0.0, 0.0), Vector3(500.0, 500.0, 0.0), Vector3(0.0, 500.0, 0.0)],
+/// ];
+/// let mesh = generate_mesh_from_triangles(&floor, GeneratorOptions::new(10.0, 10.0));
+/// assert_eq!(1, mesh.areas.len());
+/// ```
+///
+/// [`generate_mesh`]: ./fn.generate_mesh.html
+pub fn generate_mesh_from_triangles(
+    triangles: &[[Vector3; 3]],
+    options: GeneratorOptions,
+) -> NavMesh {
+    let bucket_size = options.cell_size * 4.0;
+    let bucket = |x: f32, y: f32| {
+        (
+            (x / bucket_size).floor() as i32,
+            (y / bucket_size).floor() as i32,
+        )
+    };
+
+    // index the walkable triangles by the buckets their bounding box covers
+    let mut buckets: HashMap<(i32, i32), Vec<&[Vector3; 3]>> = HashMap::new();
+    for triangle in triangles {
+        let [a, b, c] = triangle;
+        let (u, v) = (
+            Vector3(b.0 - a.0, b.1 - a.1, b.2 - a.2),
+            Vector3(c.0 - a.0, c.1 - a.1, c.2 - a.2),
+        );
+        let normal = Vector3(
+            u.1 * v.2 - u.2 * v.1,
+            u.2 * v.0 - u.0 * v.2,
+            u.0 * v.1 - u.1 * v.0,
+        );
+        let length = (normal.0 * normal.0 + normal.1 * normal.1 + normal.2 * normal.2).sqrt();
+        if length == 0.0 || (normal.2 / length).abs() < SLOPE_LIMIT {
+            continue;
+        }
+
+        let min = bucket(a.0.min(b.0).min(c.0), a.1.min(b.1).min(c.1));
+        let max = bucket(a.0.max(b.0).max(c.0), a.1.max(b.1).max(c.1));
+        for x in min.0..=max.0 {
+            for y in min.1..=max.1 {
+                buckets.entry((x, y)).or_default().push(triangle);
+            }
+        }
+    }
+
+    let sampler = |x: f32, y: f32| {
+        buckets
+            .get(&bucket(x, y))?
+            .iter()
+            .filter_map(|triangle| triangle_height(triangle, x, y))
+            .fold(None, |highest: Option<f32>, z| {
+                Some(highest.map_or(z, |highest| highest.max(z)))
+            })
+    };
+
+    generate_mesh(sampler, options)
+}
+
+/// Generate a navigation mesh from the geometry of a compiled map
+///
+/// The visible faces of the world are triangulated and passed to
+/// [`generate_mesh_from_triangles`], displacements are triangulated from their subdivided grid.
+/// Brush entities and props are not included.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{generate_mesh_from_bsp, GeneratorOptions};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/map.bsp")?;
+/// let bsp = vbsp::Bsp::read(&file)?;
+/// let mesh = generate_mesh_from_bsp(&bsp, GeneratorOptions::new(0.0, 0.0));
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`generate_mesh_from_triangles`]: ./fn.generate_mesh_from_triangles.html
+#[cfg(feature = "bsp")]
+pub fn generate_mesh_from_bsp(bsp: &vbsp::Bsp, options: GeneratorOptions) -> NavMesh {
+    let position = |vector: vbsp::Vector| Vector3(vector.x, vector.y, vector.z);
+
+    let mut triangles = Vec::new();
+    if let Some(world) = bsp.models().next() {
+        for face in world.faces().filter(|face| face.is_visible()) {
+            match face.displacement() {
+                Some(displacement) => {
+                    // the displaced vertices are a list of triangles
+                    let vertices: Vec<Vector3> = displacement
+                        .triangulated_displaced_vertices()
+                        .map(position)
+                        .collect();
+                    triangles.extend(
+                        vertices
+                            .chunks_exact(3)
+                            .map(|triangle| [triangle[0], triangle[1], triangle[2]]),
+                    );
+                }
+                None => {
+                    let polygon: Vec<Vector3> = face
+                        .vertices()
+                        .map(|vertex| position(vertex.position))
+                        .collect();
+                    triangles.extend(fan_triangles(&polygon));
+                }
+            }
+        }
+    }
+
+    generate_mesh_from_triangles(&triangles, options)
+}
+
+/// Split a convex polygon into triangles that all share the first vertex
+#[cfg(feature = "bsp")]
+fn fan_triangles(polygon: &[Vector3]) -> impl Iterator<Item = [Vector3; 3]> + '_ {
+    polygon
+        .windows(2)
+        .skip(1)
+        .map(move |pair| [polygon[0], pair[0], pair[1]])
+}
+
+/// Get the height of a triangle at a x/y position, if the position is inside the triangle
+fn triangle_height([a, b, c]: &[Vector3; 3], x: f32, y: f32) -> Option<f32> {
+    const EPSILON: f32 = 0.001;
+    let denominator = (b.1 - c.1) * (a.0 - c.0) + (c.0 - b.0) * (a.1 - c.1);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let wa = ((b.1 - c.1) * (x - c.0) + (c.0 - b.0) * (y - c.1)) / denominator;
+    let wb = ((c.1 - a.1) * (x - c.0) + (a.0 - c.0) * (y - c.1)) / denominator;
+    let wc = 1.0 - wa - wb;
+    if wa < -EPSILON || wb < -EPSILON || wc < -EPSILON {
+        return None;
+    }
+    Some(wa * a.2 + wb * b.2 + wc * c.2)
+}
+
+#[test]
+fn test_generate() {
+    let floor = |x: f32, y: f32| {
+        if (0.0..=500.0).contains(&x) && (0.0..=500.0).contains(&y) {
+            // a wall of 100 units halfway
+            Some(if x > 250.0 { 100.0 } else { 0.0 })
+        } else {
+            None
+        }
+    };
+
+    let options = GeneratorOptions {
+        merge_passes: 0,
+        ..GeneratorOptions::new(10.0, 10.0)
+    };
+    let mesh = generate_mesh(floor, options);
+    assert_eq!(10 * 20, mesh.areas.len());
+    assert!(mesh.is_consistent(None));
+
+    let mesh = generate_mesh(floor, GeneratorOptions::new(10.0, 10.0));
+    assert_eq!(1, mesh.areas.len());
+    assert_eq!(250.0, mesh.areas[0].quad.width());
+
+    // a slope is merged into a single sloped area
+    let slope = |x: f32, y: f32| {
+        if (0.0..=500.0).contains(&x) && (0.0..=100.0).contains(&y) {
+            Some(x / 4.0)
+        } else {
+            None
+        }
+    };
+    let mesh = generate_mesh(slope, GeneratorOptions::new(10.0, 10.0));
+    assert_eq!(1, mesh.areas.len());
+    assert_eq!(125.0, mesh.areas[0].quad.south_east.2);
+
+    // connected areas are connected in both directions
+    let options = GeneratorOptions {
+        merge_passes: 1,
+        ..GeneratorOptions::new(10.0, 10.0)
+    };
+    let mesh = generate_mesh(slope, options);
+    let view = mesh.view();
+    for area in &mesh.areas {
+        for direction in NavDirection::ALL.iter() {
+            for target in &area.connections[*direction] {
+                let target = view.area(*target).unwrap();
+                assert!(target.connections[direction.opposite()].contains(&area.id));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_generate_from_triangles() {
+    let quad = |min: (f32, f32), max: (f32, f32), z: f32| {
+        vec![
+            [
+                Vector3(min.0, min.1, z),
+                Vector3(max.0, min.1, z),
+                Vector3(max.0, max.1, z),
+            ],
+            [
+                Vector3(min.0, min.1, z),
+                Vector3(max.0, max.1, z),
+                Vector3(min.0, max.1, z),
+            ],
+        ]
+    };
+
+    let mut triangles = quad((0.0, 0.0), (500.0, 500.0), 0.0);
+    // a platform that is too high to step on
+    triangles.extend(quad((200.0, 200.0), (300.0, 300.0), 64.0));
+    // a wall, which is not walkable
+    triangles.push([
+        Vector3(0.0, 100.0, 0.0),
+        Vector3(100.0, 100.0, 0.0),
+        Vector3(100.0, 100.0, 100.0),
+    ]);
+
+    let options = GeneratorOptions {
+        merge_passes: 0,
+        ..GeneratorOptions::new(10.0, 10.0)
+    };
+    let mesh = generate_mesh_from_triangles(&triangles, options);
+    // the platform and the cells around it are not reachable
+    assert_eq!(20 * 20 - 6 * 6, mesh.areas.len());
+    assert!(mesh
+        .areas
+        .iter()
+        .all(|area| area.quad.north_west.2 == 0.0 && area.quad.south_east.2 == 0.0));
+}
+
+#[cfg(feature = "bsp")]
+#[test]
+fn test_fan_triangles() {
+    // a brush face is a convex polygon, usually a quad
+    let quad = [
+        Vector3(0.0, 0.0, 0.0),
+        Vector3(500.0, 0.0, 0.0),
+        Vector3(500.0, 500.0, 0.0),
+        Vector3(0.0, 500.0, 0.0),
+    ];
+    let triangles: Vec<_> = fan_triangles(&quad).collect();
+    assert_eq!(
+        vec![[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]],
+        triangles
+    );
+
+    let mesh = generate_mesh_from_triangles(&triangles, GeneratorOptions::new(10.0, 490.0));
+    assert_eq!(1, mesh.areas.len());
+    let area = &mesh.areas[0].quad;
+    assert_eq!((0.0, 0.0), (area.north_west.0, area.north_west.1));
+    assert_eq!((500.0, 500.0), (area.south_east.0, area.south_east.1));
+
+    let hexagon: Vec<Vector3> = (0..6)
+        .map(|corner| {
+            let angle = corner as f32 * std::f32::consts::PI / 3.0;
+            Vector3(angle.cos() * 100.0, angle.sin() * 100.0, 0.0)
+        })
+        .collect();
+    assert_eq!(4, fan_triangles(&hexagon).count());
+    assert_eq!(0, fan_triangles(&hexagon[..2]).count());
+}
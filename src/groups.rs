@@ -0,0 +1,223 @@
+use crate::navmesh::NavAreaId;
+use crate::NavTree;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// The areas of a connection, with the lowest id first
+pub(crate) fn connection_key(a: NavAreaId, b: NavAreaId) -> (NavAreaId, NavAreaId) {
+    (a.min(b), a.max(b))
+}
+
+#[derive(Debug, Clone, Default)]
+struct ConnectionGroup {
+    connections: HashSet<(NavAreaId, NavAreaId)>,
+    enabled: bool,
+}
+
+/// Named groups of connections that can be disabled together
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConnectionGroups {
+    groups: BTreeMap<String, ConnectionGroup>,
+    /// The number of disabled groups every disabled connection is part of
+    disabled: HashMap<(NavAreaId, NavAreaId), usize>,
+}
+
+impl ConnectionGroup {
+    fn disable(&self, disabled: &mut HashMap<(NavAreaId, NavAreaId), usize>) {
+        for connection in &self.connections {
+            *disabled.entry(*connection).or_default() += 1;
+        }
+    }
+
+    fn enable(&self, disabled: &mut HashMap<(NavAreaId, NavAreaId), usize>) {
+        for connection in &self.connections {
+            if let Some(count) = disabled.get_mut(connection) {
+                *count -= 1;
+                if *count == 0 {
+                    disabled.remove(connection);
+                }
+            }
+        }
+    }
+}
+
+impl ConnectionGroups {
+    /// Whether the moves between two areas are disabled by any group
+    pub(crate) fn is_disabled(&self, a: NavAreaId, b: NavAreaId) -> bool {
+        !self.disabled.is_empty() && self.disabled.contains_key(&connection_key(a, b))
+    }
+
+    /// Remove the connections to any of the areas from all groups
+    pub(crate) fn remove_areas(&mut self, areas: &HashSet<NavAreaId>) {
+        let ConnectionGroups { groups, disabled } = self;
+        for group in groups.values_mut() {
+            let removed = ConnectionGroup {
+                connections: group
+                    .connections
+                    .iter()
+                    .filter(|(a, b)| areas.contains(a) || areas.contains(b))
+                    .copied()
+                    .collect(),
+                enabled: group.enabled,
+            };
+            if !removed.enabled {
+                removed.enable(disabled);
+            }
+            for connection in &removed.connections {
+                group.connections.remove(connection);
+            }
+        }
+    }
+
+    /// The connections of a group, with the lowest area id first
+    pub(crate) fn connections(&self, name: &str) -> Option<&HashSet<(NavAreaId, NavAreaId)>> {
+        self.groups.get(name).map(|group| &group.connections)
+    }
+}
+
+impl NavTree {
+    /// Add a named group of connections that can be disabled together, like the connections
+    /// through a door
+    ///
+    /// Every connection is given as the two areas it connects, disabling the group disables all
+    /// moves between the areas in both directions, including ladders and off-mesh links. Disabled
+    /// connections are skipped by path searches, reachability and raycasts. Groups start
+    /// enabled, adding a group with the name of an existing group replaces it.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mut tree = get_area_tree(file)?;
+    /// tree.add_connection_group("door_a", vec![(120.into(), 121.into()), (120.into(), 122.into())]);
+    ///
+    /// // the door closes after setup
+    /// tree.set_group_enabled("door_a", false);
+    /// let path = tree.find_path(1.into(), 250.into());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_connection_group(
+        &mut self,
+        name: impl Into<String>,
+        connections: impl IntoIterator<Item = (NavAreaId, NavAreaId)>,
+    ) {
+        let group = ConnectionGroup {
+            connections: connections
+                .into_iter()
+                .map(|(a, b)| connection_key(a, b))
+                .collect(),
+            enabled: true,
+        };
+        if let Some(old) = self.groups.groups.insert(name.into(), group) {
+            if !old.enabled {
+                old.enable(&mut self.groups.disabled);
+            }
+        }
+    }
+
+    /// Remove a group of connections, enabling it's connections if the group was disabled
+    ///
+    /// Returns `false` if there is no group with the name.
+    pub fn remove_connection_group(&mut self, name: &str) -> bool {
+        match self.groups.groups.remove(name) {
+            Some(group) => {
+                if !group.enabled {
+                    group.enable(&mut self.groups.disabled);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Enable or disable a group of connections
+    ///
+    /// Connections that are part of multiple groups are disabled while any of the groups is
+    /// disabled. Returns `false` if there is no group with the name.
+    pub fn set_group_enabled(&mut self, name: &str, enabled: bool) -> bool {
+        let ConnectionGroups { groups, disabled } = &mut self.groups;
+        let group = match groups.get_mut(name) {
+            Some(group) => group,
+            None => return false,
+        };
+        if group.enabled != enabled {
+            group.enabled = enabled;
+            if enabled {
+                group.enable(disabled);
+            } else {
+                group.disable(disabled);
+            }
+        }
+        true
+    }
+
+    /// Whether a group of connections is enabled, `None` if there is no group with the name
+    pub fn is_group_enabled(&self, name: &str) -> Option<bool> {
+        self.groups.groups.get(name).map(|group| group.enabled)
+    }
+
+    /// Get the names of all groups of connections and whether they are enabled
+    pub fn connection_groups(&self) -> impl Iterator<Item = (&str, bool)> {
+        self.groups
+            .groups
+            .iter()
+            .map(|(name, group)| (name.as_str(), group.enabled))
+    }
+}
+
+#[test]
+fn test_connection_groups() {
+    use crate::DistanceCost;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let path = tree.find_path(start, goal).unwrap();
+    let (a, b) = (path[path.len() / 2], path[path.len() / 2 + 1]);
+
+    tree.add_connection_group("door", vec![(b, a)]);
+    tree.add_connection_group("gate", vec![(a, b), (path[0], path[1])]);
+    assert_eq!(
+        vec![("door", true), ("gate", true)],
+        tree.connection_groups().collect::<Vec<_>>()
+    );
+    assert_eq!(Some(path.clone()), tree.find_path(start, goal));
+
+    // disabled connections can't be used in either direction
+    assert!(tree.set_group_enabled("door", false));
+    assert_eq!(Some(false), tree.is_group_enabled("door"));
+    assert!(tree
+        .traversable_neighbors(&tree[a])
+        .all(|(next, _)| next.id != b));
+    assert!(tree
+        .traversable_neighbors(&tree[b])
+        .all(|(next, _)| next.id != a));
+    if let Some(detour) = tree.find_path(start, goal) {
+        assert!(detour
+            .windows(2)
+            .all(|pair| (pair[0], pair[1]) != (a, b) && (pair[0], pair[1]) != (b, a)));
+    }
+    assert!(tree.reachable_from(start, &DistanceCost).contains(&path[1]));
+
+    // connections stay disabled while any of their groups is disabled
+    tree.set_group_enabled("gate", false);
+    tree.set_group_enabled("door", false);
+    tree.set_group_enabled("door", true);
+    assert!(tree
+        .traversable_neighbors(&tree[a])
+        .all(|(next, _)| next.id != b));
+    assert!(tree
+        .traversable_neighbors(&tree[path[0]])
+        .all(|(next, _)| next.id != path[1]));
+
+    assert!(tree.remove_connection_group("gate"));
+    assert!(!tree.remove_connection_group("gate"));
+    assert!(!tree.set_group_enabled("gate", true));
+    assert_eq!(None, tree.is_group_enabled("gate"));
+    assert_eq!(Some(path), tree.find_path(start, goal));
+}
@@ -0,0 +1,220 @@
+use crate::navmesh::{HammerUnit, NavArea, NavAreaId, NavHidingSpot, Vector3};
+use crate::{NavMesh, NavTree, Rect};
+use aabb_quadtree::{ItemId, QuadTree, Spatial};
+use euclid::{TypedPoint2D, TypedSize2D};
+use std::cmp::Ordering;
+
+/// A hiding spot and the area it belongs to
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexedHidingSpot {
+    pub area: NavAreaId,
+    pub spot: NavHidingSpot,
+}
+
+impl Spatial<HammerUnit> for IndexedHidingSpot {
+    fn aabb(&self) -> Rect {
+        point_rect(self.spot.location.0, self.spot.location.1, 0.0)
+    }
+}
+
+fn point_rect(x: f32, y: f32, radius: f32) -> Rect {
+    Rect::new(
+        TypedPoint2D::new(x - radius - 0.5, y - radius - 0.5),
+        TypedSize2D::new(radius * 2.0 + 1.0, radius * 2.0 + 1.0),
+    )
+}
+
+/// A spatial index over the hiding spots of all areas
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, NavHidingSpot, Vector3};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let index = tree.hiding_spot_index();
+/// let position = Vector3(150.0, -312.0, 64.0);
+/// if let Some((cover, distance)) = index.nearest_hiding_spot(position, NavHidingSpot::IN_COVER) {
+///     println!("cover at {:?} is {} units away", cover.spot.location, distance);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct HidingSpotIndex {
+    tree: QuadTree<IndexedHidingSpot, HammerUnit, [(ItemId, Rect); 4]>,
+    rect: Rect,
+    len: usize,
+}
+
+impl HidingSpotIndex {
+    /// Build an index over the hiding spots of the areas
+    pub fn new<'a>(areas: impl Iterator<Item = &'a NavArea>) -> Self {
+        let spots: Vec<_> = areas
+            .flat_map(|area| {
+                area.hiding_spots.iter().map(move |spot| IndexedHidingSpot {
+                    area: area.id,
+                    spot: spot.clone(),
+                })
+            })
+            .collect();
+
+        let (min_x, min_y, max_x, max_y) = spots.iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(min_x, min_y, max_x, max_y), indexed| {
+                let location = indexed.spot.location;
+                (
+                    min_x.min(location.0),
+                    min_y.min(location.1),
+                    max_x.max(location.0),
+                    max_y.max(location.1),
+                )
+            },
+        );
+        let (min_x, min_y, max_x, max_y) = if spots.is_empty() {
+            (0.0, 0.0, 0.0, 0.0)
+        } else {
+            (min_x, min_y, max_x, max_y)
+        };
+        let rect = Rect::new(
+            TypedPoint2D::new(min_x - 1.0, min_y - 1.0),
+            TypedSize2D::new(max_x - min_x + 2.0, max_y - min_y + 2.0),
+        );
+
+        let mut tree = QuadTree::default(rect, spots.len());
+        let mut len = 0;
+        for spot in spots {
+            if tree.insert(spot).is_some() {
+                len += 1;
+            }
+        }
+
+        HidingSpotIndex { tree, rect, len }
+    }
+
+    /// Find all hiding spots with all of the given flags within a radius around a point
+    ///
+    /// Use `0` as flags to include all hiding spots.
+    pub fn hiding_spots_in_radius(
+        &self,
+        point: Vector3,
+        radius: f32,
+        flags: u8,
+    ) -> impl Iterator<Item = (&IndexedHidingSpot, f32)> {
+        self.tree
+            .query(point_rect(point.0, point.1, radius))
+            .into_iter()
+            .map(move |(indexed, ..)| (indexed, indexed.spot.location.distance(point)))
+            .filter(move |(indexed, distance)| *distance <= radius && indexed.spot.has_flags(flags))
+    }
+
+    /// Find the hiding spot with all of the given flags closest to a point and it's distance
+    ///
+    /// Use `0` as flags to include all hiding spots.
+    pub fn nearest_hiding_spot(
+        &self,
+        point: Vector3,
+        flags: u8,
+    ) -> Option<(&IndexedHidingSpot, f32)> {
+        // the largest radius needed to cover the full index from the point
+        let max_radius = [
+            point.0 - self.rect.min_x(),
+            self.rect.max_x() - point.0,
+            point.1 - self.rect.min_y(),
+            self.rect.max_y() - point.1,
+        ]
+        .iter()
+        .fold(0.0f32, |max, distance| max.max(*distance))
+            * std::f32::consts::SQRT_2;
+
+        // any spot closer than the nearest spot within the radius is also within the radius
+        let mut radius = 256.0f32;
+        while radius < max_radius {
+            let nearest = self
+                .hiding_spots_in_radius(point, radius, flags)
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+            if nearest.is_some() {
+                return nearest;
+            }
+            radius *= 2.0;
+        }
+
+        // the spots might still be further away vertically
+        self.iter()
+            .filter(|indexed| indexed.spot.has_flags(flags))
+            .map(|indexed| (indexed, indexed.spot.location.distance(point)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+    }
+
+    /// Iterate over all indexed hiding spots
+    pub fn iter(&self) -> impl Iterator<Item = &IndexedHidingSpot> {
+        self.tree.iter().map(|(_, (indexed, _))| indexed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl NavTree {
+    /// Build an index over the hiding spots of all areas in the tree
+    pub fn hiding_spot_index(&self) -> HidingSpotIndex {
+        HidingSpotIndex::new(self.areas())
+    }
+}
+
+impl NavMesh {
+    /// Build an index over the hiding spots of all areas in the mesh
+    pub fn hiding_spot_index(&self) -> HidingSpotIndex {
+        HidingSpotIndex::new(self.areas.iter())
+    }
+}
+
+#[test]
+fn test_hiding_spot_index() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    let index = mesh.hiding_spot_index();
+    let all: Vec<_> = mesh
+        .areas
+        .iter()
+        .flat_map(|area| area.hiding_spots.iter())
+        .collect();
+    assert_eq!(all.len(), index.len());
+
+    let point = Vector3(0.0, -1000.0, 200.0);
+    let radius = 1000.0;
+    let expected = all
+        .iter()
+        .filter(|spot| {
+            spot.location.distance(point) <= radius && spot.has_flags(NavHidingSpot::IN_COVER)
+        })
+        .count();
+    assert!(expected > 0);
+    assert_eq!(
+        expected,
+        index
+            .hiding_spots_in_radius(point, radius, NavHidingSpot::IN_COVER)
+            .count()
+    );
+
+    let nearest = all
+        .iter()
+        .filter(|spot| spot.has_flags(NavHidingSpot::EXPOSED))
+        .map(|spot| spot.location.distance(point))
+        .fold(f32::INFINITY, f32::min);
+    let (found, found_distance) = index
+        .nearest_hiding_spot(point, NavHidingSpot::EXPOSED)
+        .unwrap();
+    assert_eq!(nearest, found_distance);
+    assert!(found.spot.has_flags(NavHidingSpot::EXPOSED));
+
+    assert!(index
+        .nearest_hiding_spot(point, NavHidingSpot::IDEAL_SNIPER_SPOT)
+        .is_none());
+}
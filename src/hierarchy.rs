@@ -0,0 +1,225 @@
+use crate::navmesh::{NavAreaId, Vector3};
+use crate::path::{astar, astar_by};
+use crate::{NavCost, NavTree};
+use std::collections::{HashMap, HashSet};
+
+/// The connections between the places of a mesh, used to speed up path searches on large meshes
+///
+/// Two places are connected when an area in one place is connected to an area in the other place.
+/// Areas without a place are grouped together as the place with id `0`.
+///
+/// The graph is a snapshot of the tree it was created from and needs to be recreated when the
+/// areas of the tree change.
+#[derive(Debug, Clone)]
+pub struct PlaceGraph {
+    centers: HashMap<u16, Vector3>,
+    neighbors: HashMap<u16, Vec<u16>>,
+}
+
+impl PlaceGraph {
+    /// Create the place graph for all areas of a tree
+    pub fn new(tree: &NavTree) -> Self {
+        let mut sums: HashMap<u16, (Vector3, usize)> = HashMap::new();
+        let mut neighbors: HashMap<u16, HashSet<u16>> = HashMap::new();
+
+        for area in tree.areas() {
+            let center = area.center();
+            let (sum, count) = sums
+                .entry(area.place)
+                .or_insert((Vector3(0.0, 0.0, 0.0), 0));
+            *sum = Vector3(sum.0 + center.0, sum.1 + center.1, sum.2 + center.2);
+            *count += 1;
+
+            let place_neighbors = neighbors.entry(area.place).or_default();
+            for (next, _) in tree.traversable_neighbors(area) {
+                if next.place != area.place {
+                    place_neighbors.insert(next.place);
+                }
+            }
+        }
+
+        let centers = sums
+            .into_iter()
+            .map(|(place, (sum, count))| {
+                let count = count as f32;
+                (place, Vector3(sum.0 / count, sum.1 / count, sum.2 / count))
+            })
+            .collect();
+        let neighbors = neighbors
+            .into_iter()
+            .map(|(place, neighbors)| {
+                let mut neighbors: Vec<u16> = neighbors.into_iter().collect();
+                neighbors.sort_unstable();
+                (place, neighbors)
+            })
+            .collect();
+
+        PlaceGraph { centers, neighbors }
+    }
+
+    /// Get the ids of all places in the graph
+    pub fn places(&self) -> impl Iterator<Item = u16> + '_ {
+        self.centers.keys().copied()
+    }
+
+    /// Get the average center of the areas of a place
+    pub fn center(&self, place: u16) -> Option<Vector3> {
+        self.centers.get(&place).copied()
+    }
+
+    /// Get the places that can be reached directly from a place
+    pub fn neighbors(&self, place: u16) -> &[u16] {
+        self.neighbors
+            .get(&place)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Find the shortest chain of places between two places
+    ///
+    /// The length of the chain is the sum of the distances between the centers of the places.
+    pub fn find_path(&self, start: u16, goal: u16) -> Option<Vec<u16>> {
+        let goal_center = self.center(goal)?;
+        self.center(start)?;
+
+        astar_by(
+            start,
+            goal,
+            |place| {
+                let center = self.center(place);
+                self.neighbors(place)
+                    .iter()
+                    .filter_map(move |next| Some((*next, self.center(*next)?.distance(center?))))
+            },
+            |place| {
+                self.center(place)
+                    .map_or(0.0, |center| center.distance(goal_center))
+            },
+        )
+        .map(|(path, _)| path)
+    }
+}
+
+impl NavTree {
+    /// Find a path between two areas by first finding the places to go through
+    ///
+    /// The search first finds the shortest chain of places from the place of the start area to
+    /// the place of the goal area and then searches for the cheapest path through the areas of
+    /// those places. This is much faster than [`find_path_with_cost`] on large meshes but the
+    /// path isn't always the cheapest one. When the areas of the places don't connect the start
+    /// to the goal, the full mesh is searched instead. No path is found when the start or the goal
+    /// area is blocked.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, AttributeCost, PlaceGraph};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let places = PlaceGraph::new(&tree);
+    /// let cost = AttributeCost::default();
+    /// let path = tree.find_path_hierarchical(&places, 1.into(), 250.into(), &cost);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`find_path_with_cost`]: #method.find_path_with_cost
+    pub fn find_path_hierarchical(
+        &self,
+        places: &PlaceGraph,
+        start: NavAreaId,
+        goal: NavAreaId,
+        cost: &impl NavCost,
+    ) -> Option<Vec<NavAreaId>> {
+        if self.is_blocked(start) || self.is_blocked(goal) {
+            return None;
+        }
+
+        let start_area = self.area(start)?;
+        let goal_area = self.area(goal)?;
+        let corridor: HashSet<u16> = places
+            .find_path(start_area.place, goal_area.place)
+            .into_iter()
+            .flatten()
+            .collect();
+
+        astar(
+            self,
+            start,
+            goal,
+            |area| {
+                self.traversable_neighbors(area)
+                    .filter(|(next, _)| corridor.contains(&next.place))
+                    .filter_map(move |(next, kind)| {
+                        Some((next, self.move_cost(cost, area, next, kind)?))
+                    })
+            },
+            |area| cost.estimate(area, goal_area),
+        )
+        .map(|(path, _)| path)
+        .or_else(|| self.find_path_with_cost(start, goal, cost))
+    }
+}
+
+#[test]
+fn test_find_path_hierarchical() {
+    use crate::DistanceCost;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file).unwrap();
+
+    // divide the map into a grid of places
+    let ids: Vec<NavAreaId> = tree.areas().map(|area| area.id).collect();
+    for id in ids {
+        let mut area = tree[id].clone();
+        let center = area.center();
+        area.place =
+            1 + ((center.0 + 4096.0) / 512.0) as u16 * 32 + ((center.1 + 4096.0) / 512.0) as u16;
+        let handle = tree.handle(id).unwrap();
+        tree.replace(handle, area).unwrap();
+    }
+    let places = PlaceGraph::new(&tree);
+    assert!(places.places().count() > 1);
+    for place in places.places() {
+        assert!(!places.neighbors(place).contains(&place));
+    }
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let path = tree
+        .find_path_hierarchical(&places, start, goal, &DistanceCost)
+        .unwrap();
+    assert_eq!(Some(&start), path.first());
+    assert_eq!(Some(&goal), path.last());
+    for pair in path.windows(2) {
+        assert!(tree
+            .traversable_neighbors(&tree[pair[0]])
+            .any(|(next, _)| next.id == pair[1]));
+    }
+
+    let length = |path: &[NavAreaId]| -> f32 {
+        path.windows(2)
+            .map(|pair| tree[pair[0]].center().distance(tree[pair[1]].center()))
+            .sum()
+    };
+    let shortest = tree.find_path(start, goal).unwrap();
+    assert!(length(&path) >= length(&shortest) - 0.01);
+
+    // the place chain matches the places of the path
+    let chain = places
+        .find_path(tree[start].place, tree[goal].place)
+        .unwrap();
+    assert_eq!(Some(&tree[start].place), chain.first());
+    assert_eq!(Some(&tree[goal].place), chain.last());
+    for pair in chain.windows(2) {
+        assert!(places.neighbors(pair[0]).contains(&pair[1]));
+    }
+
+    tree.set_blocked(goal, true);
+    assert_eq!(
+        None,
+        tree.find_path_hierarchical(&places, start, goal, &DistanceCost)
+    );
+}
@@ -1,16 +1,109 @@
+pub use crate::boundary::{BoundaryArea, BoundaryEdge};
+pub use crate::builder::{EditError, NavAxis, NavCorner, NavMeshBuilder};
+#[cfg(feature = "cache")]
+pub use crate::cache::CacheError;
+#[cfg(feature = "callouts")]
+pub use crate::callouts::{canonical_place_name, normalize_place_name};
+pub use crate::check::ConsistencyIssue;
+pub use crate::chokepoint::Chokepoint;
+pub use crate::corridor::PathCorridor;
+pub use crate::cost::{AttributeCost, CostOverlay, DistanceCost, NavCost, TeamCost};
+pub use crate::custom::{CustomAreaData, NavGame, TfAttributeFlags};
+pub use crate::diff::{nav_diff, AreaDiff, NavDiff};
+#[cfg(feature = "gltf")]
+pub use crate::export::GltfColoring;
+#[cfg(feature = "godot")]
+pub use crate::export::GodotOptions;
+#[cfg(feature = "petgraph")]
+pub use crate::export::NavEdge;
+#[cfg(feature = "json")]
+pub use crate::export::JSON_SCHEMA_VERSION;
+pub use crate::export::{
+    DetourOffMeshConnection, DetourPoly, DetourTile, DotOptions, Heightmap, NavCsr, NavTriangles,
+    ObjOptions,
+};
+pub use crate::flags::{NavAttributeFlags, NavFlagFilter};
+pub use crate::frustum::{Frustum, Plane};
+#[cfg(feature = "bsp")]
+pub use crate::generate::generate_mesh_from_bsp;
+pub use crate::generate::{generate_mesh, generate_mesh_from_triangles, GeneratorOptions};
+pub use crate::hiding::{HidingSpotIndex, IndexedHidingSpot};
+pub use crate::hierarchy::PlaceGraph;
+pub use crate::link::{OffMeshLink, OffMeshLinkId};
+pub use crate::matrix::DistanceMatrix;
+pub use crate::merge::MergePolicy;
+pub use crate::mesh::{NavMesh, NavMeshView, PlaceAreas, PlaceRef};
 use crate::navmesh::HammerUnit;
 pub use crate::navmesh::{
-    ApproachArea, Connections, EncounterPath, LadderConnections, LadderDirection, LightIntensity,
-    NavDirection, NavHidingSpot, NavQuad, Vector3, VisibleArea,
+    ApproachArea, Connections, EncounterPath, EncounterSpot, LadderConnections, LadderDirection,
+    LightIntensity, NavAreaId, NavDirection, NavHidingSpot, NavLadder, NavLadderId, NavPlace,
+    NavQuad, Team, Vector3, VisibleArea,
 };
 use crate::parser::read_quads;
-pub use crate::parser::{read_areas, NavArea, ParseError};
+pub use crate::parser::{read_areas, read_mesh, read_mesh_for_game, NavArea, ParseError};
+pub use crate::patch::{NavPatch, PatchError, PatchOperation};
+pub use crate::path::{NavPath, PathOptions};
+pub use crate::pathcache::PathCache;
+pub use crate::raycast::{AreaCrossing, RaycastHit};
+pub use crate::replan::Replanner;
+pub use crate::travel::{MovementProfile, PathMetrics};
+pub use crate::tree::{
+    get_area_tree, get_area_tree_for_game, AreaHandle, EdgeKind, NavTree, RefreshSummary,
+    TreeEditError,
+};
+pub use crate::visibility::VisibilityMatrix;
+pub use crate::water::WaterVolume;
+pub use crate::writer::{VisibilityMode, WriteError, WriteOptions};
 use aabb_quadtree::{ItemId, QuadTree};
 use bitbuffer::{BitReadStream, LittleEndian};
 use euclid::{TypedPoint2D, TypedRect, TypedSize2D};
 
+#[cfg(feature = "rayon")]
+mod batch;
+mod blocking;
+mod boundary;
+mod builder;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "callouts")]
+mod callouts;
+mod check;
+mod chokepoint;
+mod components;
+mod convert;
+mod corridor;
+mod cost;
+mod custom;
+mod diff;
+mod export;
+mod extract;
+mod flags;
+mod frustum;
+mod funnel;
+mod generate;
+mod groups;
+mod hiding;
+mod hierarchy;
+mod link;
+mod matrix;
+mod merge;
+mod mesh;
 mod navmesh;
 mod parser;
+mod patch;
+mod path;
+mod pathcache;
+#[cfg(feature = "rand")]
+mod random;
+mod raycast;
+mod replan;
+mod simplify;
+mod theta;
+mod travel;
+mod tree;
+mod visibility;
+mod water;
+mod writer;
 
 type Rect = TypedRect<f32, HammerUnit>;
 
@@ -35,7 +128,18 @@ pub fn get_quad_tree(
 ) -> Result<NavQuadTree, ParseError> {
     let areas = read_quads(data.into())?;
 
-    let (min_x, min_y, max_x, max_y) = areas.iter().fold(
+    let mut tree = QuadTree::default(tree_bounds(areas.iter()), areas.len());
+
+    for area in areas {
+        tree.insert(area);
+    }
+
+    Ok(NavQuadTree(tree))
+}
+
+/// Get the bounding box for a tree containing all quads, with some padding
+fn tree_bounds<'a>(quads: impl Iterator<Item = &'a NavQuad>) -> Rect {
+    let (min_x, min_y, max_x, max_y) = quads.fold(
         (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
         |(min_x, min_y, max_x, max_y), area| {
             (
@@ -47,19 +151,10 @@ pub fn get_quad_tree(
         },
     );
 
-    let mut tree = QuadTree::default(
-        Rect::new(
-            TypedPoint2D::new(min_x - 1.0, min_y - 1.0),
-            TypedSize2D::new(max_x - min_x + 2.0, max_y - min_y + 2.0),
-        ),
-        areas.len(),
-    );
-
-    for area in areas {
-        tree.insert(area);
-    }
-
-    Ok(NavQuadTree(tree))
+    Rect::new(
+        TypedPoint2D::new(min_x - 1.0, min_y - 1.0),
+        TypedSize2D::new(max_x - min_x + 2.0, max_y - min_y + 2.0),
+    )
 }
 
 impl NavQuadTree {
@@ -106,17 +201,48 @@ impl NavQuadTree {
     /// Get the z height at a point
     ///
     /// A z-guess should be provided to resolve cases where multiple z values are possible
+    ///
+    /// Returns `f32::MIN` if there is no area at the point, use [`find_height_near`] to detect
+    /// points that are not on the mesh.
+    ///
+    /// [`find_height_near`]: #method.find_height_near
     pub fn find_best_height(&self, x: f32, y: f32, z_guess: f32) -> f32 {
-        let found_heights = self.find_z_height(x, y);
-        let best_z = f32::MIN;
-
-        found_heights.fold(best_z, |best_z, found_z| {
-            if (found_z - z_guess).abs() < (best_z - z_guess).abs() {
-                found_z
-            } else {
-                best_z
-            }
-        })
+        self.find_height_near(x, y, z_guess, f32::INFINITY)
+            .unwrap_or(f32::MIN)
+    }
+
+    /// Get the z height at a point closest to a z-guess
+    ///
+    /// The heights are interpolated from the heights of the corners of the areas.
+    ///
+    /// Returns `None` if there is no area at the point with a height that differs at most
+    /// `max_delta` from the z-guess
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_quad_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_quad_tree(file)?;
+    /// match tree.find_height_near(150.0, -312.0, 64.0, 32.0) {
+    ///     Some(height) => println!("the ground is at {}", height),
+    ///     None => println!("not on the mesh"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_height_near(&self, x: f32, y: f32, z_guess: f32, max_delta: f32) -> Option<f32> {
+        self.query(x, y)
+            .map(|quad| quad.interpolated_z(x, y))
+            .filter(|found_z| (found_z - z_guess).abs() <= max_delta)
+            .fold(None, |best_z: Option<f32>, found_z| match best_z {
+                Some(best_z) if (best_z - z_guess).abs() <= (found_z - z_guess).abs() => {
+                    Some(best_z)
+                }
+                _ => Some(found_z),
+            })
     }
 
     /// Get all navigation areas from the nav file
@@ -176,7 +302,35 @@ fn test_tree() {
         vec![147.23126],
         tree.find_z_height(point4.0, point4.1).collect::<Vec<f32>>()
     );
+
+    assert_eq!(
+        Some(108.144714),
+        tree.find_height_near(point2.0, point2.1, 100.0, 50.0)
+    );
+    assert_eq!(None, tree.find_height_near(point2.0, point2.1, 200.0, 50.0));
+    assert_eq!(None, tree.find_height_near(-100_000.0, 0.0, 0.0, 50.0));
+    assert_eq!(f32::MIN, tree.find_best_height(-100_000.0, 0.0, 0.0));
 }
 
 #[cfg(doctest)]
 doc_comment::doctest!("../README.md");
+
+#[test]
+fn test_height_near_slope() {
+    let mut builder = NavMeshBuilder::new(NavGame::TeamFortress2);
+    // a slope rising along the y axis
+    builder
+        .add_area(NavQuad {
+            north_west: Vector3(0.0, 0.0, 0.0),
+            south_east: Vector3(100.0, 400.0, 200.0),
+            north_east_z: 0.0,
+            south_west_z: 200.0,
+        })
+        .unwrap();
+    let mesh = builder.build();
+    let tree = get_quad_tree(mesh.to_bytes(mesh.major_version).unwrap()).unwrap();
+
+    assert_eq!(Some(50.0), tree.find_height_near(50.0, 100.0, 40.0, 20.0));
+    assert_eq!(150.0, tree.find_best_height(20.0, 300.0, 0.0));
+    assert_eq!(None, tree.find_height_near(50.0, 300.0, 0.0, 20.0));
+}
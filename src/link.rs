@@ -0,0 +1,281 @@
+use crate::navmesh::{NavArea, NavAreaId, Vector3};
+use crate::{EdgeKind, NavCost, NavTree};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// The id of an off-mesh link, unique within the tree it was added to
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct OffMeshLinkId(u32);
+
+impl fmt::Display for OffMeshLinkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A custom connection between two areas that isn't part of the nav file, like a teleporter or a
+/// jump pad
+#[derive(Debug, Clone, PartialEq)]
+pub struct OffMeshLink {
+    pub id: OffMeshLinkId,
+    /// The area the link starts in
+    pub from: NavAreaId,
+    /// The area the link ends in
+    pub to: NavAreaId,
+    /// Where the link starts
+    pub start: Vector3,
+    /// Where the link ends
+    pub end: Vector3,
+    /// The cost of moving through the link
+    pub cost: f32,
+    /// Whether the link can also be used from the end to the start
+    pub bidirectional: bool,
+}
+
+/// The off-mesh links of a tree, indexed by the areas they can be used from
+#[derive(Debug, Clone, Default)]
+pub(crate) struct OffMeshLinks {
+    links: BTreeMap<OffMeshLinkId, OffMeshLink>,
+    by_area: HashMap<NavAreaId, Vec<OffMeshLinkId>>,
+    next_id: u32,
+}
+
+impl OffMeshLinks {
+    fn insert(&mut self, mut link: OffMeshLink) -> OffMeshLinkId {
+        let id = OffMeshLinkId(self.next_id);
+        self.next_id += 1;
+        link.id = id;
+
+        self.by_area.entry(link.from).or_default().push(id);
+        if link.bidirectional && link.to != link.from {
+            self.by_area.entry(link.to).or_default().push(id);
+        }
+        self.links.insert(id, link);
+        id
+    }
+
+    fn remove(&mut self, id: OffMeshLinkId) -> Option<OffMeshLink> {
+        let link = self.links.remove(&id)?;
+        for area in &[link.from, link.to] {
+            if let Some(ids) = self.by_area.get_mut(area) {
+                ids.retain(|other| *other != id);
+            }
+        }
+        Some(link)
+    }
+
+    /// Remove the links that start or end in any of the areas
+    pub(crate) fn remove_areas(&mut self, areas: &HashSet<NavAreaId>) {
+        let ids: Vec<OffMeshLinkId> = self
+            .links
+            .values()
+            .filter(|link| areas.contains(&link.from) || areas.contains(&link.to))
+            .map(|link| link.id)
+            .collect();
+        for id in ids {
+            self.remove(id);
+        }
+        for area in areas {
+            self.by_area.remove(area);
+        }
+    }
+
+    /// The links that can be used from an area, with the area at the other end of the link
+    pub(crate) fn usable_from(
+        &self,
+        area: NavAreaId,
+    ) -> impl Iterator<Item = (&OffMeshLink, NavAreaId)> + '_ {
+        self.by_area
+            .get(&area)
+            .into_iter()
+            .flatten()
+            .filter_map(move |id| {
+                let link = self.links.get(id)?;
+                if link.from == area {
+                    Some((link, link.to))
+                } else {
+                    Some((link, link.from))
+                }
+            })
+    }
+}
+
+impl NavTree {
+    /// Add a link between the areas at two points
+    ///
+    /// The areas of the points are the areas at the x/y coordinates with the height closest to
+    /// the z coordinates of the points. The link is used by path searches and reachability next
+    /// to the connections and ladders of the areas, the cost of a link is decided by
+    /// [`NavCost::link_cost`]. Path searches expect links not to cost less than the distance
+    /// between the centers of the areas they connect, cheaper links might not be preferred over
+    /// longer walks.
+    ///
+    /// Returns `None` if either point isn't above an area.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mut tree = get_area_tree(file)?;
+    /// // a teleporter from the spawn to the front line
+    /// let teleporter = tree.add_link(
+    ///     Vector3(150.0, -312.0, 64.0),
+    ///     Vector3(1200.0, 200.0, 128.0),
+    ///     1.0,
+    ///     false,
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`NavCost::link_cost`]: ./trait.NavCost.html#method.link_cost
+    pub fn add_link(
+        &mut self,
+        start: Vector3,
+        end: Vector3,
+        cost: f32,
+        bidirectional: bool,
+    ) -> Option<OffMeshLinkId> {
+        let from = self.area_under(start.0, start.1, start.2)?.id;
+        let to = self.area_under(end.0, end.1, end.2)?.id;
+        Some(self.links.insert(OffMeshLink {
+            id: OffMeshLinkId(0),
+            from,
+            to,
+            start,
+            end,
+            cost,
+            bidirectional,
+        }))
+    }
+
+    /// Add a link between the centers of two areas
+    ///
+    /// Returns `None` if either area doesn't exist, see [`add_link`] for how links are used.
+    ///
+    /// [`add_link`]: #method.add_link
+    pub fn add_area_link(
+        &mut self,
+        from: NavAreaId,
+        to: NavAreaId,
+        cost: f32,
+        bidirectional: bool,
+    ) -> Option<OffMeshLinkId> {
+        let start = self.area(from)?.center();
+        let end = self.area(to)?.center();
+        Some(self.links.insert(OffMeshLink {
+            id: OffMeshLinkId(0),
+            from,
+            to,
+            start,
+            end,
+            cost,
+            bidirectional,
+        }))
+    }
+
+    /// Remove a link
+    pub fn remove_link(&mut self, id: OffMeshLinkId) -> Option<OffMeshLink> {
+        self.links.remove(id)
+    }
+
+    /// Get a link by it's id
+    pub fn link(&self, id: OffMeshLinkId) -> Option<&OffMeshLink> {
+        self.links.links.get(&id)
+    }
+
+    /// Get all links in the tree
+    pub fn links(&self) -> impl Iterator<Item = &OffMeshLink> {
+        self.links.links.values()
+    }
+
+    /// The cost of moving between two neighboring areas, resolving the cost of off-mesh links
+    pub(crate) fn move_cost(
+        &self,
+        cost: &impl NavCost,
+        from: &NavArea,
+        to: &NavArea,
+        kind: EdgeKind,
+    ) -> Option<f32> {
+        match kind {
+            EdgeKind::OffMesh(id) => cost.link_cost(self.link(id)?, from, to),
+            _ => cost.edge_cost(from, to, kind),
+        }
+    }
+}
+
+#[test]
+fn test_links() {
+    use crate::DistanceCost;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let path = tree.find_path(start, goal).unwrap();
+    assert!(path.len() > 2);
+
+    let id = tree
+        .add_link(tree[start].center(), tree[goal].center(), 1.0, false)
+        .unwrap();
+    assert_eq!(start, tree.link(id).unwrap().from);
+    assert_eq!(goal, tree.link(id).unwrap().to);
+    assert!(tree
+        .traversable_neighbors(&tree[start])
+        .any(|(next, kind)| next.id == goal && kind == EdgeKind::OffMesh(id)));
+    assert_eq!(Some(vec![start, goal]), tree.find_path(start, goal));
+
+    // the link only goes one way
+    let back = tree.find_path(goal, start).unwrap();
+    assert!(back.len() > 2);
+
+    // areas that are only reachable through a link are reachable
+    let reachable = tree.reachable_from(start, &DistanceCost);
+    let isolated = tree
+        .areas()
+        .map(|area| area.id)
+        .find(|id| !reachable.contains(id));
+    if let Some(isolated) = isolated {
+        let link = tree.add_area_link(start, isolated, 10.0, true).unwrap();
+        assert!(tree
+            .reachable_from(start, &DistanceCost)
+            .contains(&isolated));
+        assert!(tree
+            .reachable_from(isolated, &DistanceCost)
+            .contains(&start));
+        tree.remove_link(link).unwrap();
+    }
+
+    tree.remove_link(id).unwrap();
+    assert_eq!(0, tree.links().count());
+    assert_eq!(Some(path), tree.find_path(start, goal));
+}
+
+#[test]
+fn test_links_of_removed_areas() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file.clone()).unwrap();
+    let mut mesh = crate::read_mesh(file).unwrap();
+
+    let (a, b, c) = (mesh.areas[0].id, mesh.areas[1].id, mesh.areas[2].id);
+    let into_removed = tree.add_area_link(a, c, 1.0, false).unwrap();
+    let out_of_removed = tree.add_area_link(c, b, 1.0, true).unwrap();
+    let kept = tree.add_area_link(a, b, 1.0, false).unwrap();
+
+    mesh.areas.remove(2);
+    let summary = tree
+        .refresh_from(mesh.to_bytes(mesh.major_version).unwrap())
+        .unwrap();
+    assert_eq!(1, summary.removed);
+
+    assert!(tree.link(into_removed).is_none());
+    assert!(tree.link(out_of_removed).is_none());
+    assert!(tree.link(kept).is_some());
+    assert_eq!(1, tree.links().count());
+    assert_eq!(1, tree.links.usable_from(a).count());
+    assert_eq!(0, tree.links.usable_from(b).count());
+}
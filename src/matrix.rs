@@ -0,0 +1,267 @@
+use crate::navmesh::{NavArea, NavAreaId};
+use crate::path::Candidate;
+use crate::{NavCost, NavTree};
+use std::collections::{BinaryHeap, HashMap};
+
+/// The travel cost between every pair of areas, see [`NavTree::distance_matrix`]
+///
+/// The costs are stored row by row, the cost from the area at index `from` to the area at index
+/// `to` is at `from * len + to`. Areas that can't be reached have an infinite cost.
+///
+/// [`NavTree::distance_matrix`]: ./struct.NavTree.html#method.distance_matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceMatrix {
+    ids: Vec<NavAreaId>,
+    index: HashMap<NavAreaId, usize>,
+    costs: Vec<f32>,
+}
+
+impl DistanceMatrix {
+    /// The number of areas in the matrix
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the matrix has no areas
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The id of the area at every index, sorted by id
+    pub fn ids(&self) -> &[NavAreaId] {
+        &self.ids
+    }
+
+    /// Get the index of an area
+    pub fn index(&self, id: NavAreaId) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    /// Get the travel cost between two areas, `None` if either area isn't in the matrix or the
+    /// second area can't be reached from the first
+    pub fn get(&self, from: NavAreaId, to: NavAreaId) -> Option<f32> {
+        let cost = self.row(self.index(from)?)[self.index(to)?];
+        Some(cost).filter(|cost| cost.is_finite())
+    }
+
+    /// Get the travel costs from the area at an index to all areas
+    pub fn row(&self, index: usize) -> &[f32] {
+        &self.costs[index * self.len()..(index + 1) * self.len()]
+    }
+
+    /// Get the travel costs between all areas, row by row
+    pub fn as_slice(&self) -> &[f32] {
+        &self.costs
+    }
+}
+
+/// The moves between areas by index, with the cost of every move
+struct IndexedGraph {
+    ids: Vec<NavAreaId>,
+    index: HashMap<NavAreaId, usize>,
+    outgoing: Vec<Vec<(usize, f32)>>,
+    incoming: Vec<Vec<(usize, f32)>>,
+}
+
+impl IndexedGraph {
+    fn new(tree: &NavTree, cost: &impl NavCost) -> Self {
+        let mut areas: Vec<&NavArea> = tree.areas().collect();
+        areas.sort_by_key(|area| area.id);
+        let ids: Vec<NavAreaId> = areas.iter().map(|area| area.id).collect();
+        let index: HashMap<NavAreaId, usize> = ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (*id, index))
+            .collect();
+
+        let mut outgoing = vec![Vec::new(); areas.len()];
+        let mut incoming = vec![Vec::new(); areas.len()];
+        for (from, area) in areas.iter().enumerate() {
+            for (next, kind) in tree.traversable_neighbors(area) {
+                let to = match index.get(&next.id) {
+                    Some(to) => *to,
+                    None => continue,
+                };
+                if let Some(cost) = tree.move_cost(cost, area, next, kind) {
+                    outgoing[from].push((to, cost));
+                    incoming[to].push((from, cost));
+                }
+            }
+        }
+
+        IndexedGraph {
+            ids,
+            index,
+            outgoing,
+            incoming,
+        }
+    }
+
+    /// Get the cheapest cost from a source to every area over a set of edges
+    fn costs_from(edges: &[Vec<(usize, f32)>], source: usize, costs: &mut [f32]) {
+        costs.iter_mut().for_each(|cost| *cost = f32::INFINITY);
+        costs[source] = 0.0;
+        let mut open = BinaryHeap::new();
+        open.push(Candidate {
+            estimate: 0.0,
+            cost: 0.0,
+            id: source,
+        });
+
+        while let Some(Candidate { cost, id, .. }) = open.pop() {
+            if cost > costs[id] {
+                continue;
+            }
+            for (next, edge_cost) in &edges[id] {
+                let next_cost = cost + edge_cost;
+                if next_cost < costs[*next] {
+                    costs[*next] = next_cost;
+                    open.push(Candidate {
+                        estimate: next_cost,
+                        cost: next_cost,
+                        id: *next,
+                    });
+                }
+            }
+        }
+    }
+
+    fn matrix(self, costs: Vec<f32>) -> DistanceMatrix {
+        DistanceMatrix {
+            ids: self.ids,
+            index: self.index,
+            costs,
+        }
+    }
+}
+
+impl NavTree {
+    /// Get the travel cost between every pair of areas under a cost
+    ///
+    /// This runs a search from every area, which takes a while for large maps, see
+    /// [`distance_matrix_with_landmarks`] for a faster approximation.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, DistanceCost};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let matrix = tree.distance_matrix(&DistanceCost);
+    /// if let Some(distance) = matrix.get(1.into(), 250.into()) {
+    ///     println!("area 250 is {} units from area 1", distance);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`distance_matrix_with_landmarks`]: #method.distance_matrix_with_landmarks
+    pub fn distance_matrix(&self, cost: &impl NavCost) -> DistanceMatrix {
+        let graph = IndexedGraph::new(self, cost);
+        let len = graph.ids.len();
+        let mut costs = vec![0.0; len * len];
+        for (source, row) in costs.chunks_mut(len.max(1)).enumerate() {
+            IndexedGraph::costs_from(&graph.outgoing, source, row);
+        }
+        graph.matrix(costs)
+    }
+
+    /// Approximate the travel cost between every pair of areas using a number of landmark areas
+    ///
+    /// The travel costs from and to every landmark are searched, the cost between two areas is
+    /// approximated by the cheapest route through any of the landmarks. The approximated costs
+    /// are never lower than the actual costs and are exact for routes starting or ending at a
+    /// landmark. The landmarks are picked to be spread out over the map, more landmarks give a
+    /// better approximation.
+    pub fn distance_matrix_with_landmarks(
+        &self,
+        cost: &impl NavCost,
+        landmarks: usize,
+    ) -> DistanceMatrix {
+        let graph = IndexedGraph::new(self, cost);
+        let len = graph.ids.len();
+        let landmarks = landmarks.min(len);
+
+        // pick every next landmark as the area furthest away from the picked landmarks
+        let mut from_landmarks = vec![vec![0.0; len]; landmarks];
+        let mut to_landmarks = vec![vec![0.0; len]; landmarks];
+        let mut nearest = vec![f32::INFINITY; len];
+        for landmark in 0..landmarks {
+            let source = (0..len)
+                .max_by(|a, b| {
+                    nearest[*a]
+                        .partial_cmp(&nearest[*b])
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                        .then(b.cmp(a))
+                })
+                .unwrap_or_default();
+            IndexedGraph::costs_from(&graph.outgoing, source, &mut from_landmarks[landmark]);
+            IndexedGraph::costs_from(&graph.incoming, source, &mut to_landmarks[landmark]);
+            for (nearest, cost) in nearest.iter_mut().zip(&from_landmarks[landmark]) {
+                *nearest = nearest.min(*cost);
+            }
+        }
+
+        let mut costs = vec![f32::INFINITY; len * len];
+        for (from, row) in costs.chunks_mut(len.max(1)).enumerate() {
+            for (to_landmark, from_landmark) in to_landmarks.iter().zip(&from_landmarks) {
+                let to_landmark = to_landmark[from];
+                if to_landmark.is_infinite() {
+                    continue;
+                }
+                for (cost, from_landmark) in row.iter_mut().zip(from_landmark) {
+                    *cost = cost.min(to_landmark + from_landmark);
+                }
+            }
+            row[from] = 0.0;
+        }
+        graph.matrix(costs)
+    }
+}
+
+#[test]
+fn test_distance_matrix() {
+    use crate::DistanceCost;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let matrix = tree.distance_matrix(&DistanceCost);
+    assert_eq!(tree.len(), matrix.len());
+    assert_eq!(tree.len() * tree.len(), matrix.as_slice().len());
+    assert!(matrix.ids().windows(2).all(|pair| pair[0] < pair[1]));
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let field = tree.distance_field(&[start], &DistanceCost);
+    let row = matrix.row(matrix.index(start).unwrap());
+    for (id, cost) in matrix.ids().iter().zip(row) {
+        match field.get(id) {
+            Some(expected) => assert!((expected - cost).abs() < 0.1),
+            None => assert!(cost.is_infinite()),
+        }
+    }
+    assert_eq!(Some(0.0), matrix.get(start, start));
+    if let Some(expected) = field.get(&goal) {
+        assert!((expected - matrix.get(start, goal).unwrap()).abs() < 0.1);
+    }
+    assert_eq!(None, matrix.get(start, NavAreaId::from(u32::MAX)));
+
+    // landmark costs are never lower than the actual costs
+    let approximate = tree.distance_matrix_with_landmarks(&DistanceCost, 16);
+    assert_eq!(matrix.ids(), approximate.ids());
+    for (exact, approximate) in matrix.as_slice().iter().zip(approximate.as_slice()) {
+        assert!(*approximate >= exact - 0.1 || (exact.is_infinite() && approximate.is_infinite()));
+    }
+    assert_eq!(Some(0.0), approximate.get(goal, goal));
+
+    // the first landmark is the first area, so it's costs are exact
+    let first = matrix.row(0);
+    for (exact, approximate) in first.iter().zip(approximate.row(0)) {
+        assert!(
+            (exact - approximate).abs() < 0.1 || (exact.is_infinite() && approximate.is_infinite())
+        );
+    }
+}
@@ -0,0 +1,365 @@
+use crate::navmesh::{NavArea, NavAreaId, NavLadderId};
+use crate::{EditError, NavGame, NavMesh, NavMeshBuilder};
+use euclid::TypedRect;
+use std::collections::{HashMap, HashSet};
+
+/// The maximum height difference between areas from both meshes for them to be stitched
+const STEP_HEIGHT: f32 = 18.0;
+
+/// The minimum height difference between overlapping areas for them to be on different floors
+const FLOOR_HEIGHT: f32 = 36.0;
+
+/// How [`NavMesh::merge`] handles areas from both meshes that overlap
+///
+/// [`NavMesh::merge`]: ./struct.NavMesh.html#method.merge
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum MergePolicy {
+    /// Keep all areas from both meshes
+    KeepBoth,
+    /// Drop the areas from the other mesh that overlap with areas of this mesh
+    PreferSelf,
+    /// Drop the areas of this mesh that overlap with areas from the other mesh
+    PreferOther,
+}
+
+fn overlaps(a: &NavArea, b: &NavArea) -> bool {
+    use aabb_quadtree::Spatial;
+
+    let (a_rect, b_rect) = (a.aabb(), b.aabb());
+    let intersection: Option<TypedRect<_, _>> = a_rect.intersection(&b_rect);
+    match intersection {
+        Some(intersection) if intersection.size.width > 0.0 && intersection.size.height > 0.0 => {
+            let center = intersection.center();
+            let height_difference = a.quad.interpolated_z(center.x, center.y)
+                - b.quad.interpolated_z(center.x, center.y);
+            height_difference.abs() < FLOOR_HEIGHT
+        }
+        _ => false,
+    }
+}
+
+fn remap_area(area: &mut NavArea, map: &HashMap<NavAreaId, NavAreaId>) {
+    let remap = |id: &mut NavAreaId| {
+        if let Some(new) = map.get(id) {
+            *id = *new;
+        }
+    };
+
+    remap(&mut area.id);
+    for direction in area.connections.0.iter_mut() {
+        direction.iter_mut().for_each(remap);
+    }
+    for path in &mut area.encounter_paths {
+        remap(&mut path.from_area_id);
+        remap(&mut path.to_area_id);
+    }
+    for approach in &mut area.approach_areas {
+        remap(&mut approach.approach_here);
+        remap(&mut approach.approach_pre);
+        remap(&mut approach.approach_next);
+    }
+    for visible in &mut area.visible_areas {
+        remap(&mut visible.id);
+    }
+    let mut inherit = NavAreaId::from(area.inherit_visibility_from_area_id);
+    remap(&mut inherit);
+    area.inherit_visibility_from_area_id = inherit.into();
+}
+
+impl NavMesh {
+    /// Merge another navigation mesh into this mesh
+    ///
+    /// Areas and ladders from the other mesh that have the same id as one in this mesh get a new
+    /// id, the place tables are merged by name. Areas from both meshes that share an edge are
+    /// connected to each other. Overlapping areas are handled according to the merge policy.
+    ///
+    /// Ladder references to areas that don't exist in the other mesh are dropped.
+    ///
+    /// The version and custom data of this mesh are kept, the custom data of the other mesh is
+    /// dropped since it can't be combined without knowing it's format. If the game of this mesh
+    /// is unknown, the game of the other mesh is used.
+    ///
+    /// Returns the ids of the areas from the other mesh in the merged mesh, areas dropped
+    /// because of the merge policy are not included. Fails without changing the mesh if the
+    /// merged place table doesn't fit in a nav file.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{read_mesh, MergePolicy};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let mut mesh = read_mesh(std::fs::read("path/to/generated.nav")?)?;
+    /// let edited = read_mesh(std::fs::read("path/to/edited.nav")?)?;
+    /// mesh.merge(edited, MergePolicy::PreferOther)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn merge(
+        &mut self,
+        mut other: NavMesh,
+        policy: MergePolicy,
+    ) -> Result<HashMap<NavAreaId, NavAreaId>, EditError> {
+        // merge the place tables by name
+        let mut new_places = Vec::new();
+        let mut place_map = HashMap::new();
+        for place in &other.places {
+            let id = match self
+                .places
+                .iter()
+                .chain(new_places.iter())
+                .find(|own| own.name == place.name)
+            {
+                Some(own) => own.id,
+                None => {
+                    if self.places.len() + new_places.len() >= u16::MAX as usize {
+                        return Err(EditError::TooManyPlaces);
+                    }
+                    let id = (self.places.len() + new_places.len()) as u16 + 1;
+                    new_places.push(crate::NavPlace {
+                        id,
+                        name: place.name.clone(),
+                    });
+                    id
+                }
+            };
+            place_map.insert(place.id, id);
+        }
+        self.places.extend(new_places);
+
+        // renumber conflicting area ids
+        let existing: HashSet<NavAreaId> = self.areas.iter().map(|area| area.id).collect();
+        let mut next_id = existing
+            .iter()
+            .chain(other.areas.iter().map(|area| &area.id))
+            .map(|id| u32::from(*id))
+            .max()
+            .unwrap_or(0);
+        let mut area_map = HashMap::new();
+        for area in &other.areas {
+            let id = if existing.contains(&area.id) {
+                next_id += 1;
+                NavAreaId::from(next_id)
+            } else {
+                area.id
+            };
+            area_map.insert(area.id, id);
+        }
+
+        // renumber conflicting ladder ids
+        let existing_ladders: HashSet<NavLadderId> =
+            self.ladders.iter().map(|ladder| ladder.id).collect();
+        let mut next_ladder = existing_ladders
+            .iter()
+            .chain(other.ladders.iter().map(|ladder| &ladder.id))
+            .map(|id| u32::from(*id))
+            .max()
+            .unwrap_or(0);
+        let mut ladder_map = HashMap::new();
+        for ladder in &mut other.ladders {
+            if existing_ladders.contains(&ladder.id) {
+                next_ladder += 1;
+                ladder_map.insert(ladder.id, NavLadderId::from(next_ladder));
+                ladder.id = NavLadderId::from(next_ladder);
+            }
+            for area in [
+                &mut ladder.top_forward_area,
+                &mut ladder.top_left_area,
+                &mut ladder.top_right_area,
+                &mut ladder.top_behind_area,
+                &mut ladder.bottom_area,
+            ]
+            .iter_mut()
+            {
+                **area = area.and_then(|id| area_map.get(&id).copied());
+            }
+        }
+
+        for area in &mut other.areas {
+            remap_area(area, &area_map);
+            area.place = place_map.get(&area.place).copied().unwrap_or(0);
+            for direction in area.ladder_connections.0.iter_mut() {
+                for id in direction.iter_mut() {
+                    if let Some(new) = ladder_map.get(id) {
+                        *id = *new;
+                    }
+                }
+            }
+        }
+
+        let removed: Vec<NavAreaId> = match policy {
+            MergePolicy::KeepBoth => Vec::new(),
+            MergePolicy::PreferSelf => other
+                .areas
+                .iter()
+                .filter(|area| self.areas.iter().any(|own| overlaps(own, area)))
+                .map(|area| area.id)
+                .collect(),
+            MergePolicy::PreferOther => self
+                .areas
+                .iter()
+                .filter(|own| other.areas.iter().any(|area| overlaps(own, area)))
+                .map(|area| area.id)
+                .collect(),
+        };
+
+        let own_areas: HashSet<NavAreaId> = self.areas.iter().map(|area| area.id).collect();
+        self.areas.extend(other.areas);
+        self.ladders.extend(other.ladders);
+        self.has_unnamed_areas |= other.has_unnamed_areas;
+        if self.game == NavGame::Unknown {
+            self.game = other.game;
+        }
+
+        let mut builder = NavMeshBuilder::from_mesh(self.clone());
+        // the areas were just added to the builder
+        let _ = builder.remove_areas(&removed);
+
+        // connect the areas along the border between both meshes
+        let mut connections = Vec::new();
+        {
+            let areas = &builder.mesh().areas;
+            let (own, new): (Vec<&NavArea>, Vec<&NavArea>) =
+                areas.iter().partition(|area| own_areas.contains(&area.id));
+            for a in &own {
+                for b in &new {
+                    if let Some(direction) = a.quad.touching_direction(&b.quad, STEP_HEIGHT) {
+                        connections.push((a.id, b.id, direction));
+                    }
+                }
+            }
+        }
+        for (a, b, direction) in connections {
+            // both areas are known to exist
+            let _ = builder.connect(a, b, direction);
+        }
+
+        *self = builder.build();
+
+        let removed: HashSet<NavAreaId> = removed.into_iter().collect();
+        area_map.retain(|_, id| !removed.contains(id));
+        Ok(area_map)
+    }
+}
+
+#[test]
+fn test_merge() {
+    use crate::navmesh::NavDirection;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    // split the mesh in two halves along x and merge them back together
+    let split = 0.0;
+    let mut west = NavMeshBuilder::from_mesh(mesh.clone());
+    let mut east = NavMeshBuilder::from_mesh(mesh.clone());
+    let (west_ids, east_ids): (Vec<_>, Vec<_>) = mesh
+        .areas
+        .iter()
+        .partition(|area| area.quad.north_west.0 < split);
+    let west_ids: Vec<_> = west_ids.into_iter().map(|area| area.id).collect();
+    let east_ids: Vec<_> = east_ids.into_iter().map(|area| area.id).collect();
+    west.remove_areas(&east_ids).unwrap();
+    east.remove_areas(&west_ids).unwrap();
+    let mut merged = west.build();
+    let west_count = merged.areas.len();
+    let map = merged.merge(east.build(), MergePolicy::KeepBoth).unwrap();
+
+    assert_eq!(1930, merged.areas.len());
+    assert_eq!(1930 - west_count, map.len());
+    // all ids are unique, so no area is renumbered
+    assert!(map.iter().all(|(old, new)| old == new));
+    assert!(merged.is_consistent(None));
+
+    // areas that were connected across the split are connected again
+    let view = merged.view();
+    let original = mesh.view();
+    let crossing = mesh
+        .areas
+        .iter()
+        .filter(|area| area.quad.north_west.0 < split)
+        .flat_map(|area| {
+            NavDirection::ALL.iter().flat_map(move |direction| {
+                area.connections[*direction]
+                    .iter()
+                    .map(move |target| (area, *target, *direction))
+            })
+        })
+        .filter(|(_, target, _)| original.area(*target).unwrap().quad.north_west.0 >= split)
+        .filter(|(area, target, _)| {
+            area.quad
+                .touching_direction(&original.area(*target).unwrap().quad, STEP_HEIGHT)
+                .is_some()
+        })
+        .collect::<Vec<_>>();
+    assert!(!crossing.is_empty());
+    for (area, target, direction) in crossing {
+        assert!(view.area(area.id).unwrap().connections[direction].contains(&target));
+    }
+
+    // merging a mesh into itself with a preference drops all duplicate areas
+    let mut own = mesh.clone();
+    let map = own.merge(mesh.clone(), MergePolicy::PreferSelf).unwrap();
+    assert_eq!(1930, own.areas.len());
+    assert!(map.is_empty());
+}
+
+#[test]
+fn test_merge_dangling_and_places() {
+    use crate::navmesh::{NavDirection, NavLadder, NavQuad, Vector3};
+    use crate::NavPlace;
+
+    let quad = |x: f32| NavQuad {
+        north_west: Vector3(x, 0.0, 0.0),
+        south_east: Vector3(x + 100.0, 100.0, 0.0),
+        north_east_z: 0.0,
+        south_west_z: 0.0,
+    };
+    let mut builder = NavMeshBuilder::new(NavGame::Unknown);
+    builder.add_area(quad(0.0)).unwrap();
+    let mut mesh = builder.build();
+
+    let mut builder = NavMeshBuilder::new(NavGame::CounterStrike);
+    let other_area = builder.add_area(quad(500.0)).unwrap();
+    builder.set_place(other_area, Some("other")).unwrap();
+    let mut other = builder.build();
+    other.ladders.push(NavLadder {
+        id: NavLadderId::from(1),
+        width: 32.0,
+        top: Vector3(550.0, 0.0, 100.0),
+        bottom: Vector3(550.0, 0.0, 0.0),
+        length: 100.0,
+        direction: NavDirection::North,
+        is_dangling: false,
+        top_forward_area: Some(NavAreaId::from(1234)),
+        top_left_area: None,
+        top_right_area: None,
+        top_behind_area: None,
+        bottom_area: Some(other_area),
+    });
+    other.custom_data = vec![1, 2, 3];
+
+    // a full place table can't take the place of the other mesh
+    let mut full = mesh.clone();
+    full.places = (1..=u16::MAX)
+        .map(|id| NavPlace {
+            id,
+            name: format!("place{}", id),
+        })
+        .collect();
+    let before = full.clone();
+    assert!(matches!(
+        full.merge(other.clone(), MergePolicy::KeepBoth),
+        Err(EditError::TooManyPlaces)
+    ));
+    assert_eq!(before, full);
+
+    let map = mesh.merge(other, MergePolicy::KeepBoth).unwrap();
+    let new_area = map[&other_area];
+    assert_eq!(NavGame::CounterStrike, mesh.game);
+    assert!(mesh.custom_data.is_empty());
+    assert_eq!(None, mesh.ladders[0].top_forward_area);
+    assert_eq!(Some(new_area), mesh.ladders[0].bottom_area);
+    let place = mesh.view().area(new_area).unwrap().place;
+    assert_eq!("other", mesh.place(place).unwrap().name);
+}
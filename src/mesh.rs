@@ -0,0 +1,336 @@
+use crate::custom::{CustomAreaData, NavGame};
+use crate::navmesh::{
+    ApproachArea, EncounterPath, NavArea, NavAreaId, NavLadder, NavLadderId, NavPlace, Vector3,
+    VisibleArea,
+};
+use crate::tree::extent;
+use std::collections::HashMap;
+
+/// A fully parsed nav file
+///
+/// Contains the header information, the place table and all navigation areas
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NavMesh {
+    /// The game the nav file was parsed for
+    pub game: NavGame,
+    pub major_version: u32,
+    pub minor_version: u32,
+    /// The size of the bsp file the nav was generated for
+    pub bsp_size: u32,
+    pub is_analyzed: bool,
+    pub places: Vec<NavPlace>,
+    pub has_unnamed_areas: bool,
+    pub areas: Vec<NavArea>,
+    pub ladders: Vec<NavLadder>,
+    /// Game specific data stored after the ladders
+    pub custom_data: Vec<u8>,
+}
+
+/// A reference to a place, by either it's name or id
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PlaceRef<'a> {
+    Name(&'a str),
+    Id(u16),
+}
+
+impl<'a> From<&'a str> for PlaceRef<'a> {
+    fn from(name: &'a str) -> Self {
+        PlaceRef::Name(name)
+    }
+}
+
+impl From<u16> for PlaceRef<'_> {
+    fn from(id: u16) -> Self {
+        PlaceRef::Id(id)
+    }
+}
+
+/// All areas of a place, see [`NavMesh::areas_in_place`]
+///
+/// [`NavMesh::areas_in_place`]: ./struct.NavMesh.html#method.areas_in_place
+#[derive(Debug, Clone)]
+pub struct PlaceAreas<'a> {
+    pub place: &'a NavPlace,
+    pub areas: Vec<&'a NavArea>,
+    /// The minimum corner of the bounding box of all areas
+    pub min: Vector3,
+    /// The maximum corner of the bounding box of all areas
+    pub max: Vector3,
+}
+
+impl NavMesh {
+    /// Get a place by it's id
+    ///
+    /// Returns `None` for the id `0`, which is used for areas without a place
+    pub fn place(&self, id: u16) -> Option<&NavPlace> {
+        if id == 0 {
+            return None;
+        }
+        self.places.get(id as usize - 1)
+    }
+
+    /// Get the place a navigation area belongs to
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// for area in &mesh.areas {
+    ///     if let Some(place) = mesh.area_place(area) {
+    ///         println!("area {} is in {}", area.id, place.name);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn area_place(&self, area: &NavArea) -> Option<&NavPlace> {
+        self.place(area.place)
+    }
+
+    /// Get all areas of a place and their combined bounding box
+    ///
+    /// Returns `None` if the place doesn't exist
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// if let Some(spawn) = mesh.areas_in_place("BlueSpawn") {
+    ///     println!("the blue spawn spans from {:?} to {:?}", spawn.min, spawn.max);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn areas_in_place<'a>(&self, place: impl Into<PlaceRef<'a>>) -> Option<PlaceAreas<'_>> {
+        let place = match place.into() {
+            PlaceRef::Name(name) => self.places.iter().find(|place| place.name == name)?,
+            PlaceRef::Id(id) => self.place(id)?,
+        };
+
+        let areas: Vec<&NavArea> = self
+            .areas
+            .iter()
+            .filter(|area| area.place == place.id)
+            .collect();
+        let (min, max) = extent(areas.iter().copied());
+
+        Some(PlaceAreas {
+            place,
+            areas,
+            min,
+            max,
+        })
+    }
+
+    /// Get a ladder by it's id
+    pub fn ladder(&self, id: NavLadderId) -> Option<&NavLadder> {
+        self.ladders.iter().find(|ladder| ladder.id == id)
+    }
+
+    /// Decode the game specific data of an area
+    pub fn custom_area_data<T: CustomAreaData>(&self, area: &NavArea) -> Option<T> {
+        T::decode(&area.custom_data, self.game)
+    }
+
+    /// Create a view of the mesh that resolves area ids into areas
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let view = mesh.view();
+    /// for area in &mesh.areas {
+    ///     for (visible, _) in view.visible_areas(area) {
+    ///         println!("area {} can see area {}", area.id, visible.id);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn view(&self) -> NavMeshView<'_> {
+        NavMeshView::new(self)
+    }
+}
+
+/// A view of a navigation mesh with an index of all area ids
+///
+/// Used to resolve the area ids referenced by areas into the areas themselves
+#[derive(Debug, Clone)]
+pub struct NavMeshView<'a> {
+    mesh: &'a NavMesh,
+    index: HashMap<NavAreaId, usize>,
+}
+
+impl<'a> NavMeshView<'a> {
+    pub fn new(mesh: &'a NavMesh) -> Self {
+        let index = mesh
+            .areas
+            .iter()
+            .enumerate()
+            .map(|(index, area)| (area.id, index))
+            .collect();
+
+        NavMeshView { mesh, index }
+    }
+
+    /// Get the mesh this view was created from
+    pub fn mesh(&self) -> &'a NavMesh {
+        self.mesh
+    }
+
+    /// Get an area by it's id
+    pub fn area(&self, id: NavAreaId) -> Option<&'a NavArea> {
+        let mesh = self.mesh;
+        self.index.get(&id).map(|index| &mesh.areas[*index])
+    }
+
+    /// Get the areas an encounter path goes from and to
+    pub fn encounter_path_areas(
+        &self,
+        path: &EncounterPath,
+    ) -> (Option<&'a NavArea>, Option<&'a NavArea>) {
+        (self.area(path.from_area_id), self.area(path.to_area_id))
+    }
+
+    /// Get the current, previous and next areas of an approach area
+    pub fn approach_areas(
+        &self,
+        approach: &ApproachArea,
+    ) -> (
+        Option<&'a NavArea>,
+        Option<&'a NavArea>,
+        Option<&'a NavArea>,
+    ) {
+        (
+            self.area(approach.approach_here),
+            self.area(approach.approach_pre),
+            self.area(approach.approach_next),
+        )
+    }
+
+    /// Get the area referenced by a visible area entry
+    pub fn visible_area(&self, visible: &VisibleArea) -> Option<&'a NavArea> {
+        self.area(visible.id)
+    }
+
+    /// Get all areas that are visible from an area, with their visibility attributes
+    ///
+    /// Unknown area ids are skipped
+    pub fn visible_areas<'b>(
+        &'b self,
+        area: &'a NavArea,
+    ) -> impl Iterator<Item = (&'a NavArea, &'a VisibleArea)> + 'b {
+        area.visible_areas
+            .iter()
+            .filter_map(move |visible| Some((self.visible_area(visible)?, visible)))
+    }
+}
+
+#[test]
+fn test_view() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    let view = mesh.view();
+
+    for area in &mesh.areas {
+        assert_eq!(area.id, view.area(area.id).unwrap().id);
+
+        for path in &area.encounter_paths {
+            let (from, to) = view.encounter_path_areas(path);
+            assert!(from.is_some());
+            assert!(to.is_some());
+        }
+        assert_eq!(area.visible_areas.len(), view.visible_areas(area).count());
+    }
+}
+
+#[test]
+fn test_custom_area_data() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh_for_game(file.clone(), NavGame::TeamFortress2).unwrap();
+
+    assert!(mesh
+        .areas
+        .iter()
+        .all(|area| mesh.custom_area_data::<u32>(area).is_some()));
+
+    // the layout of the area data isn't known for every game
+    assert!(matches!(
+        crate::read_mesh_for_game(file, NavGame::CounterStrike),
+        Err(crate::ParseError::UnknownAreaDataSize(
+            NavGame::CounterStrike
+        ))
+    ));
+
+    // areas of an unknown game have no game specific data
+    let mut builder = crate::NavMeshBuilder::new(NavGame::Unknown);
+    builder
+        .add_area(crate::NavQuad {
+            north_west: crate::Vector3(0.0, 0.0, 0.0),
+            south_east: crate::Vector3(100.0, 100.0, 0.0),
+            north_east_z: 0.0,
+            south_west_z: 0.0,
+        })
+        .unwrap();
+    builder
+        .add_area(crate::NavQuad {
+            north_west: crate::Vector3(100.0, 0.0, 0.0),
+            south_east: crate::Vector3(200.0, 100.0, 0.0),
+            north_east_z: 0.0,
+            south_west_z: 0.0,
+        })
+        .unwrap();
+    let mesh = builder.build();
+    let written = mesh.to_bytes(mesh.major_version).unwrap();
+    let read = crate::read_mesh_for_game(written, NavGame::Unknown).unwrap();
+    assert_eq!(mesh.areas, read.areas);
+    assert!(read.areas.iter().all(|area| area.custom_data.is_empty()));
+}
+
+#[test]
+fn test_areas_in_place() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    assert!(mesh.areas_in_place("Spawn").is_none());
+
+    let first = mesh.areas[0].id;
+    let second = mesh.areas[1].id;
+    let mut builder = crate::NavMeshBuilder::from_mesh(mesh);
+    builder.set_place(first, Some("Spawn")).unwrap();
+    builder.set_place(second, Some("Spawn")).unwrap();
+    let mesh = builder.build();
+
+    let spawn = mesh.areas_in_place("Spawn").unwrap();
+    assert_eq!(2, spawn.areas.len());
+    let by_id = mesh.areas_in_place(spawn.place.id).unwrap();
+    assert_eq!(spawn.min, by_id.min);
+    for area in &spawn.areas {
+        assert!(area.quad.north_west.0 >= spawn.min.0);
+        assert!(area.quad.south_east.1 <= spawn.max.1);
+    }
+}
+
+#[test]
+#[cfg(feature = "serde")]
+fn test_serde() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let json = serde_json::to_string(&mesh).unwrap();
+    let parsed: NavMesh = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(mesh, parsed);
+}
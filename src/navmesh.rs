@@ -5,13 +5,25 @@ use euclid::{TypedPoint2D, TypedSize2D};
 use std::fmt;
 use std::fmt::Debug;
 use std::ops::Index;
+use std::time::Duration;
 
 /// A 3 dimensional coordinate
-#[derive(Debug, BitRead)]
+#[derive(Debug, BitRead, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector3(pub f32, pub f32, pub f32);
 
+impl Vector3 {
+    /// Get the distance between two points
+    pub fn distance(self, other: Vector3) -> f32 {
+        ((self.0 - other.0).powi(2) + (self.1 - other.1).powi(2) + (self.2 - other.2).powi(2))
+            .sqrt()
+    }
+}
+
 /// A unique identifier for a navigation area
-#[derive(Debug, BitRead, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, BitRead, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct NavAreaId(u32);
 
 impl fmt::Display for NavAreaId {
@@ -20,8 +32,45 @@ impl fmt::Display for NavAreaId {
     }
 }
 
+impl From<u32> for NavAreaId {
+    fn from(id: u32) -> Self {
+        NavAreaId(id)
+    }
+}
+
+impl From<NavAreaId> for u32 {
+    fn from(id: NavAreaId) -> Self {
+        id.0
+    }
+}
+
+/// A unique identifier for a ladder
+#[derive(Debug, BitRead, Clone, Copy, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct NavLadderId(u32);
+
+impl fmt::Display for NavLadderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<u32> for NavLadderId {
+    fn from(id: u32) -> Self {
+        NavLadderId(id)
+    }
+}
+
+impl From<NavLadderId> for u32 {
+    fn from(id: NavLadderId) -> Self {
+        id.0
+    }
+}
+
 /// A navigation area from the nav file
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NavArea {
     pub id: NavAreaId,
     pub quad: NavQuad,
@@ -37,6 +86,126 @@ pub struct NavArea {
     pub earliest_occupy_second_team: f32,
     pub visible_areas: Vec<VisibleArea>,
     pub inherit_visibility_from_area_id: u32,
+    /// The raw game specific data of the area, see [`CustomAreaData`]
+    ///
+    /// [`CustomAreaData`]: ./trait.CustomAreaData.html
+    pub custom_data: Vec<u8>,
+}
+
+impl NavArea {
+    /// Get the z height of a x/y point inside the navigation area, interpolated from the heights
+    /// of the corners
+    pub fn get_z_height(&self, x: f32, y: f32) -> f32 {
+        self.quad.interpolated_z(x, y)
+    }
+
+    /// Get the center of the area, with the height interpolated from the corners
+    pub fn center(&self) -> Vector3 {
+        self.quad.center()
+    }
+
+    /// Whether a x/y point is inside the navigation area, including it's edges
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        self.quad.contains(x, y)
+    }
+
+    /// Get the upwards facing unit normal of the surface of the area at a x/y point
+    ///
+    /// Points outside of the area are clamped to the edge of the area
+    pub fn normal_at(&self, x: f32, y: f32) -> Vector3 {
+        let (dx, dy) = self.quad.gradient(x, y);
+        let length = (dx * dx + dy * dy + 1.0).sqrt();
+        Vector3(-dx / length, -dy / length, 1.0 / length)
+    }
+
+    /// Get the angle between the surface of the area and the horizontal plane at a x/y point in
+    /// degrees
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_areas;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let areas = read_areas(file)?;
+    /// let ramps = areas
+    ///     .iter()
+    ///     .filter(|area| {
+    ///         let center = area.quad.corners()[0];
+    ///         area.slope_at(center.0, center.1) > 10.0
+    ///     })
+    ///     .count();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn slope_at(&self, x: f32, y: f32) -> f32 {
+        self.normal_at(x, y).2.clamp(-1.0, 1.0).acos().to_degrees()
+    }
+
+    /// Get the earliest time after the round start a team can reach this area
+    ///
+    /// Returns `None` if the stored time is negative, not a number or too large for a `Duration`
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{read_areas, Team};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let areas = read_areas(file)?;
+    /// for area in &areas {
+    ///     println!("{}: {:?}", area.id, area.occupy_time(Team::First));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn occupy_time(&self, team: Team) -> Option<Duration> {
+        let seconds = match team {
+            Team::First => self.earliest_occupy_first_team,
+            Team::Second => self.earliest_occupy_second_team,
+        };
+        Duration::try_from_secs_f32(seconds).ok()
+    }
+}
+
+/// The playing teams as used by the team specific data in nav files
+///
+/// The first team is team `2` in the engine (Terrorists in Counter-Strike, RED in Team Fortress 2),
+/// the second team is team `3` (Counter-Terrorists, BLU)
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Team {
+    First,
+    Second,
+}
+
+impl Team {
+    /// Get the team number as used by the engine
+    pub fn engine_team(self) -> u8 {
+        match self {
+            Team::First => 2,
+            Team::Second => 3,
+        }
+    }
+
+    /// Get the team from the team number as used by the engine
+    pub fn from_engine_team(team: u8) -> Option<Team> {
+        match team {
+            2 => Some(Team::First),
+            3 => Some(Team::Second),
+            _ => None,
+        }
+    }
+
+    /// Get the opposing team
+    pub fn other(self) -> Team {
+        match self {
+            Team::First => Team::Second,
+            Team::Second => Team::First,
+        }
+    }
 }
 
 pub(crate) struct HammerUnit;
@@ -59,8 +228,9 @@ pub(crate) struct HammerUnit;
 /// ```
 ///
 /// [`NavDirection`]: ./enum.NavDirection.html
-#[derive(Debug, Default)]
-pub struct Connections([Vec<NavAreaId>; 4]);
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Connections(pub(crate) [Vec<NavAreaId>; 4]);
 
 impl<E: Endianness> BitRead<E> for Connections {
     fn read(stream: &mut BitReadStream<E>) -> Result<Self, ReadError> {
@@ -86,6 +256,13 @@ impl<E: Endianness> BitRead<E> for Connections {
     }
 }
 
+impl Connections {
+    /// Iterate over the connected area ids in all directions
+    pub fn iter(&self) -> impl Iterator<Item = &NavAreaId> {
+        self.0.iter().flatten()
+    }
+}
+
 impl Index<NavDirection> for Connections {
     type Output = Vec<NavAreaId>;
 
@@ -94,9 +271,9 @@ impl Index<NavDirection> for Connections {
     }
 }
 
-/// The connections from a navigation area into it's neighbours
+/// The ladders connected to a navigation area
 ///
-/// Contains a list of area id's for every [`NavDirection`]
+/// Contains a list of ladder id's for every [`LadderDirection`]
 ///
 /// # Examples
 ///
@@ -111,9 +288,10 @@ impl Index<NavDirection> for Connections {
 /// let down_connections = &connections[LadderDirection::Down];
 /// ```
 ///
-/// [`NavDirection`]: ./enum.NavDirection.html
-#[derive(Debug, Default)]
-pub struct LadderConnections([Vec<NavAreaId>; 2]);
+/// [`LadderDirection`]: ./enum.LadderDirection.html
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LadderConnections(pub(crate) [Vec<NavLadderId>; 2]);
 
 impl<E: Endianness> BitRead<E> for LadderConnections {
     fn read(stream: &mut BitReadStream<E>) -> Result<Self, ReadError> {
@@ -139,8 +317,15 @@ impl<E: Endianness> BitRead<E> for LadderConnections {
     }
 }
 
+impl LadderConnections {
+    /// Iterate over the connected ladder ids in both directions
+    pub fn iter(&self) -> impl Iterator<Item = &NavLadderId> {
+        self.0.iter().flatten()
+    }
+}
+
 impl Index<LadderDirection> for LadderConnections {
-    type Output = Vec<NavAreaId>;
+    type Output = Vec<NavLadderId>;
 
     fn index(&self, index: LadderDirection) -> &Self::Output {
         &self.0[index as u8 as usize]
@@ -148,7 +333,8 @@ impl Index<LadderDirection> for LadderConnections {
 }
 
 /// The directions in which two areas can be connected
-#[derive(Debug, BitRead)]
+#[derive(Debug, BitRead, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 #[discriminant_bits = 8]
 pub enum NavDirection {
@@ -158,8 +344,43 @@ pub enum NavDirection {
     West,
 }
 
+impl NavDirection {
+    /// All directions, in the order they are stored in the nav file
+    pub const ALL: [NavDirection; 4] = [
+        NavDirection::North,
+        NavDirection::East,
+        NavDirection::South,
+        NavDirection::West,
+    ];
+
+    pub(crate) fn from_index(index: u32) -> Option<Self> {
+        NavDirection::ALL.get(index as usize).copied()
+    }
+
+    /// The direction pointing the other way
+    pub fn opposite(self) -> Self {
+        match self {
+            NavDirection::North => NavDirection::South,
+            NavDirection::East => NavDirection::West,
+            NavDirection::South => NavDirection::North,
+            NavDirection::West => NavDirection::East,
+        }
+    }
+
+    /// The lowercase name of the direction
+    pub fn name(self) -> &'static str {
+        match self {
+            NavDirection::North => "north",
+            NavDirection::East => "east",
+            NavDirection::South => "south",
+            NavDirection::West => "west",
+        }
+    }
+}
+
 /// The directions in which two areas can be connected by ladder
-#[derive(Debug, BitRead)]
+#[derive(Debug, BitRead, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 #[discriminant_bits = 8]
 pub enum LadderDirection {
@@ -167,43 +388,113 @@ pub enum LadderDirection {
     Down,
 }
 
+impl LadderDirection {
+    /// Both directions, in the order they are stored in the nav file
+    pub const ALL: [LadderDirection; 2] = [LadderDirection::Up, LadderDirection::Down];
+
+    /// The lowercase name of the direction
+    pub fn name(self) -> &'static str {
+        match self {
+            LadderDirection::Up => "up",
+            LadderDirection::Down => "down",
+        }
+    }
+}
+
+/// A ladder connecting navigation areas
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NavLadder {
+    pub id: NavLadderId,
+    pub width: f32,
+    pub top: Vector3,
+    pub bottom: Vector3,
+    pub length: f32,
+    /// The direction the ladder is facing
+    pub direction: NavDirection,
+    /// Only stored in version 6 nav files
+    pub is_dangling: bool,
+    pub top_forward_area: Option<NavAreaId>,
+    pub top_left_area: Option<NavAreaId>,
+    pub top_right_area: Option<NavAreaId>,
+    pub top_behind_area: Option<NavAreaId>,
+    pub bottom_area: Option<NavAreaId>,
+}
+
+impl NavLadder {
+    /// Get the areas reached by climbing the ladder in a direction
+    ///
+    /// Climbing up reaches the areas at the top of the ladder, climbing down reaches the area at
+    /// the bottom
+    pub fn areas(&self, direction: LadderDirection) -> impl Iterator<Item = NavAreaId> {
+        let areas = match direction {
+            LadderDirection::Up => [
+                self.top_forward_area,
+                self.top_left_area,
+                self.top_right_area,
+                self.top_behind_area,
+            ],
+            LadderDirection::Down => [self.bottom_area, None, None, None],
+        };
+        IntoIterator::into_iter(areas).flatten()
+    }
+}
+
 /// A hiding spot within an area
-#[derive(Debug, BitRead)]
+#[derive(Debug, BitRead, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NavHidingSpot {
-    id: u32,
-    location: Vector3,
-    flags: u8,
+    pub id: u32,
+    pub location: Vector3,
+    pub flags: u8,
+}
+
+impl NavHidingSpot {
+    pub const IN_COVER: u8 = 0x1;
+    pub const GOOD_SNIPER_SPOT: u8 = 0x2;
+    pub const IDEAL_SNIPER_SPOT: u8 = 0x4;
+    pub const EXPOSED: u8 = 0x8;
+
+    /// Whether the hiding spot has all of the given flags
+    pub fn has_flags(&self, flags: u8) -> bool {
+        self.flags & flags == flags
+    }
 }
 
 /// An area that can be used for approach, no longer used in newer nav files
-#[derive(Debug, BitRead)]
+#[derive(Debug, BitRead, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ApproachArea {
-    approach_here: u32,
-    approach_pre: u32,
-    approach_type: u8,
-    approach_next: u32,
-    approach_how: u8,
+    pub approach_here: NavAreaId,
+    pub approach_pre: NavAreaId,
+    pub approach_type: u8,
+    pub approach_next: NavAreaId,
+    pub approach_how: u8,
 }
 
 /// A path that can be used to approach an area
-#[derive(Debug, BitRead)]
+#[derive(Debug, BitRead, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EncounterPath {
-    from_area_id: NavAreaId,
-    from_direction: u8,
-    to_area_id: NavAreaId,
-    to_direction: u8,
+    pub from_area_id: NavAreaId,
+    pub from_direction: u8,
+    pub to_area_id: NavAreaId,
+    pub to_direction: u8,
     #[size_bits = 8]
-    spots: Vec<EncounterSpot>,
+    pub spots: Vec<EncounterSpot>,
 }
 
-#[derive(Debug, BitRead)]
+/// A spot along an encounter path
+#[derive(Debug, BitRead, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EncounterSpot {
-    order: u32,
-    distance: u8, // divide by 255
+    pub order: u32,
+    pub distance: u8, // divide by 255
 }
 
 /// The light intensity at the four corners of an area
-#[derive(Debug, BitRead, Default)]
+#[derive(Debug, BitRead, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LightIntensity {
     pub north_west: f32,
     pub north_east: f32,
@@ -212,20 +503,37 @@ pub struct LightIntensity {
 }
 
 /// An area that is visible
-#[derive(Debug, BitRead)]
+#[derive(Debug, BitRead, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VisibleArea {
-    id: u32,
-    attributes: u8,
+    pub id: NavAreaId,
+    pub attributes: u8,
+}
+
+impl VisibleArea {
+    pub const POTENTIALLY_VISIBLE: u8 = 0x1;
+    pub const PARTIALLY_VISIBLE: u8 = 0x2;
+    pub const COMPLETELY_VISIBLE: u8 = 0x4;
+
+    /// Whether the area is potentially visible
+    pub fn is_visible(&self) -> bool {
+        self.attributes & VisibleArea::POTENTIALLY_VISIBLE != 0
+    }
 }
 
-#[derive(Debug)]
+/// A named place from the nav file's place table
+///
+/// Areas refer to their place by id, where `0` means the area has no place
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NavPlace {
-    id: u32,
-    name: String,
+    pub id: u16,
+    pub name: String,
 }
 
 /// A navigation area from the nav file
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NavQuad {
     pub north_west: Vector3,
     pub south_east: Vector3,
@@ -241,6 +549,156 @@ impl NavQuad {
         self.south_east.1 - self.north_west.1
     }
 
+    /// Whether a x/y point is inside the footprint of the quad, including it's edges
+    pub fn contains(&self, x: f32, y: f32) -> bool {
+        (self.north_west.0..=self.south_east.0).contains(&x)
+            && (self.north_west.1..=self.south_east.1).contains(&y)
+    }
+
+    /// Get the four corners of the quad, in the order north west, north east, south east, south west
+    pub fn corners(&self) -> [Vector3; 4] {
+        [
+            self.north_west,
+            Vector3(self.south_east.0, self.north_west.1, self.north_east_z),
+            self.south_east,
+            Vector3(self.north_west.0, self.south_east.1, self.south_west_z),
+        ]
+    }
+
+    /// Get the area of the sloped surface of the quad
+    pub(crate) fn surface_area(&self) -> f32 {
+        let [nw, ne, se, sw] = self.corners();
+        let triangle = |a: Vector3, b: Vector3, c: Vector3| {
+            let (u, v) = (
+                (b.0 - a.0, b.1 - a.1, b.2 - a.2),
+                (c.0 - a.0, c.1 - a.1, c.2 - a.2),
+            );
+            let cross = (
+                u.1 * v.2 - u.2 * v.1,
+                u.2 * v.0 - u.0 * v.2,
+                u.0 * v.1 - u.1 * v.0,
+            );
+            (cross.0 * cross.0 + cross.1 * cross.1 + cross.2 * cross.2).sqrt() / 2.0
+        };
+        triangle(nw, ne, se) + triangle(nw, se, sw)
+    }
+
+    /// Get the rate of change of the interpolated height along the x and y axis at a point
+    pub(crate) fn gradient(&self, x: f32, y: f32) -> (f32, f32) {
+        let (width, height) = (self.width(), self.height());
+        if width <= 0.0 || height <= 0.0 {
+            return (0.0, 0.0);
+        }
+        let u = ((x - self.north_west.0) / width).clamp(0.0, 1.0);
+        let v = ((y - self.north_west.1) / height).clamp(0.0, 1.0);
+        let north = self.north_east_z - self.north_west.2;
+        let south = self.south_east.2 - self.south_west_z;
+        let west = self.south_west_z - self.north_west.2;
+        let east = self.south_east.2 - self.north_east_z;
+        (
+            (north + (south - north) * v) / width,
+            (west + (east - west) * u) / height,
+        )
+    }
+
+    /// Get the horizontal distance from a point to the quad, `0` for points inside the quad
+    pub(crate) fn distance_2d(&self, x: f32, y: f32) -> f32 {
+        let dx = (self.north_west.0 - x).max(x - self.south_east.0).max(0.0);
+        let dy = (self.north_west.1 - y).max(y - self.south_east.1).max(0.0);
+        (dx * dx + dy * dy).sqrt()
+    }
+
+    /// Get the point on the surface of the quad closest to a x/y coordinate
+    pub(crate) fn closest_point(&self, x: f32, y: f32) -> Vector3 {
+        let x = x.clamp(self.north_west.0, self.south_east.0);
+        let y = y.clamp(self.north_west.1, self.south_east.1);
+        Vector3(x, y, self.interpolated_z(x, y))
+    }
+
+    /// Get the horizontal distance from a line segment to the quad, `0` if the segment crosses
+    /// the quad
+    pub(crate) fn segment_distance_2d(&self, from: (f32, f32), to: (f32, f32)) -> f32 {
+        let delta = (to.0 - from.0, to.1 - from.1);
+        let (mut start, mut end) = (0.0f32, 1.0f32);
+        let mut crosses = true;
+        for (origin, delta, min, max) in [
+            (from.0, delta.0, self.north_west.0, self.south_east.0),
+            (from.1, delta.1, self.north_west.1, self.south_east.1),
+        ]
+        .iter()
+        {
+            if *delta == 0.0 {
+                crosses &= (*min..=*max).contains(origin);
+            } else {
+                let (a, b) = ((min - origin) / delta, (max - origin) / delta);
+                start = start.max(a.min(b));
+                end = end.min(a.max(b));
+            }
+        }
+        if crosses && start <= end {
+            return 0.0;
+        }
+
+        let point_to_segment = |x: f32, y: f32| {
+            let length = delta.0 * delta.0 + delta.1 * delta.1;
+            let t = if length > 0.0 {
+                (((x - from.0) * delta.0 + (y - from.1) * delta.1) / length).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            ((from.0 + delta.0 * t - x).powi(2) + (from.1 + delta.1 * t - y).powi(2)).sqrt()
+        };
+        self.corners()
+            .iter()
+            .map(|corner| point_to_segment(corner.0, corner.1))
+            .chain(vec![
+                self.distance_2d(from.0, from.1),
+                self.distance_2d(to.0, to.1),
+            ])
+            .fold(f32::INFINITY, f32::min)
+    }
+
+    /// Whether the quad overlaps a convex polygon when seen from above
+    ///
+    /// The polygon can be in either winding order, an empty polygon overlaps nothing
+    pub(crate) fn intersects_polygon(&self, polygon: &[(f32, f32)]) -> bool {
+        if polygon.is_empty() {
+            return false;
+        }
+        let corners = self.corners();
+        let overlaps_on = |axis: (f32, f32)| {
+            let project = |(x, y): (f32, f32)| x * axis.0 + y * axis.1;
+            let range = |points: &mut dyn Iterator<Item = f32>| {
+                points.fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), value| {
+                    (min.min(value), max.max(value))
+                })
+            };
+            let quad = range(&mut corners.iter().map(|corner| project((corner.0, corner.1))));
+            let polygon = range(&mut polygon.iter().map(|point| project(*point)));
+            quad.0 <= polygon.1 && polygon.0 <= quad.1
+        };
+
+        // separating axis test, using the axes of the quad and the normals of the polygon edges
+        let edge_normals = polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(from, to)| (from.1 - to.1, to.0 - from.0));
+        overlaps_on((1.0, 0.0))
+            && overlaps_on((0.0, 1.0))
+            && edge_normals
+                .filter(|normal| *normal != (0.0, 0.0))
+                .all(overlaps_on)
+    }
+
+    /// Get the center of the quad, with the height interpolated from the corners
+    pub(crate) fn center(&self) -> Vector3 {
+        Vector3(
+            (self.north_west.0 + self.south_east.0) / 2.0,
+            (self.north_west.1 + self.south_east.1) / 2.0,
+            self.corners().iter().map(|corner| corner.2).sum::<f32>() / 4.0,
+        )
+    }
+
     /// Get the z height of a x/y point inside the navigation area
     ///
     /// # Examples
@@ -273,6 +731,137 @@ impl NavQuad {
     }
 }
 
+impl NavQuad {
+    /// Interpolate the height of a point from the heights of the corners
+    ///
+    /// Points outside of the quad are clamped to the edge of the quad
+    pub(crate) fn interpolated_z(&self, x: f32, y: f32) -> f32 {
+        let u = ((x - self.north_west.0) / self.width()).clamp(0.0, 1.0);
+        let v = ((y - self.north_west.1) / self.height()).clamp(0.0, 1.0);
+        let north = self.north_west.2 + (self.north_east_z - self.north_west.2) * u;
+        let south = self.south_west_z + (self.south_east.2 - self.south_west_z) * u;
+        let height = north + (south - north) * v;
+        if height.is_finite() {
+            height
+        } else {
+            self.north_west.2
+        }
+    }
+
+    /// Get the fixed coordinate of a side of the quad and the range it spans along the side
+    pub(crate) fn side(&self, direction: NavDirection) -> (f32, (f32, f32)) {
+        let x_range = (self.north_west.0, self.south_east.0);
+        let y_range = (self.north_west.1, self.south_east.1);
+        match direction {
+            NavDirection::North => (self.north_west.1, x_range),
+            NavDirection::East => (self.south_east.0, y_range),
+            NavDirection::South => (self.south_east.1, x_range),
+            NavDirection::West => (self.north_west.0, y_range),
+        }
+    }
+
+    /// Get the part of a side of the quad that is shared with a quad next to it
+    ///
+    /// The ends of the shared edge are returned as the left and right end as seen when moving
+    /// out of the quad, with the height of this quad at both ends.
+    pub(crate) fn portal(
+        &self,
+        other: &NavQuad,
+        direction: NavDirection,
+    ) -> Option<(Vector3, Vector3)> {
+        let (fixed, (min, max)) = self.side(direction);
+        let (_, (other_min, other_max)) = other.side(direction.opposite());
+        let (min, max) = (min.max(other_min), max.min(other_max));
+        if min > max {
+            return None;
+        }
+
+        let point = |along: f32| match direction {
+            NavDirection::North | NavDirection::South => {
+                Vector3(along, fixed, self.interpolated_z(along, fixed))
+            }
+            NavDirection::East | NavDirection::West => {
+                Vector3(fixed, along, self.interpolated_z(fixed, along))
+            }
+        };
+        // north is towards negative y, so when looking down the z axis left is towards the
+        // higher coordinate when moving north or east
+        Some(match direction {
+            NavDirection::North | NavDirection::East => (point(max), point(min)),
+            NavDirection::South | NavDirection::West => (point(min), point(max)),
+        })
+    }
+
+    /// Get the direction in which another quad touches this quad
+    ///
+    /// The quads need to share a part of an edge, with a height difference along the shared
+    /// edge of at most `max_step`
+    pub(crate) fn touching_direction(
+        &self,
+        other: &NavQuad,
+        max_step: f32,
+    ) -> Option<NavDirection> {
+        const EPSILON: f32 = 1.0;
+        let overlap = |a_min: f32, a_max: f32, b_min: f32, b_max: f32| {
+            let (min, max) = (a_min.max(b_min), a_max.min(b_max));
+            if max - min > EPSILON {
+                Some((min + max) / 2.0)
+            } else {
+                None
+            }
+        };
+        let (a, b) = (self, other);
+
+        let (direction, x, y) = if (a.south_east.0 - b.north_west.0).abs() < EPSILON {
+            let y = overlap(
+                a.north_west.1,
+                a.south_east.1,
+                b.north_west.1,
+                b.south_east.1,
+            )?;
+            (NavDirection::East, a.south_east.0, y)
+        } else if (a.north_west.0 - b.south_east.0).abs() < EPSILON {
+            let y = overlap(
+                a.north_west.1,
+                a.south_east.1,
+                b.north_west.1,
+                b.south_east.1,
+            )?;
+            (NavDirection::West, a.north_west.0, y)
+        } else if (a.north_west.1 - b.south_east.1).abs() < EPSILON {
+            let x = overlap(
+                a.north_west.0,
+                a.south_east.0,
+                b.north_west.0,
+                b.south_east.0,
+            )?;
+            (NavDirection::North, x, a.north_west.1)
+        } else if (a.south_east.1 - b.north_west.1).abs() < EPSILON {
+            let x = overlap(
+                a.north_west.0,
+                a.south_east.0,
+                b.north_west.0,
+                b.south_east.0,
+            )?;
+            (NavDirection::South, x, a.south_east.1)
+        } else {
+            return None;
+        };
+
+        if (a.interpolated_z(x, y) - b.interpolated_z(x, y)).abs() <= max_step {
+            Some(direction)
+        } else {
+            None
+        }
+    }
+}
+
+impl Spatial<HammerUnit> for NavArea {
+    fn aabb(&self) -> Rect {
+        self.quad.aabb()
+    }
+}
+
 impl Spatial<HammerUnit> for NavQuad {
     fn aabb(&self) -> Rect {
         Rect {
@@ -284,3 +873,55 @@ impl Spatial<HammerUnit> for NavQuad {
         }
     }
 }
+
+#[test]
+fn test_occupy_time() {
+    let mut builder = crate::NavMeshBuilder::new(crate::NavGame::Unknown);
+    let id = builder
+        .add_area(NavQuad {
+            north_west: Vector3(0.0, 0.0, 0.0),
+            south_east: Vector3(100.0, 100.0, 0.0),
+            north_east_z: 0.0,
+            south_west_z: 0.0,
+        })
+        .unwrap();
+    let mut area = builder.area(id).unwrap().clone();
+
+    area.earliest_occupy_first_team = 12.5;
+    area.earliest_occupy_second_team = 0.0;
+    assert_eq!(
+        Some(Duration::from_millis(12500)),
+        area.occupy_time(Team::First)
+    );
+    assert_eq!(Some(Duration::ZERO), area.occupy_time(Team::Second));
+
+    area.earliest_occupy_first_team = f32::MAX;
+    area.earliest_occupy_second_team = -1.0;
+    assert_eq!(None, area.occupy_time(Team::First));
+    assert_eq!(None, area.occupy_time(Team::Second));
+
+    area.earliest_occupy_first_team = f32::NAN;
+    area.earliest_occupy_second_team = f32::INFINITY;
+    assert_eq!(None, area.occupy_time(Team::First));
+    assert_eq!(None, area.occupy_time(Team::Second));
+}
+
+#[test]
+fn test_area_z_height() {
+    let mut builder = crate::NavMeshBuilder::new(crate::NavGame::Unknown);
+    // a slope rising from north to south, away from the origin
+    let id = builder
+        .add_area(NavQuad {
+            north_west: Vector3(100.0, 200.0, 0.0),
+            south_east: Vector3(200.0, 400.0, 100.0),
+            north_east_z: 0.0,
+            south_west_z: 100.0,
+        })
+        .unwrap();
+    let area = builder.area(id).unwrap();
+
+    assert_eq!(0.0, area.get_z_height(150.0, 200.0));
+    assert_eq!(25.0, area.get_z_height(150.0, 250.0));
+    assert_eq!(50.0, area.get_z_height(120.0, 300.0));
+    assert_eq!(100.0, area.get_z_height(180.0, 400.0));
+}
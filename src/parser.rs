@@ -1,24 +1,29 @@
 pub use crate::navmesh::NavArea;
-use crate::navmesh::NavQuad;
-use crate::{Connections, EncounterPath, LadderConnections, NavHidingSpot, VisibleArea};
+use crate::navmesh::{NavAreaId, NavDirection, NavLadder, NavPlace, NavQuad};
+use crate::{
+    Connections, EncounterPath, LadderConnections, NavGame, NavHidingSpot, NavMesh, VisibleArea,
+};
 use bitbuffer::{BitRead, BitReadStream, LittleEndian};
-use err_derive::Error;
+use thiserror::Error;
 
 /// Errors that can occur when parsing the binary nav file
 #[derive(Debug, Error)]
 pub enum ParseError {
     /// An error ocured when reading from the source binary data
-    #[error(display = "Error while reading from data: {}", _0)]
-    ReadError(#[error(source)] bitbuffer::ReadError),
-    #[error(
-        display = "Invalid magic number ({:#8X}), not a nav file or corrupted",
-        _0
-    )]
+    #[error("Error while reading from data: {0}")]
+    ReadError(#[from] bitbuffer::ReadError),
     /// The binary data contained an invalid magic number and is probably not a nav file
+    #[error("Invalid magic number ({0:#8X}), not a nav file or corrupted")]
     InvalidMagicNumber(u32),
     /// The version of the nav file is not supported by this parser
-    #[error(display = "The major version for this nav ({}), is not supported", _0)]
+    #[error("The major version for this nav ({0}), is not supported")]
     UnsupportedVersion(u32),
+    /// A ladder has an invalid direction
+    #[error("Invalid ladder direction ({0})")]
+    InvalidDirection(u32),
+    /// The size of the game specific area data isn't known for the game
+    #[error("The size of the area data for {0:?} is not known")]
+    UnknownAreaDataSize(NavGame),
 }
 
 /// Parse all navigation areas from a nav file
@@ -37,7 +42,78 @@ pub enum ParseError {
 pub fn read_areas(
     data: impl Into<BitReadStream<LittleEndian>>,
 ) -> Result<Vec<NavArea>, ParseError> {
-    let mut data = data.into();
+    read_mesh(data).map(|mesh| mesh.areas)
+}
+
+/// Parse the full navigation mesh, including the place table, from a nav file
+///
+/// The game specific data of the areas is read as stored by Team Fortress 2, use
+/// [`read_mesh_for_game`] for the nav files of other games.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::read_mesh;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let mesh = read_mesh(file)?;
+/// for place in &mesh.places {
+///     println!("{}: {}", place.id, place.name);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`read_mesh_for_game`]: ./fn.read_mesh_for_game.html
+pub fn read_mesh(data: impl Into<BitReadStream<LittleEndian>>) -> Result<NavMesh, ParseError> {
+    read_mesh_for_game(data, NavGame::TeamFortress2)
+}
+
+/// Parse the full navigation mesh from a nav file made for a specific game
+///
+/// The game determines how the game specific data stored with every area is read. Fails with
+/// [`ParseError::UnknownAreaDataSize`] for games where the size of the data isn't known.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{read_mesh_for_game, NavGame};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let mesh = read_mesh_for_game(file, NavGame::TeamFortress2)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`ParseError::UnknownAreaDataSize`]: ./enum.ParseError.html#variant.UnknownAreaDataSize
+pub fn read_mesh_for_game(
+    data: impl Into<BitReadStream<LittleEndian>>,
+    game: NavGame,
+) -> Result<NavMesh, ParseError> {
+    read_nav(data.into(), game, None)
+}
+
+/// Parse the navigation mesh and a hash of the binary record of every area
+pub(crate) fn read_mesh_with_hashes(
+    data: BitReadStream<LittleEndian>,
+    game: NavGame,
+) -> Result<(NavMesh, Vec<u64>), ParseError> {
+    let mut hashes = Vec::new();
+    let mesh = read_nav(data, game, Some(&mut hashes))?;
+    Ok((mesh, hashes))
+}
+
+fn read_nav(
+    mut data: BitReadStream<LittleEndian>,
+    game: NavGame,
+    mut record_hashes: Option<&mut Vec<u64>>,
+) -> Result<NavMesh, ParseError> {
+    let area_data_size = game
+        .area_data_size()
+        .ok_or(ParseError::UnknownAreaDataSize(game))?;
+
     let magic = data.read()?;
     if magic != 0xFEED_FACE {
         return Err(ParseError::InvalidMagicNumber(magic));
@@ -45,15 +121,15 @@ pub fn read_areas(
 
     let major_version: u32 = data.read()?;
 
-    if major_version < 6 || major_version > 16 {
+    if !(6..=16).contains(&major_version) {
         return Err(ParseError::UnsupportedVersion(major_version));
     }
 
-    let _minor_version: u32 = if major_version >= 10 { data.read()? } else { 0 };
+    let minor_version: u32 = if major_version >= 10 { data.read()? } else { 0 };
 
-    let _size: u32 = data.read()?;
+    let bsp_size: u32 = data.read()?;
 
-    let _is_analysed = if major_version >= 14 {
+    let is_analyzed = if major_version >= 14 {
         data.read_int::<u8>(8)? == 1
     } else {
         false
@@ -61,14 +137,16 @@ pub fn read_areas(
 
     let place_count: u16 = data.read()?;
 
-    // let places = Vec::with_capacity(place_count as usize);
-    for _id in 1..=place_count {
+    let mut places = Vec::with_capacity(place_count as usize);
+    for id in 1..=place_count {
         let name_length: u16 = data.read()?;
-        let _name = data.read_string(Some(name_length as usize))?;
-        // TODO
+        let name = data.read_string(Some(name_length as usize))?;
+        // names are stored null terminated
+        let name = name.trim_end_matches('\0').to_string();
+        places.push(NavPlace { id, name });
     }
 
-    let _has_unnamed_areas = if major_version >= 12 {
+    let has_unnamed_areas = if major_version >= 12 {
         data.read_int::<u8>(8)? == 1
     } else {
         false
@@ -79,6 +157,8 @@ pub fn read_areas(
     let mut areas = Vec::with_capacity(area_count as usize);
 
     for _ in 0..area_count {
+        let record_start = data.clone();
+
         let id = data.read()?;
 
         let flags = if major_version <= 8 {
@@ -112,10 +192,17 @@ pub fn read_areas(
 
         let place = data.read()?;
 
-        let ladder_connections = data.read()?;
+        let ladder_connections = if major_version >= 7 {
+            data.read()?
+        } else {
+            Default::default()
+        };
 
-        let earliest_occupy_first_team = data.read()?;
-        let earliest_occupy_second_team = data.read()?;
+        let (earliest_occupy_first_team, earliest_occupy_second_team) = if major_version >= 8 {
+            (data.read()?, data.read()?)
+        } else {
+            (0.0, 0.0)
+        };
 
         let light_intensity = if major_version >= 11 {
             data.read()?
@@ -123,16 +210,21 @@ pub fn read_areas(
             Default::default()
         };
 
-        let visible_areas = if major_version >= 16 {
+        let (visible_areas, inherit_visibility_from_area_id) = if major_version >= 16 {
             let visible_areas_count: u32 = data.read()?;
-            data.read_sized(visible_areas_count as usize)?
+            (data.read_sized(visible_areas_count as usize)?, data.read()?)
         } else {
-            Vec::new()
+            (Vec::new(), 0)
         };
 
-        let inherit_visibility_from_area_id = data.read()?;
+        let custom_data = data.read_bytes(area_data_size)?;
 
-        data.skip_bits(32)?;
+        if let Some(hashes) = record_hashes.as_mut() {
+            let record_length = (data.pos() - record_start.pos()) / 8;
+            hashes.push(record_hash(
+                &record_start.clone().read_bytes(record_length)?,
+            ));
+        }
 
         areas.push(NavArea {
             id,
@@ -154,12 +246,87 @@ pub fn read_areas(
             light_intensity,
             visible_areas,
             inherit_visibility_from_area_id,
+            custom_data,
         });
     }
 
-    debug_assert!(data.bits_left() <= 32);
+    // older tools don't always write the ladder count when there are no ladders
+    let ladder_count: u32 = if data.bits_left() >= 32 {
+        data.read()?
+    } else {
+        0
+    };
 
-    Ok(areas)
+    let mut ladders = Vec::with_capacity(ladder_count as usize);
+    for _ in 0..ladder_count {
+        ladders.push(read_ladder(&mut data, major_version)?);
+    }
+
+    let custom_data = data.read_bytes(data.bits_left() / 8)?;
+
+    Ok(NavMesh {
+        game,
+        major_version,
+        minor_version,
+        bsp_size,
+        is_analyzed,
+        places,
+        has_unnamed_areas,
+        areas,
+        ladders,
+        custom_data,
+    })
+}
+
+/// Hash the binary record of an area
+///
+/// The hashes are stored in tree caches, so this uses 64 bit FNV-1a which, unlike the hashers
+/// from the standard library, gives the same result across Rust versions and platforms.
+fn record_hash(record: &[u8]) -> u64 {
+    record.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn read_ladder(
+    data: &mut BitReadStream<LittleEndian>,
+    major_version: u32,
+) -> Result<NavLadder, ParseError> {
+    let id = data.read()?;
+    let width = data.read()?;
+    let top = data.read()?;
+    let bottom = data.read()?;
+    let length = data.read()?;
+
+    let direction: u32 = data.read()?;
+    let direction =
+        NavDirection::from_index(direction).ok_or(ParseError::InvalidDirection(direction))?;
+
+    let is_dangling = if major_version == 6 {
+        data.read_int::<u8>(8)? == 1
+    } else {
+        false
+    };
+
+    let mut read_area = || -> Result<Option<NavAreaId>, ParseError> {
+        let id: u32 = data.read()?;
+        Ok(if id == 0 { None } else { Some(id.into()) })
+    };
+
+    Ok(NavLadder {
+        id,
+        width,
+        top,
+        bottom,
+        length,
+        direction,
+        is_dangling,
+        top_forward_area: read_area()?,
+        top_left_area: read_area()?,
+        top_right_area: read_area()?,
+        top_behind_area: read_area()?,
+        bottom_area: read_area()?,
+    })
 }
 
 pub(crate) fn read_quads(
@@ -268,3 +435,10 @@ fn test_quads() {
     let quads = read_quads(data).unwrap();
     assert_eq!(1930, quads.len());
 }
+
+#[test]
+fn test_record_hash() {
+    assert_eq!(0xcbf2_9ce4_8422_2325, record_hash(b""));
+    assert_eq!(0xaf63_dc4c_8601_ec8c, record_hash(b"a"));
+    assert_eq!(0x8594_4171_f739_67e8, record_hash(b"foobar"));
+}
@@ -0,0 +1,493 @@
+use crate::navmesh::{Connections, NavAreaId, NavDirection, NavQuad, Vector3};
+use crate::{EditError, NavMesh, NavMeshBuilder};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+const PATCH_HEADER: &str = "navpatch 1";
+
+/// Errors that can occur when parsing or applying a patch
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum PatchError {
+    /// The text doesn't start with the patch header
+    #[error("Not a nav patch")]
+    InvalidHeader,
+    /// A line of the patch couldn't be parsed
+    #[error("Invalid patch instruction on line {0}")]
+    InvalidLine(usize),
+    /// The patch was made for a different nav file
+    #[error("The patch was made for a different nav file")]
+    BaseMismatch,
+    /// An instruction of the patch couldn't be applied
+    #[error("Failed to apply patch: {0}")]
+    Edit(#[from] EditError),
+}
+
+/// A single change made by a patch
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchOperation {
+    /// Remove an area and all references to it
+    RemoveArea(NavAreaId),
+    /// Add a new area without any connections
+    AddArea {
+        id: NavAreaId,
+        quad: NavQuad,
+        flags: u32,
+        place: Option<String>,
+    },
+    /// Change the corners of an area
+    SetQuad { id: NavAreaId, quad: NavQuad },
+    /// Change the attribute flags of an area
+    SetFlags { id: NavAreaId, flags: u32 },
+    /// Change the place of an area
+    SetPlace {
+        id: NavAreaId,
+        place: Option<String>,
+    },
+    /// Add a one way connection between two areas
+    Connect {
+        from: NavAreaId,
+        to: NavAreaId,
+        direction: NavDirection,
+    },
+    /// Remove a one way connection between two areas
+    Disconnect { from: NavAreaId, to: NavAreaId },
+}
+
+/// A set of changes to the areas of a nav file
+///
+/// Patches describe added and removed areas and changes to the geometry, flags, place and
+/// connections of areas. Other data, like hiding spots or visibility, is not included.
+///
+/// The text format of a patch is a header line followed by one instruction per line:
+///
+/// ```text
+/// navpatch 1
+/// base 5e7a3c14a2b1d6f0
+/// remove 12
+/// add 2001 0 0 0 50 50 0 0 0 0 Bridge
+/// quad 14 0 0 0 50 50 0 0 0
+/// flags 14 4
+/// place 14 Bridge
+/// connect 14 east 2001
+/// disconnect 14 15
+/// ```
+///
+/// Where `base` is the checksum of the nav file the patch applies to, areas are written as the
+/// `x y z` coordinates of the north west corner, the `x y z` coordinates of the south east corner
+/// and the height of the north east and south west corners. An empty place removes the place
+/// of an area.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{read_mesh, NavPatch};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let base = read_mesh(std::fs::read("path/to/navfile.nav")?)?;
+/// let fixed = read_mesh(std::fs::read("path/to/fixed.nav")?)?;
+///
+/// let patch = NavPatch::diff(&base, &fixed);
+/// std::fs::write("path/to/fix.navpatch", patch.to_string())?;
+///
+/// let patch: NavPatch = std::fs::read_to_string("path/to/fix.navpatch")?.parse()?;
+/// let patched = patch.apply(&base)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavPatch {
+    /// The checksum of the nav file the patch applies to
+    pub base: Option<u64>,
+    pub operations: Vec<PatchOperation>,
+}
+
+/// A FNV-1a hash of the binary nav file
+fn checksum(mesh: &NavMesh) -> u64 {
+    let data = mesh.to_bytes(mesh.major_version).unwrap_or_default();
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn place_name(mesh: &NavMesh, place: u16) -> Option<String> {
+    mesh.place(place).map(|place| place.name.clone())
+}
+
+impl NavPatch {
+    /// Create a patch with the changes between two versions of a nav file
+    pub fn diff(base: &NavMesh, target: &NavMesh) -> NavPatch {
+        let base_areas: HashMap<_, _> = base.areas.iter().map(|area| (area.id, area)).collect();
+        let target_ids: HashSet<_> = target.areas.iter().map(|area| area.id).collect();
+
+        let mut removes = Vec::new();
+        let mut adds = Vec::new();
+        let mut changes = Vec::new();
+        let mut disconnects = Vec::new();
+        let mut connects = Vec::new();
+
+        for area in &base.areas {
+            if !target_ids.contains(&area.id) {
+                removes.push(PatchOperation::RemoveArea(area.id));
+            }
+        }
+
+        for area in &target.areas {
+            let place = place_name(target, area.place);
+            let old = match base_areas.get(&area.id) {
+                Some(old) => *old,
+                None => {
+                    adds.push(PatchOperation::AddArea {
+                        id: area.id,
+                        quad: area.quad.clone(),
+                        flags: area.flags,
+                        place,
+                    });
+                    for direction in NavDirection::ALL.iter() {
+                        for to in &area.connections[*direction] {
+                            connects.push(PatchOperation::Connect {
+                                from: area.id,
+                                to: *to,
+                                direction: *direction,
+                            });
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            if old.quad != area.quad {
+                changes.push(PatchOperation::SetQuad {
+                    id: area.id,
+                    quad: area.quad.clone(),
+                });
+            }
+            if old.flags != area.flags {
+                changes.push(PatchOperation::SetFlags {
+                    id: area.id,
+                    flags: area.flags,
+                });
+            }
+            if place_name(base, old.place) != place {
+                changes.push(PatchOperation::SetPlace { id: area.id, place });
+            }
+
+            // connections that changed direction are removed and added again
+            let directions = |connections: &Connections, to: &NavAreaId| {
+                NavDirection::ALL
+                    .iter()
+                    .filter(|direction| connections[**direction].contains(to))
+                    .copied()
+                    .collect::<Vec<_>>()
+            };
+            let mut targets: Vec<NavAreaId> = old.connections.iter().copied().collect();
+            targets.extend(area.connections.iter().copied());
+            let mut seen = HashSet::new();
+            targets.retain(|to| seen.insert(*to));
+            for to in targets {
+                let (old_directions, new_directions) = (
+                    directions(&old.connections, &to),
+                    directions(&area.connections, &to),
+                );
+                if old_directions == new_directions {
+                    continue;
+                }
+                if !old_directions.is_empty() && target_ids.contains(&to) {
+                    disconnects.push(PatchOperation::Disconnect { from: area.id, to });
+                }
+                for direction in new_directions {
+                    connects.push(PatchOperation::Connect {
+                        from: area.id,
+                        to,
+                        direction,
+                    });
+                }
+            }
+        }
+
+        let mut operations = removes;
+        operations.extend(adds);
+        operations.extend(changes);
+        operations.extend(disconnects);
+        operations.extend(connects);
+
+        NavPatch {
+            base: Some(checksum(base)),
+            operations,
+        }
+    }
+
+    /// Apply the patch to a nav file
+    ///
+    /// Fails with [`PatchError::BaseMismatch`] if the patch was made for a different nav file
+    ///
+    /// [`PatchError::BaseMismatch`]: ./enum.PatchError.html#variant.BaseMismatch
+    pub fn apply(&self, base: &NavMesh) -> Result<NavMesh, PatchError> {
+        if let Some(expected) = self.base {
+            if checksum(base) != expected {
+                return Err(PatchError::BaseMismatch);
+            }
+        }
+
+        let mut builder = NavMeshBuilder::from_mesh(base.clone());
+        let removed: Vec<NavAreaId> = self
+            .operations
+            .iter()
+            .filter_map(|operation| match operation {
+                PatchOperation::RemoveArea(id) => Some(*id),
+                _ => None,
+            })
+            .collect();
+        builder.remove_areas(&removed)?;
+
+        for operation in &self.operations {
+            match operation {
+                PatchOperation::RemoveArea(_) => {}
+                PatchOperation::AddArea {
+                    id,
+                    quad,
+                    flags,
+                    place,
+                } => {
+                    builder.add_area_with_id(*id, quad.clone())?;
+                    builder.set_flags(*id, *flags)?;
+                    builder.set_place(*id, place.as_deref())?;
+                }
+                PatchOperation::SetQuad { id, quad } => builder.set_quad(*id, quad.clone())?,
+                PatchOperation::SetFlags { id, flags } => builder.set_flags(*id, *flags)?,
+                PatchOperation::SetPlace { id, place } => {
+                    builder.set_place(*id, place.as_deref())?
+                }
+                PatchOperation::Connect {
+                    from,
+                    to,
+                    direction,
+                } => builder.connect_one_way(*from, *to, *direction)?,
+                PatchOperation::Disconnect { from, to } => {
+                    builder.disconnect_one_way(*from, *to)?
+                }
+            }
+        }
+
+        Ok(builder.build())
+    }
+}
+
+fn write_quad(f: &mut fmt::Formatter<'_>, quad: &NavQuad) -> fmt::Result {
+    let (nw, se) = (quad.north_west, quad.south_east);
+    write!(
+        f,
+        "{} {} {} {} {} {} {} {}",
+        nw.0, nw.1, nw.2, se.0, se.1, se.2, quad.north_east_z, quad.south_west_z
+    )
+}
+
+impl fmt::Display for NavPatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", PATCH_HEADER)?;
+        if let Some(base) = self.base {
+            writeln!(f, "base {:016x}", base)?;
+        }
+        for operation in &self.operations {
+            match operation {
+                PatchOperation::RemoveArea(id) => write!(f, "remove {}", id)?,
+                PatchOperation::AddArea {
+                    id,
+                    quad,
+                    flags,
+                    place,
+                } => {
+                    write!(f, "add {} ", id)?;
+                    write_quad(f, quad)?;
+                    write!(f, " {} {}", flags, place.as_deref().unwrap_or(""))?;
+                }
+                PatchOperation::SetQuad { id, quad } => {
+                    write!(f, "quad {} ", id)?;
+                    write_quad(f, quad)?;
+                }
+                PatchOperation::SetFlags { id, flags } => write!(f, "flags {} {}", id, flags)?,
+                PatchOperation::SetPlace { id, place } => {
+                    write!(f, "place {} {}", id, place.as_deref().unwrap_or(""))?
+                }
+                PatchOperation::Connect {
+                    from,
+                    to,
+                    direction,
+                } => write!(f, "connect {} {} {}", from, direction.name(), to)?,
+                PatchOperation::Disconnect { from, to } => write!(f, "disconnect {} {}", from, to)?,
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_operation(line: &str) -> Option<PatchOperation> {
+    let mut parts = line.splitn(2, ' ');
+    let instruction = parts.next()?;
+    let arguments = parts.next().unwrap_or("");
+
+    // the place name is the remainder of the line and can contain spaces
+    let split = |count: usize| -> Option<(Vec<&str>, Option<String>)> {
+        let mut parts = arguments.splitn(count + 1, ' ');
+        let values = (&mut parts).take(count).collect::<Vec<_>>();
+        if values.len() != count {
+            return None;
+        }
+        let place = parts
+            .next()
+            .map(str::trim)
+            .filter(|place| !place.is_empty());
+        Some((values, place.map(String::from)))
+    };
+    let id = |value: &str| value.parse::<u32>().ok().map(NavAreaId::from);
+    let quad = |values: &[&str]| -> Option<NavQuad> {
+        let values = values
+            .iter()
+            .map(|value| value.parse::<f32>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        Some(NavQuad {
+            north_west: Vector3(values[0], values[1], values[2]),
+            south_east: Vector3(values[3], values[4], values[5]),
+            north_east_z: values[6],
+            south_west_z: values[7],
+        })
+    };
+
+    Some(match instruction {
+        "remove" => PatchOperation::RemoveArea(id(arguments)?),
+        "add" => {
+            let (values, place) = split(10)?;
+            PatchOperation::AddArea {
+                id: id(values[0])?,
+                quad: quad(&values[1..9])?,
+                flags: values[9].parse().ok()?,
+                place,
+            }
+        }
+        "quad" => {
+            let (values, _) = split(9)?;
+            PatchOperation::SetQuad {
+                id: id(values[0])?,
+                quad: quad(&values[1..9])?,
+            }
+        }
+        "flags" => {
+            let (values, _) = split(2)?;
+            PatchOperation::SetFlags {
+                id: id(values[0])?,
+                flags: values[1].parse().ok()?,
+            }
+        }
+        "place" => {
+            let (values, place) = split(1)?;
+            PatchOperation::SetPlace {
+                id: id(values[0])?,
+                place,
+            }
+        }
+        "connect" => {
+            let (values, _) = split(3)?;
+            let direction = NavDirection::ALL
+                .iter()
+                .find(|direction| direction.name() == values[1])?;
+            PatchOperation::Connect {
+                from: id(values[0])?,
+                to: id(values[2])?,
+                direction: *direction,
+            }
+        }
+        "disconnect" => {
+            let (values, _) = split(2)?;
+            PatchOperation::Disconnect {
+                from: id(values[0])?,
+                to: id(values[1])?,
+            }
+        }
+        _ => return None,
+    })
+}
+
+impl FromStr for NavPatch {
+    type Err = PatchError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header.trim_end() == PATCH_HEADER => {}
+            _ => return Err(PatchError::InvalidHeader),
+        }
+
+        let mut patch = NavPatch {
+            base: None,
+            operations: Vec::new(),
+        };
+        for (index, line) in lines {
+            let line = line.trim_end();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(base) = line.strip_prefix("base ") {
+                let base = u64::from_str_radix(base, 16)
+                    .map_err(|_| PatchError::InvalidLine(index + 1))?;
+                patch.base = Some(base);
+                continue;
+            }
+            let operation = parse_operation(line).ok_or(PatchError::InvalidLine(index + 1))?;
+            patch.operations.push(operation);
+        }
+        Ok(patch)
+    }
+}
+
+#[test]
+fn test_patch() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let base = crate::read_mesh(file).unwrap();
+
+    let mut builder = NavMeshBuilder::from_mesh(base.clone());
+    let first = base.areas[0].id;
+    let second = base.areas[1].id;
+    let removed = base.areas[2].id;
+    builder.set_flags(first, 0x4).unwrap();
+    builder.set_place(first, Some("Upper Bridge")).unwrap();
+    builder.remove_area(removed).unwrap();
+    let added = builder
+        .add_area(NavQuad {
+            north_west: Vector3(0.0, 0.0, 0.5),
+            south_east: Vector3(25.0, 25.0, 0.5),
+            north_east_z: 0.5,
+            south_west_z: 0.5,
+        })
+        .unwrap();
+    builder.connect(added, first, NavDirection::West).unwrap();
+    builder.disconnect(first, second).unwrap();
+    let target = builder.build();
+
+    let patch = NavPatch::diff(&base, &target);
+    let text = patch.to_string();
+    let parsed: NavPatch = text.parse().unwrap();
+    assert_eq!(patch, parsed);
+
+    let patched = parsed.apply(&base).unwrap();
+    assert_eq!(target.areas.len(), patched.areas.len());
+    let view = patched.view();
+    for area in &target.areas {
+        let patched_area = view.area(area.id).unwrap();
+        assert_eq!(area.quad, patched_area.quad);
+        assert_eq!(area.flags, patched_area.flags);
+        assert_eq!(area.connections, patched_area.connections);
+        assert_eq!(
+            place_name(&target, area.place),
+            place_name(&patched, patched_area.place)
+        );
+    }
+
+    // the patch only applies to the original nav
+    assert_eq!(Err(PatchError::BaseMismatch), patch.apply(&target));
+    assert_eq!(
+        Err(PatchError::InvalidLine(2)),
+        "navpatch 1\nfoo 1".parse::<NavPatch>()
+    );
+}
@@ -0,0 +1,990 @@
+use crate::navmesh::{NavArea, NavAreaId, Vector3};
+use crate::{DistanceCost, EdgeKind, NavCost, NavTree};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// A path between two points on the mesh, see [`NavTree::find_path_points`]
+///
+/// [`NavTree::find_path_points`]: ./struct.NavTree.html#method.find_path_points
+#[derive(Debug, Clone, PartialEq)]
+pub struct NavPath {
+    /// The areas the path goes through, from the area of the start point to the area of the end
+    /// point
+    pub areas: Vec<NavAreaId>,
+    /// The points of the path, starting at the start point and ending at the end point
+    pub points: Vec<Vector3>,
+}
+
+impl NavPath {
+    /// The total length of the path
+    pub fn length(&self) -> f32 {
+        self.points
+            .windows(2)
+            .map(|pair| pair[0].distance(pair[1]))
+            .sum()
+    }
+}
+
+/// An area in the open set of a search, the candidate with the lowest estimate is popped first
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Candidate<T = NavAreaId> {
+    pub(crate) estimate: f32,
+    pub(crate) cost: f32,
+    pub(crate) id: T,
+}
+
+impl<T: Ord> PartialEq for Candidate<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T: Ord> Eq for Candidate<T> {}
+
+impl<T: Ord> PartialOrd for Candidate<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T: Ord> Ord for Candidate<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .estimate
+            .partial_cmp(&self.estimate)
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| other.id.cmp(&self.id))
+    }
+}
+
+/// Follow the recorded predecessors back from the goal to the start
+pub(crate) fn reconstruct<T: Copy + Eq + std::hash::Hash>(
+    came_from: &HashMap<T, T>,
+    goal: T,
+) -> Vec<T> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(previous) = came_from.get(&current) {
+        path.push(*previous);
+        current = *previous;
+    }
+    path.reverse();
+    path
+}
+
+/// Run an A* search from the start to the goal area
+///
+/// `edges` gives the areas reachable from an area with the cost of moving there, the heuristic
+/// must never overestimate the remaining cost to the goal for the path to be the cheapest.
+pub(crate) fn astar<'a, E, I>(
+    tree: &'a NavTree,
+    start: NavAreaId,
+    goal: NavAreaId,
+    edges: E,
+    heuristic: impl Fn(&NavArea) -> f32,
+) -> Option<(Vec<NavAreaId>, f32)>
+where
+    E: Fn(&'a NavArea) -> I,
+    I: Iterator<Item = (&'a NavArea, f32)>,
+{
+    tree.area(start)?;
+    tree.area(goal)?;
+
+    astar_by(
+        start,
+        goal,
+        |id| {
+            tree.area(id)
+                .into_iter()
+                .flat_map(&edges)
+                .map(|(next, cost)| (next.id, cost))
+        },
+        |id| tree.area(id).map_or(0.0, &heuristic),
+    )
+}
+
+/// Run an A* search over any graph, from the start to the goal node
+///
+/// `edges` gives the nodes reachable from a node with the cost of moving there, see [`astar`]
+pub(crate) fn astar_by<T, I>(
+    start: T,
+    goal: T,
+    edges: impl Fn(T) -> I,
+    heuristic: impl Fn(T) -> f32,
+) -> Option<(Vec<T>, f32)>
+where
+    T: Copy + Ord + std::hash::Hash,
+    I: Iterator<Item = (T, f32)>,
+{
+    let mut open = BinaryHeap::new();
+    let mut costs: HashMap<T, f32> = HashMap::new();
+    let mut came_from = HashMap::new();
+    costs.insert(start, 0.0);
+    open.push(Candidate {
+        estimate: heuristic(start),
+        cost: 0.0,
+        id: start,
+    });
+
+    while let Some(Candidate { cost, id, .. }) = open.pop() {
+        if id == goal {
+            return Some((reconstruct(&came_from, goal), cost));
+        }
+        // skip outdated entries for nodes that have since been reached more cheaply
+        if costs.get(&id).is_some_and(|best| cost > *best) {
+            continue;
+        }
+        for (next, edge_cost) in edges(id) {
+            let next_cost = cost + edge_cost;
+            if !costs.get(&next).is_some_and(|best| next_cost >= *best) {
+                costs.insert(next, next_cost);
+                came_from.insert(next, id);
+                open.push(Candidate {
+                    estimate: next_cost + heuristic(next),
+                    cost: next_cost,
+                    id: next,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+/// Options for path searches, see [`NavTree::find_path_with_options`]
+///
+/// [`NavTree::find_path_with_options`]: ./struct.NavTree.html#method.find_path_with_options
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PathOptions {
+    /// Search from the start and the goal at the same time until both searches meet
+    ///
+    /// This explores far fewer areas for long paths on large meshes, but needs to index the
+    /// connections of all areas in reverse before searching, making it slower for short paths.
+    pub bidirectional: bool,
+    /// The highest step up between neighboring areas that can be walked up
+    pub max_step_up: Option<f32>,
+    /// The highest drop down between neighboring areas that can be walked down
+    pub max_drop_down: Option<f32>,
+}
+
+impl PathOptions {
+    /// Whether the options allow a move between two neighboring areas
+    ///
+    /// The height difference of walking between the areas is the difference between the heights
+    /// of the areas at the points closest to the middle between the two areas. Climbing a ladder
+    /// or using an off-mesh link is always allowed.
+    fn allows(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> bool {
+        if kind != EdgeKind::Walk || (self.max_step_up.is_none() && self.max_drop_down.is_none()) {
+            return true;
+        }
+
+        let (a, b) = (from.center(), to.center());
+        let middle = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+        let height = to.quad.closest_point(middle.0, middle.1).2
+            - from.quad.closest_point(middle.0, middle.1).2;
+        !self.max_step_up.is_some_and(|max| height > max)
+            && !self.max_drop_down.is_some_and(|max| -height > max)
+    }
+}
+
+/// One direction of a bidirectional search
+struct Search {
+    open: BinaryHeap<Candidate>,
+    costs: HashMap<NavAreaId, f32>,
+    came_from: HashMap<NavAreaId, NavAreaId>,
+}
+
+impl Search {
+    fn new(start: NavAreaId, estimate: f32) -> Self {
+        let mut open = BinaryHeap::new();
+        open.push(Candidate {
+            estimate,
+            cost: 0.0,
+            id: start,
+        });
+        let mut costs = HashMap::new();
+        costs.insert(start, 0.0);
+        Search {
+            open,
+            costs,
+            came_from: HashMap::new(),
+        }
+    }
+
+    /// Pop the next area to expand, skipping outdated entries
+    fn pop(&mut self) -> Option<(NavAreaId, f32)> {
+        while let Some(Candidate { cost, id, .. }) = self.open.pop() {
+            if !self.costs.get(&id).is_some_and(|best| cost > *best) {
+                return Some((id, cost));
+            }
+        }
+        None
+    }
+
+    /// Record a cheaper way to reach an area, returns false if the area was already reached more
+    /// cheaply
+    fn relax(&mut self, id: NavAreaId, from: NavAreaId, cost: f32, heuristic: f32) -> bool {
+        if self.costs.get(&id).is_some_and(|best| cost >= *best) {
+            return false;
+        }
+        self.costs.insert(id, cost);
+        self.came_from.insert(id, from);
+        self.open.push(Candidate {
+            estimate: cost + heuristic,
+            cost,
+            id,
+        });
+        true
+    }
+}
+
+impl NavTree {
+    /// Find the shortest path between two areas over the connections between areas
+    ///
+    /// The path is returned as the ordered list of areas from the start to the goal area, both
+    /// included. The length of a path is the sum of the distances between the centers of
+    /// consecutive areas. Returns `None` if either area doesn't exist or the goal can't be
+    /// reached from the start.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// if let Some(path) = tree.find_path(1.into(), 250.into()) {
+    ///     for id in path {
+    ///         println!("walk through area {}", id);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_path(&self, start: NavAreaId, goal: NavAreaId) -> Option<Vec<NavAreaId>> {
+        self.find_path_with_cost(start, goal, &DistanceCost)
+    }
+
+    /// Find the cheapest path between two areas using a custom cost
+    ///
+    /// Areas are connected by their connections and by ladders, the cost decides which moves
+    /// are allowed and how expensive they are. Use [`AttributeCost`] for paths that follow the
+    /// attributes of the areas the way the bots of the game do.
+    ///
+    /// [`AttributeCost`]: ./struct.AttributeCost.html
+    pub fn find_path_with_cost(
+        &self,
+        start: NavAreaId,
+        goal: NavAreaId,
+        cost: &impl NavCost,
+    ) -> Option<Vec<NavAreaId>> {
+        self.find_path_with_options(start, goal, cost, PathOptions::default())
+    }
+
+    /// Find the cheapest path between two areas using a custom cost and search options
+    ///
+    /// Moves between areas that step up or drop down further than allowed by the options are
+    /// skipped, next to the moves not allowed by the cost.
+    ///
+    /// The bidirectional search relies on the estimates of the cost never decreasing by more
+    /// than the cost of a move when moving to a neighboring area, which holds for the default
+    /// estimate as long as moves don't cost less than the distance between the areas.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, DistanceCost, PathOptions};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let options = PathOptions {
+    ///     bidirectional: true,
+    ///     max_step_up: Some(18.0),
+    ///     max_drop_down: Some(250.0),
+    /// };
+    /// let path = tree.find_path_with_options(1.into(), 250.into(), &DistanceCost, options);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_path_with_options(
+        &self,
+        start: NavAreaId,
+        goal: NavAreaId,
+        cost: &impl NavCost,
+        options: PathOptions,
+    ) -> Option<Vec<NavAreaId>> {
+        if self.is_blocked(start) || self.is_blocked(goal) {
+            return None;
+        }
+        if options.bidirectional {
+            return self.bidirectional_search(start, goal, cost, options);
+        }
+
+        let goal_area = self.area(goal)?;
+        astar(
+            self,
+            start,
+            goal,
+            |area| {
+                self.traversable_neighbors(area)
+                    .filter(move |(next, kind)| options.allows(area, next, *kind))
+                    .filter_map(move |(next, kind)| {
+                        Some((next, self.move_cost(cost, area, next, kind)?))
+                    })
+            },
+            |area| cost.estimate(area, goal_area),
+        )
+        .map(|(path, _)| path)
+    }
+
+    /// Search from both the start and the goal, expanding the smaller of the two searches
+    fn bidirectional_search(
+        &self,
+        start: NavAreaId,
+        goal: NavAreaId,
+        cost: &impl NavCost,
+        options: PathOptions,
+    ) -> Option<Vec<NavAreaId>> {
+        let start_area = self.area(start)?;
+        let goal_area = self.area(goal)?;
+
+        // the backward search follows the moves between areas in reverse
+        let mut incoming: HashMap<NavAreaId, Vec<(&NavArea, EdgeKind)>> = HashMap::new();
+        for area in self.areas() {
+            for (next, kind) in self.traversable_neighbors(area) {
+                if options.allows(area, next, kind) {
+                    incoming.entry(next.id).or_default().push((area, kind));
+                }
+            }
+        }
+
+        let estimate = cost.estimate(start_area, goal_area);
+        let mut forward = Search::new(start, estimate);
+        let mut backward = Search::new(goal, estimate);
+        // the length of the cheapest path found so far and the area where the searches met
+        let mut best: Option<(f32, NavAreaId)> = if start == goal {
+            Some((0.0, start))
+        } else {
+            None
+        };
+
+        while let (Some(forward_min), Some(backward_min)) =
+            (forward.open.peek(), backward.open.peek())
+        {
+            let bound = forward_min.estimate.max(backward_min.estimate);
+            if best.is_some_and(|(length, _)| bound >= length) {
+                break;
+            }
+
+            if forward.open.len() <= backward.open.len() {
+                let (id, current) = match forward.pop() {
+                    Some(next) => next,
+                    None => break,
+                };
+                let area = match self.area(id) {
+                    Some(area) => area,
+                    None => continue,
+                };
+                let moves = self
+                    .traversable_neighbors(area)
+                    .filter(|(next, kind)| options.allows(area, next, *kind));
+                for (next, kind) in moves {
+                    let next_cost = match self.move_cost(cost, area, next, kind) {
+                        Some(edge_cost) => current + edge_cost,
+                        None => continue,
+                    };
+                    let heuristic = cost.estimate(next, goal_area);
+                    if forward.relax(next.id, id, next_cost, heuristic) {
+                        if let Some(rest) = backward.costs.get(&next.id) {
+                            let length = next_cost + rest;
+                            if !best.is_some_and(|(best, _)| length >= best) {
+                                best = Some((length, next.id));
+                            }
+                        }
+                    }
+                }
+            } else {
+                let (id, current) = match backward.pop() {
+                    Some(next) => next,
+                    None => break,
+                };
+                let area = match self.area(id) {
+                    Some(area) => area,
+                    None => continue,
+                };
+                for (previous, kind) in incoming.get(&id).into_iter().flatten() {
+                    let previous_cost = match self.move_cost(cost, previous, area, *kind) {
+                        Some(edge_cost) => current + edge_cost,
+                        None => continue,
+                    };
+                    let heuristic = cost.estimate(start_area, previous);
+                    if backward.relax(previous.id, id, previous_cost, heuristic) {
+                        if let Some(rest) = forward.costs.get(&previous.id) {
+                            let length = previous_cost + rest;
+                            if !best.is_some_and(|(best, _)| length >= best) {
+                                best = Some((length, previous.id));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let (_, meeting) = best?;
+        let mut path = reconstruct(&forward.came_from, meeting);
+        let mut current = meeting;
+        while let Some(next) = backward.came_from.get(&current) {
+            path.push(*next);
+            current = *next;
+        }
+        Some(path)
+    }
+
+    /// Get the travel cost from the nearest seed area to every area that can be reached from
+    /// the seeds
+    ///
+    /// The cost of an area is the cost of the cheapest path from any of the seed areas to the
+    /// area, seed areas themselves have a cost of `0`. Areas that can't be reached from any seed
+    /// are not included.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, DistanceCost};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let field = tree.distance_field(&[1.into(), 2.into()], &DistanceCost);
+    /// for (id, distance) in &field {
+    ///     println!("area {} is {} units from the objective", id, distance);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn distance_field(
+        &self,
+        seeds: &[NavAreaId],
+        cost: &impl NavCost,
+    ) -> HashMap<NavAreaId, f32> {
+        let mut open = BinaryHeap::new();
+        let mut costs: HashMap<NavAreaId, f32> = HashMap::new();
+        for seed in seeds {
+            if self.area(*seed).is_some() {
+                costs.insert(*seed, 0.0);
+                open.push(Candidate {
+                    estimate: 0.0,
+                    cost: 0.0,
+                    id: *seed,
+                });
+            }
+        }
+
+        while let Some(Candidate {
+            cost: current, id, ..
+        }) = open.pop()
+        {
+            if costs.get(&id).is_some_and(|best| current > *best) {
+                continue;
+            }
+            let area = match self.area(id) {
+                Some(area) => area,
+                None => continue,
+            };
+            for (next, kind) in self.traversable_neighbors(area) {
+                let next_cost = match self.move_cost(cost, area, next, kind) {
+                    Some(edge_cost) => current + edge_cost,
+                    None => continue,
+                };
+                if !costs.get(&next.id).is_some_and(|best| next_cost >= *best) {
+                    costs.insert(next.id, next_cost);
+                    open.push(Candidate {
+                        estimate: next_cost,
+                        cost: next_cost,
+                        id: next.id,
+                    });
+                }
+            }
+        }
+
+        costs
+    }
+
+    /// Find the cheapest area to reach from a start area that matches a predicate
+    ///
+    /// Areas are searched in order of the cost of reaching them, the first area that matches
+    /// the predicate is returned with the cheapest path from the start to it. The start area is
+    /// checked first, returns `None` if no area that can be reached from the start matches.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, DistanceCost};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let hiding_spot = tree.find_nearest_where(
+    ///     1.into(),
+    ///     |area| !area.hiding_spots.is_empty(),
+    ///     &DistanceCost,
+    /// );
+    /// if let Some((area, path)) = hiding_spot {
+    ///     println!("area {} has a hiding spot {} areas away", area.id, path.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn find_nearest_where(
+        &self,
+        start: NavAreaId,
+        mut predicate: impl FnMut(&NavArea) -> bool,
+        cost: &impl NavCost,
+    ) -> Option<(&NavArea, Vec<NavAreaId>)> {
+        self.area(start)?;
+        let mut open = BinaryHeap::new();
+        let mut costs: HashMap<NavAreaId, f32> = HashMap::new();
+        let mut came_from: HashMap<NavAreaId, NavAreaId> = HashMap::new();
+        costs.insert(start, 0.0);
+        open.push(Candidate {
+            estimate: 0.0,
+            cost: 0.0,
+            id: start,
+        });
+
+        while let Some(Candidate {
+            cost: current, id, ..
+        }) = open.pop()
+        {
+            if costs.get(&id).is_some_and(|best| current > *best) {
+                continue;
+            }
+            let area = match self.area(id) {
+                Some(area) => area,
+                None => continue,
+            };
+            if predicate(area) {
+                return Some((area, reconstruct(&came_from, id)));
+            }
+            for (next, kind) in self.traversable_neighbors(area) {
+                let next_cost = match self.move_cost(cost, area, next, kind) {
+                    Some(edge_cost) => current + edge_cost,
+                    None => continue,
+                };
+                if !costs.get(&next.id).is_some_and(|best| next_cost >= *best) {
+                    costs.insert(next.id, next_cost);
+                    came_from.insert(next.id, id);
+                    open.push(Candidate {
+                        estimate: next_cost,
+                        cost: next_cost,
+                        id: next.id,
+                    });
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Find all areas that can be reached from an area under a cost
+    ///
+    /// Areas are reached through their connections and ladders, only following the moves the
+    /// cost allows. Since connections only go one way, areas that can only be reached by dropping
+    /// down are included while the areas above them might not be. The start area is always
+    /// included, the set is empty if the start area doesn't exist.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, AttributeCost};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let reachable = tree.reachable_from(1.into(), &AttributeCost::default());
+    /// println!("{} of {} areas can be reached", reachable.len(), tree.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn reachable_from(&self, start: NavAreaId, cost: &impl NavCost) -> HashSet<NavAreaId> {
+        let mut reachable = HashSet::new();
+        let mut queue: Vec<&NavArea> = self.area(start).into_iter().collect();
+        reachable.extend(queue.iter().map(|area| area.id));
+
+        while let Some(area) = queue.pop() {
+            for (next, kind) in self.traversable_neighbors(area) {
+                if self.move_cost(cost, area, next, kind).is_some() && reachable.insert(next.id) {
+                    queue.push(next);
+                }
+            }
+        }
+
+        reachable
+    }
+}
+
+impl NavTree {
+    /// Find a path between two points on the mesh
+    ///
+    /// The areas of the points are the areas at the x/y coordinates with the height closest to
+    /// the z coordinates of the points. The points of the path are the shortest line from the start
+    /// to the end point through the areas of the path, see [`smooth_path`]. Returns `None` if
+    /// either point isn't above an area or the end can't be reached from the start.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let from = Vector3(150.0, -312.0, 64.0);
+    /// let to = Vector3(1200.0, 200.0, 128.0);
+    /// if let Some(path) = tree.find_path_points(from, to) {
+    ///     println!("the path is {} units long", path.length());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`smooth_path`]: #method.smooth_path
+    pub fn find_path_points(&self, from: Vector3, to: Vector3) -> Option<NavPath> {
+        self.find_path_points_with_cost(from, to, &DistanceCost)
+    }
+
+    /// Find the cheapest path between two points on the mesh using a custom cost
+    pub fn find_path_points_with_cost(
+        &self,
+        from: Vector3,
+        to: Vector3,
+        cost: &impl NavCost,
+    ) -> Option<NavPath> {
+        let start = self.open_area_under(from.0, from.1, from.2)?;
+        let goal = self.open_area_under(to.0, to.1, to.2)?;
+        let areas = self.find_path_with_cost(start.id, goal.id, cost)?;
+
+        let from = Vector3(from.0, from.1, start.quad.interpolated_z(from.0, from.1));
+        let to = Vector3(to.0, to.1, goal.quad.interpolated_z(to.0, to.1));
+        let points = self.smooth_path(from, to, &areas);
+
+        Some(NavPath { areas, points })
+    }
+}
+
+#[test]
+fn test_find_path() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let path = tree.find_path(start, goal).unwrap();
+    assert_eq!(Some(&start), path.first());
+    assert_eq!(Some(&goal), path.last());
+    for pair in path.windows(2) {
+        assert!(tree[pair[0]].connections.iter().any(|id| *id == pair[1]));
+    }
+
+    // the path is as short as the one found without a heuristic
+    let length = |path: &[NavAreaId]| -> f32 {
+        path.windows(2)
+            .map(|pair| tree[pair[0]].center().distance(tree[pair[1]].center()))
+            .sum()
+    };
+    let (dijkstra, cost) = astar(
+        &tree,
+        start,
+        goal,
+        |area| {
+            let center = area.center();
+            tree.neighbors(area)
+                .map(move |(next, _)| (next, center.distance(next.center())))
+        },
+        |_| 0.0,
+    )
+    .unwrap();
+    assert!((length(&dijkstra) - cost).abs() < 0.1);
+    assert!((length(&path) - cost).abs() < 0.1);
+
+    assert_eq!(Some(vec![start]), tree.find_path(start, start));
+    assert_eq!(None, tree.find_path(start, NavAreaId::from(u32::MAX)));
+}
+
+#[test]
+fn test_find_path_bidirectional() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+    let options = PathOptions {
+        bidirectional: true,
+        ..PathOptions::default()
+    };
+    let cost = crate::AttributeCost::default();
+
+    let length = |path: &[NavAreaId]| -> f32 {
+        path.windows(2)
+            .map(|pair| {
+                let next = &tree[pair[1]];
+                tree.traversable_neighbors(&tree[pair[0]])
+                    .filter(|(area, _)| area.id == next.id)
+                    .filter_map(|(area, kind)| cost.edge_cost(&tree[pair[0]], area, kind))
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .sum()
+    };
+
+    let points = [
+        (-1000.0, -1030.0),
+        (1500.0, 200.0),
+        (0.0, 0.0),
+        (-2000.0, 1500.0),
+        (2500.0, -500.0),
+    ];
+    for from in &points {
+        for to in &points {
+            let start = tree.nearest_area(from.0, from.1).unwrap().0.id;
+            let goal = tree.nearest_area(to.0, to.1).unwrap().0.id;
+            let expected = tree.find_path_with_cost(start, goal, &cost);
+            let path = tree.find_path_with_options(start, goal, &cost, options);
+            assert_eq!(expected.is_some(), path.is_some());
+            if let (Some(expected), Some(path)) = (expected, path) {
+                assert_eq!(Some(&start), path.first());
+                assert_eq!(Some(&goal), path.last());
+                assert!((length(&expected) - length(&path)).abs() < 0.1);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_path_step_height() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let step = |path: &[NavAreaId]| {
+        path.windows(2)
+            .map(|pair| {
+                let (from, to) = (&tree[pair[0]], &tree[pair[1]]);
+                let (a, b) = (from.center(), to.center());
+                let middle = ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0);
+                to.quad.closest_point(middle.0, middle.1).2
+                    - from.quad.closest_point(middle.0, middle.1).2
+            })
+            .collect::<Vec<f32>>()
+    };
+
+    for bidirectional in [false, true].iter().copied() {
+        let options = PathOptions {
+            bidirectional,
+            max_step_up: Some(18.0),
+            max_drop_down: Some(250.0),
+        };
+        if let Some(path) = tree.find_path_with_options(start, goal, &DistanceCost, options) {
+            assert_eq!(Some(&start), path.first());
+            assert_eq!(Some(&goal), path.last());
+            for height in step(&path) {
+                assert!((-250.0..=18.0).contains(&height));
+            }
+        }
+
+        // negative limits forbid walking between any areas
+        let flat = PathOptions {
+            bidirectional,
+            max_step_up: Some(-1.0),
+            max_drop_down: Some(-1.0),
+        };
+        assert_eq!(
+            None,
+            tree.find_path_with_options(start, goal, &DistanceCost, flat)
+        );
+    }
+}
+
+#[test]
+fn test_distance_field() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let first = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let second = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let field = tree.distance_field(&[first, second], &DistanceCost);
+    assert_eq!(Some(&0.0), field.get(&first));
+    assert_eq!(Some(&0.0), field.get(&second));
+
+    // the distance to an area is the length of the shortest path from the nearest seed
+    let target = tree.nearest_area(0.0, 0.0).unwrap().0.id;
+    let path_length = |start: NavAreaId| {
+        tree.find_path(start, target).map(|path| {
+            path.windows(2)
+                .map(|pair| tree[pair[0]].center().distance(tree[pair[1]].center()))
+                .sum::<f32>()
+        })
+    };
+    let expected = match (path_length(first), path_length(second)) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    };
+    match (expected, field.get(&target)) {
+        (Some(expected), Some(distance)) => assert!((expected - distance).abs() < 0.1),
+        (expected, distance) => assert_eq!(expected.is_some(), distance.is_some()),
+    }
+
+    for (id, distance) in &field {
+        assert!(*distance >= 0.0);
+        let area = &tree[*id];
+        for (next, kind) in tree.traversable_neighbors(area) {
+            let edge = DistanceCost.edge_cost(area, next, kind).unwrap();
+            assert!(field[&next.id] <= distance + edge + 0.01);
+        }
+    }
+}
+
+#[test]
+fn test_find_nearest_where() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+
+    let (area, path) = tree
+        .find_nearest_where(start, |area| area.id == start, &DistanceCost)
+        .unwrap();
+    assert_eq!(start, area.id);
+    assert_eq!(vec![start], path);
+
+    let (area, path) = tree
+        .find_nearest_where(start, |area| area.id == goal, &DistanceCost)
+        .unwrap();
+    assert_eq!(goal, area.id);
+    assert_eq!(tree.find_path(start, goal), Some(path));
+
+    // the nearest matching area is the matching area with the lowest distance
+    let field = tree.distance_field(&[start], &DistanceCost);
+    let matches = |area: &NavArea| !area.hiding_spots.is_empty() && area.id != start;
+    let nearest = tree.find_nearest_where(start, matches, &DistanceCost);
+    let expected = field
+        .iter()
+        .filter(|(id, _)| matches(&tree[**id]))
+        .map(|(_, distance)| *distance)
+        .fold(None, |min: Option<f32>, distance| {
+            Some(min.map_or(distance, |min| min.min(distance)))
+        });
+    assert_eq!(expected.is_some(), nearest.is_some());
+    if let (Some(expected), Some((area, path))) = (expected, nearest) {
+        assert!(matches(area));
+        assert!((field[&area.id] - expected).abs() < 0.01);
+        assert_eq!(Some(&start), path.first());
+        assert_eq!(Some(&area.id), path.last());
+    }
+
+    assert!(tree
+        .find_nearest_where(start, |_| false, &DistanceCost)
+        .is_none());
+    assert!(tree
+        .find_nearest_where(NavAreaId::from(u32::MAX), |_| true, &DistanceCost)
+        .is_none());
+}
+
+#[test]
+fn test_reachable_from() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0;
+    let (x, y, z) = (start.center().0, start.center().1, start.center().2);
+    let start = start.id;
+    let reachable = tree.reachable_from(start, &DistanceCost);
+    assert_eq!(tree.region_at(x, y, z, true), reachable);
+    assert!(tree.reachable_from(0.into(), &DistanceCost).is_empty());
+
+    // blocking every neighbor of the start area leaves only the start area
+    let neighbors: Vec<NavAreaId> = tree
+        .traversable_neighbors(&tree[start])
+        .map(|(next, _)| next.id)
+        .filter(|id| *id != start)
+        .collect();
+    for id in neighbors {
+        let mut area = tree[id].clone();
+        area.flags |= crate::NavAttributeFlags::NAV_BLOCKER.0;
+        let handle = tree.handle(id).unwrap();
+        tree.replace(handle, area).unwrap();
+    }
+    let blocked = tree.reachable_from(start, &crate::AttributeCost::default());
+    assert_eq!(1, blocked.len());
+    assert!(blocked.contains(&start));
+}
+
+#[test]
+fn test_find_path_points() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let from = tree.nearest_area(-1000.0, -1030.0).unwrap().0.center();
+    let to = tree.nearest_area(1500.0, 200.0).unwrap().0.center();
+    let path = tree.find_path_points(from, to).unwrap();
+
+    assert!(path.points.len() <= path.areas.len() + 1);
+    assert_eq!(Some(&from), path.points.first());
+    assert_eq!(Some(&to), path.points.last());
+    assert!(path.length() >= from.distance(to));
+
+    // every corner of the path is on the edge of an area of the path
+    for point in &path.points {
+        assert!(path
+            .areas
+            .iter()
+            .any(|id| tree[*id].quad.distance_2d(point.0, point.1) < 0.01));
+    }
+
+    assert_eq!(
+        None,
+        tree.find_path_points(from, Vector3(-100_000.0, 0.0, 0.0))
+    );
+}
+
+#[test]
+fn test_find_path_with_cost() {
+    use crate::EdgeKind;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let shortest = tree.find_path(start, goal).unwrap();
+
+    // forbid the area in the middle of the shortest path
+    let blocked = shortest[shortest.len() / 2];
+    struct Avoid(NavAreaId);
+    impl NavCost for Avoid {
+        fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32> {
+            if to.id == self.0 {
+                None
+            } else {
+                DistanceCost.edge_cost(from, to, kind)
+            }
+        }
+    }
+    if let Some(detour) = tree.find_path_with_cost(start, goal, &Avoid(blocked)) {
+        assert!(!detour.contains(&blocked));
+        assert_ne!(shortest, detour);
+    }
+
+    // a cost forbidding all moves only finds a path to the start itself
+    struct Nowhere;
+    impl NavCost for Nowhere {
+        fn edge_cost(&self, _from: &NavArea, _to: &NavArea, _kind: EdgeKind) -> Option<f32> {
+            None
+        }
+    }
+    assert_eq!(None, tree.find_path_with_cost(start, goal, &Nowhere));
+    assert_eq!(
+        Some(vec![start]),
+        tree.find_path_with_cost(start, start, &Nowhere)
+    );
+}
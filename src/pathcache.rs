@@ -0,0 +1,271 @@
+use crate::groups::connection_key;
+use crate::navmesh::NavAreaId;
+use crate::{NavCost, NavTree};
+use std::collections::{BTreeMap, HashMap};
+
+/// The start and goal of a path with the fingerprint of the cost it was searched with
+type PathKey = (NavAreaId, NavAreaId, u64);
+
+#[derive(Debug, Clone)]
+struct CachedPath {
+    path: Option<Vec<NavAreaId>>,
+    last_used: u64,
+}
+
+/// A cache for paths between areas that drops the least recently used path when it is full
+///
+/// Paths are cached by their start and goal area and a fingerprint of the cost they are searched
+/// with, the fingerprint is chosen by the caller and should be different for every cost that
+/// gives different paths. Searches that don't find a path are cached too.
+///
+/// The cache doesn't know when the areas or costs change, [`set_blocked`], [`set_group_enabled`]
+/// and [`invalidate_cost`] need to be called to drop the paths that might no longer be the
+/// cheapest.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, DistanceCost, PathCache};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let mut cache = PathCache::new(1024);
+/// let path = cache.find_path(&tree, 1.into(), 250.into(), &DistanceCost, 0);
+/// // the second search is answered from the cache
+/// let same_path = cache.find_path(&tree, 1.into(), 250.into(), &DistanceCost, 0);
+///
+/// // a door closes, dropping the paths through it
+/// cache.set_blocked(120.into(), true);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`set_blocked`]: #method.set_blocked
+/// [`set_group_enabled`]: #method.set_group_enabled
+/// [`invalidate_cost`]: #method.invalidate_cost
+#[derive(Debug, Clone)]
+pub struct PathCache {
+    capacity: usize,
+    paths: HashMap<PathKey, CachedPath>,
+    /// The keys of the cached paths by when they were last used
+    usage: BTreeMap<u64, PathKey>,
+    tick: u64,
+}
+
+impl PathCache {
+    /// Create a cache that holds up to `capacity` paths
+    pub fn new(capacity: usize) -> Self {
+        PathCache {
+            capacity,
+            paths: HashMap::new(),
+            usage: BTreeMap::new(),
+            tick: 0,
+        }
+    }
+
+    /// The maximum number of cached paths
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of cached paths
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    /// Whether no paths are cached
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    /// Get the cheapest path between two areas from the cache, or search and cache it
+    ///
+    /// See [`NavTree::find_path_with_cost`] for how the path is found.
+    ///
+    /// [`NavTree::find_path_with_cost`]: ./struct.NavTree.html#method.find_path_with_cost
+    pub fn find_path(
+        &mut self,
+        tree: &NavTree,
+        start: NavAreaId,
+        goal: NavAreaId,
+        cost: &impl NavCost,
+        fingerprint: u64,
+    ) -> Option<Vec<NavAreaId>> {
+        let key = (start, goal, fingerprint);
+        self.tick += 1;
+        if let Some(cached) = self.paths.get_mut(&key) {
+            self.usage.remove(&cached.last_used);
+            cached.last_used = self.tick;
+            self.usage.insert(self.tick, key);
+            return cached.path.clone();
+        }
+
+        let path = tree.find_path_with_cost(start, goal, cost);
+        if self.capacity > 0 {
+            while self.paths.len() >= self.capacity {
+                let oldest = match self.usage.keys().next() {
+                    Some(oldest) => *oldest,
+                    None => break,
+                };
+                if let Some(key) = self.usage.remove(&oldest) {
+                    self.paths.remove(&key);
+                }
+            }
+            self.paths.insert(
+                key,
+                CachedPath {
+                    path: path.clone(),
+                    last_used: self.tick,
+                },
+            );
+            self.usage.insert(self.tick, key);
+        }
+        path
+    }
+
+    /// Drop the cached paths affected by an area being blocked or unblocked
+    ///
+    /// Blocking an area drops the paths going through it, unblocking an area can make any path
+    /// cheaper or reachable so all paths are dropped.
+    pub fn set_blocked(&mut self, id: NavAreaId, blocked: bool) {
+        if blocked {
+            self.retain(|_, path| !path.is_some_and(|path| path.contains(&id)));
+        } else {
+            self.clear();
+        }
+    }
+
+    /// Drop the cached paths affected by a group of connections being enabled or disabled
+    ///
+    /// Disabling a group drops the paths using any of it's connections, enabling a group can make
+    /// any path cheaper or reachable so all paths are dropped. The group is looked up in the tree,
+    /// see [`NavTree::set_group_enabled`].
+    ///
+    /// [`NavTree::set_group_enabled`]: ./struct.NavTree.html#method.set_group_enabled
+    pub fn set_group_enabled(&mut self, tree: &NavTree, name: &str, enabled: bool) {
+        if enabled {
+            self.clear();
+            return;
+        }
+        let connections = match tree.groups.connections(name) {
+            Some(connections) => connections,
+            None => return,
+        };
+        self.retain(|_, path| {
+            !path.is_some_and(|path| {
+                path.windows(2)
+                    .any(|pair| connections.contains(&connection_key(pair[0], pair[1])))
+            })
+        });
+    }
+
+    /// Drop the cached paths searched with a cost
+    pub fn invalidate_cost(&mut self, fingerprint: u64) {
+        self.retain(|key, _| key.2 != fingerprint);
+    }
+
+    /// Drop all cached paths
+    pub fn clear(&mut self) {
+        self.paths.clear();
+        self.usage.clear();
+    }
+
+    fn retain(&mut self, mut keep: impl FnMut(&PathKey, Option<&[NavAreaId]>) -> bool) {
+        let usage = &mut self.usage;
+        self.paths.retain(|key, cached| {
+            let kept = keep(key, cached.path.as_deref());
+            if !kept {
+                usage.remove(&cached.last_used);
+            }
+            kept
+        });
+    }
+}
+
+#[test]
+fn test_path_cache() {
+    use crate::navmesh::NavArea;
+    use crate::{DistanceCost, EdgeKind};
+    use std::cell::Cell;
+
+    /// Distance cost that counts how often it's used
+    struct Counting(Cell<usize>);
+
+    impl NavCost for Counting {
+        fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32> {
+            self.0.set(self.0.get() + 1);
+            DistanceCost.edge_cost(from, to, kind)
+        }
+    }
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let cost = Counting(Cell::new(0));
+    let mut cache = PathCache::new(2);
+    assert!(cache.is_empty());
+
+    let path = cache.find_path(&tree, start, goal, &cost, 0);
+    assert_eq!(tree.find_path(start, goal), path);
+    let searched = cost.0.get();
+    assert!(searched > 0);
+    assert_eq!(path, cache.find_path(&tree, start, goal, &cost, 0));
+    assert_eq!(searched, cost.0.get());
+    assert_eq!(1, cache.len());
+
+    // a different cost is cached separately
+    cache.find_path(&tree, start, goal, &cost, 1);
+    assert_eq!(2, cache.len());
+    assert!(cost.0.get() > searched);
+
+    // the least recently used path is dropped when the cache is full
+    cache.find_path(&tree, start, goal, &cost, 0);
+    cache.find_path(&tree, goal, start, &cost, 0);
+    assert_eq!(2, cache.len());
+    let searched = cost.0.get();
+    cache.find_path(&tree, start, goal, &cost, 0);
+    assert_eq!(searched, cost.0.get());
+    cache.find_path(&tree, start, goal, &cost, 1);
+    assert!(cost.0.get() > searched);
+
+    // blocking an area drops the paths going through it
+    let path = path.unwrap();
+    cache.clear();
+    cache.find_path(&tree, start, goal, &cost, 0);
+    cache.find_path(&tree, start, start, &cost, 0);
+    cache.set_blocked(path[path.len() / 2], true);
+    assert_eq!(1, cache.len());
+    cache.set_blocked(path[path.len() / 2], false);
+    assert!(cache.is_empty());
+
+    cache.find_path(&tree, start, goal, &cost, 0);
+    cache.find_path(&tree, start, goal, &cost, 1);
+    cache.invalidate_cost(1);
+    assert_eq!(1, cache.len());
+
+    // disabling a group drops the paths using it's connections
+    let mut tree = tree;
+    let (a, b) = (path[path.len() / 2], path[path.len() / 2 + 1]);
+    tree.add_connection_group("door", vec![(b, a)]);
+    cache.clear();
+    cache.find_path(&tree, start, goal, &cost, 0);
+    cache.find_path(&tree, start, start, &cost, 0);
+    cache.set_group_enabled(&tree, "unknown", false);
+    assert_eq!(2, cache.len());
+    tree.set_group_enabled("door", false);
+    cache.set_group_enabled(&tree, "door", false);
+    assert_eq!(1, cache.len());
+    tree.set_group_enabled("door", true);
+    cache.set_group_enabled(&tree, "door", true);
+    assert!(cache.is_empty());
+
+    let mut disabled = PathCache::new(0);
+    assert_eq!(
+        Some(vec![start]),
+        disabled.find_path(&tree, start, start, &cost, 0)
+    );
+    assert!(disabled.is_empty());
+}
@@ -0,0 +1,154 @@
+use crate::navmesh::{NavArea, Vector3};
+use crate::NavTree;
+use rand::Rng;
+use std::collections::HashSet;
+
+/// How often to retry picking a point inside of the radius before using the closest point
+const NEAR_ATTEMPTS: usize = 16;
+
+/// Pick a random area, weighted by the surface area of the areas
+fn pick_area<'a>(areas: &[&'a NavArea], rng: &mut impl Rng) -> Option<&'a NavArea> {
+    let weights: Vec<f32> = areas.iter().map(|area| area.quad.surface_area()).collect();
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return None;
+    }
+
+    let mut target = rng.gen_range(0.0..total);
+    for (area, weight) in areas.iter().zip(weights) {
+        if target < weight {
+            return Some(area);
+        }
+        target -= weight;
+    }
+    // rounding errors can leave the target just past the last area
+    areas.last().copied()
+}
+
+/// Pick a random point on the surface of an area
+fn point_in_area(area: &NavArea, rng: &mut impl Rng) -> Vector3 {
+    let quad = &area.quad;
+    let x = quad.north_west.0 + rng.gen::<f32>() * quad.width();
+    let y = quad.north_west.1 + rng.gen::<f32>() * quad.height();
+    Vector3(x, y, quad.interpolated_z(x, y))
+}
+
+impl NavTree {
+    /// Pick a random point on the surface of the mesh
+    ///
+    /// Areas are picked with a chance proportional to their surface area, the height of the point
+    /// is interpolated from the corners of the area.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let spawn = tree.random_point(&mut rand::thread_rng());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn random_point(&self, rng: &mut impl Rng) -> Option<Vector3> {
+        let areas: Vec<&NavArea> = self.areas().collect();
+        pick_area(&areas, rng).map(|area| point_in_area(area, rng))
+    }
+
+    /// Pick a random point on the surface of the areas within a radius around a point
+    ///
+    /// If `connected` is set, only areas that can be reached from the area at the origin through
+    /// connections without leaving the radius are considered.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let origin = Vector3(150.0, -312.0, 64.0);
+    /// let target = tree.random_point_near(origin, 500.0, true, &mut rand::thread_rng());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn random_point_near(
+        &self,
+        origin: Vector3,
+        radius: f32,
+        connected: bool,
+        rng: &mut impl Rng,
+    ) -> Option<Vector3> {
+        let (x, y) = (origin.0, origin.1);
+        let areas: Vec<&NavArea> = if connected {
+            let start = self
+                .open_area_under(origin.0, origin.1, origin.2)
+                .or_else(|| Some(self.nearest_area(x, y)?.0))?;
+            let mut seen = HashSet::new();
+            seen.insert(start.id);
+            let mut queue = vec![start];
+            let mut areas = Vec::new();
+            while let Some(area) = queue.pop() {
+                areas.push(area);
+                for next in area.connections.iter().filter_map(|id| self.area(*id)) {
+                    if next.quad.distance_2d(x, y) <= radius && seen.insert(next.id) {
+                        queue.push(next);
+                    }
+                }
+            }
+            areas
+        } else {
+            self.query_radius(x, y, radius).collect()
+        };
+
+        let area = pick_area(&areas, rng)?;
+        let in_radius =
+            |point: &Vector3| (point.0 - x).powi(2) + (point.1 - y).powi(2) <= radius * radius;
+        let point = (0..NEAR_ATTEMPTS)
+            .map(|_| point_in_area(area, rng))
+            .find(in_radius)
+            .unwrap_or_else(|| area.quad.closest_point(x, y));
+        Some(point)
+    }
+}
+
+#[test]
+fn test_random_point() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+    let mut rng = StdRng::seed_from_u64(1);
+
+    for _ in 0..100 {
+        let point = tree.random_point(&mut rng).unwrap();
+        let on_surface =
+            |area: &NavArea| (area.quad.interpolated_z(point.0, point.1) - point.2).abs() < 0.01;
+        assert!(tree.query(point.0, point.1).any(on_surface));
+    }
+}
+
+#[test]
+fn test_random_point_near() {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+    let mut rng = StdRng::seed_from_u64(1);
+
+    let origin = tree.areas().next().unwrap().quad.center();
+    for connected in [false, true].iter() {
+        for _ in 0..50 {
+            let point = tree
+                .random_point_near(origin, 300.0, *connected, &mut rng)
+                .unwrap();
+            let distance = ((point.0 - origin.0).powi(2) + (point.1 - origin.1).powi(2)).sqrt();
+            assert!(distance <= 300.0 + 0.01);
+            assert!(tree.query(point.0, point.1).next().is_some());
+        }
+    }
+}
@@ -0,0 +1,542 @@
+use crate::navmesh::{NavArea, NavQuad, Vector3};
+use crate::NavTree;
+
+/// How far past the edge of an area to look for the next area
+const EDGE_STEP: f32 = 0.1;
+
+/// Get the range of the ray parameter for which the ray is above or below a rectangle
+fn slab_range(
+    origin: Vector3,
+    direction: Vector3,
+    min: (f32, f32),
+    max: (f32, f32),
+) -> Option<(f32, f32)> {
+    let mut range = (f32::NEG_INFINITY, f32::INFINITY);
+    for (start, delta, min, max) in [
+        (origin.0, direction.0, min.0, max.0),
+        (origin.1, direction.1, min.1, max.1),
+    ]
+    .iter()
+    {
+        if *delta == 0.0 {
+            if start < min || start > max {
+                return None;
+            }
+        } else {
+            let (a, b) = ((min - start) / delta, (max - start) / delta);
+            range = (range.0.max(a.min(b)), range.1.min(a.max(b)));
+        }
+    }
+    if range.0 <= range.1 {
+        Some(range)
+    } else {
+        None
+    }
+}
+
+/// Intersect a ray with the bilinear surface of a quad, returning the ray parameter of the first
+/// intersection in front of the origin
+fn intersect_quad(quad: &NavQuad, origin: Vector3, direction: Vector3) -> Option<f32> {
+    let (width, height) = (quad.width(), quad.height());
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+    let (start, end) = slab_range(
+        origin,
+        direction,
+        (quad.north_west.0, quad.north_west.1),
+        (quad.south_east.0, quad.south_east.1),
+    )?;
+    let start = start.max(0.0);
+    if start > end {
+        return None;
+    }
+
+    // the height of the surface along the ray is quadratic in the ray parameter
+    let (u0, u1) = ((origin.0 - quad.north_west.0) / width, direction.0 / width);
+    let (v0, v1) = (
+        (origin.1 - quad.north_west.1) / height,
+        direction.1 / height,
+    );
+    let z_nw = quad.north_west.2;
+    let slope_u = quad.north_east_z - z_nw;
+    let slope_v = quad.south_west_z - z_nw;
+    let twist = z_nw - quad.north_east_z - quad.south_west_z + quad.south_east.2;
+
+    let a = -twist * u1 * v1;
+    let b = direction.2 - (slope_u * u1 + slope_v * v1 + twist * (u0 * v1 + u1 * v0));
+    let c = origin.2 - (z_nw + slope_u * u0 + slope_v * v0 + twist * u0 * v0);
+
+    let roots = if a.abs() < 1e-9 {
+        if b == 0.0 {
+            return None;
+        }
+        [-c / b, f32::NAN]
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            return None;
+        }
+        let root = discriminant.sqrt();
+        [(-b - root) / (2.0 * a), (-b + root) / (2.0 * a)]
+    };
+
+    roots
+        .iter()
+        .copied()
+        .filter(|t| *t >= start && *t <= end)
+        .fold(None, |first: Option<f32>, t| {
+            Some(first.map_or(t, |first| first.min(t)))
+        })
+}
+
+/// The intersection of a ray with the mesh, see [`NavTree::raycast_3d`]
+///
+/// [`NavTree::raycast_3d`]: ./struct.NavTree.html#method.raycast_3d
+#[derive(Debug, Clone)]
+pub struct RaycastHit<'a> {
+    pub area: &'a NavArea,
+    pub point: Vector3,
+    /// The distance from the origin of the ray to the hit point
+    pub distance: f32,
+}
+
+/// The part of a line crossing an area, see [`NavTree::areas_along`]
+///
+/// [`NavTree::areas_along`]: ./struct.NavTree.html#method.areas_along
+#[derive(Debug, Clone)]
+pub struct AreaCrossing<'a> {
+    pub area: &'a NavArea,
+    /// The point where the line enters the area, with the height of the area at that point
+    pub entry: Vector3,
+    /// The point where the line leaves the area, with the height of the area at that point
+    pub exit: Vector3,
+    /// The distance along the line from the start of the line to the entry point
+    pub entry_distance: f32,
+    /// The distance along the line from the start of the line to the exit point
+    pub exit_distance: f32,
+}
+
+/// Get the fraction of the segment at which it leaves the quad
+fn exit_fraction(quad: &NavQuad, from: (f32, f32), delta: (f32, f32)) -> f32 {
+    let slab = |start: f32, delta: f32, min: f32, max: f32| {
+        if delta > 0.0 {
+            (max - start) / delta
+        } else if delta < 0.0 {
+            (min - start) / delta
+        } else {
+            f32::INFINITY
+        }
+    };
+    f32::min(
+        slab(from.0, delta.0, quad.north_west.0, quad.south_east.0),
+        slab(from.1, delta.1, quad.north_west.1, quad.south_east.1),
+    )
+}
+
+impl NavTree {
+    /// Find the first area surface hit by a ray
+    ///
+    /// The surface of areas is the bilinear interpolation of the corner heights, areas are hit
+    /// from above and below.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// // probe the ground below a point
+    /// if let Some(hit) = tree.raycast_3d(Vector3(150.0, -312.0, 500.0), Vector3(0.0, 0.0, -1.0)) {
+    ///     println!("hit area {} at {:?}", hit.area.id, hit.point);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raycast_3d(&self, origin: Vector3, direction: Vector3) -> Option<RaycastHit<'_>> {
+        let length = (direction.0.powi(2) + direction.1.powi(2) + direction.2.powi(2)).sqrt();
+        if length == 0.0 {
+            return None;
+        }
+
+        // only the part of the ray above the bounds of the tree can hit an area
+        let (min, max) = self.bounds();
+        let (start, end) = slab_range(origin, direction, (min.0, min.1), (max.0, max.1))?;
+        let (start, end) = (start.max(0.0), end);
+        if start > end {
+            return None;
+        }
+        let (x1, y1) = (
+            origin.0 + direction.0 * start,
+            origin.1 + direction.1 * start,
+        );
+        let (x2, y2) = (origin.0 + direction.0 * end, origin.1 + direction.1 * end);
+
+        self.query_rect(
+            (x1.min(x2) - 1.0, y1.min(y2) - 1.0),
+            (x1.max(x2) + 1.0, y1.max(y2) + 1.0),
+        )
+        .filter_map(|area| Some((area, intersect_quad(&area.quad, origin, direction)?)))
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(area, t)| RaycastHit {
+            area,
+            point: Vector3(
+                origin.0 + direction.0 * t,
+                origin.1 + direction.1 * t,
+                origin.2 + direction.2 * t,
+            ),
+            distance: t * length,
+        })
+    }
+
+    /// Find all areas crossed by a straight line between two x/y coordinates, ordered along the
+    /// line
+    ///
+    /// Only the x/y coordinates of the areas are taken into account, when areas overlap, like
+    /// areas on different floors, all of them are included. Areas crossed at the same point are
+    /// ordered by where the line leaves them.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for crossing in tree.areas_along((150.0, -312.0), (1200.0, -312.0)) {
+    ///     println!(
+    ///         "area {} from {} to {} units",
+    ///         crossing.area.id, crossing.entry_distance, crossing.exit_distance
+    ///     );
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn areas_along(&self, from: (f32, f32), to: (f32, f32)) -> Vec<AreaCrossing<'_>> {
+        let origin = Vector3(from.0, from.1, 0.0);
+        let direction = Vector3(to.0 - from.0, to.1 - from.1, 0.0);
+        let length = (direction.0.powi(2) + direction.1.powi(2)).sqrt();
+        let point = |area: &NavArea, t: f32| {
+            let (x, y) = (from.0 + direction.0 * t, from.1 + direction.1 * t);
+            Vector3(x, y, area.quad.interpolated_z(x, y))
+        };
+
+        let mut crossings: Vec<_> = self
+            .query_corridor(from, to, 0.0)
+            .filter_map(|area| {
+                let quad = &area.quad;
+                let (start, end) = slab_range(
+                    origin,
+                    direction,
+                    (quad.north_west.0, quad.north_west.1),
+                    (quad.south_east.0, quad.south_east.1),
+                )?;
+                let (start, end) = (start.max(0.0), end.min(1.0));
+                if start > end {
+                    return None;
+                }
+                Some(AreaCrossing {
+                    area,
+                    entry: point(area, start),
+                    exit: point(area, end),
+                    entry_distance: start * length,
+                    exit_distance: end * length,
+                })
+            })
+            .collect();
+        crossings.sort_by(|a, b| {
+            (a.entry_distance, a.exit_distance)
+                .partial_cmp(&(b.entry_distance, b.exit_distance))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        crossings
+    }
+
+    /// Sample the height of the ground at regular intervals along a path
+    ///
+    /// Returns the distance along the path and the height of the ground for every sample, taken
+    /// every `step` hammer units starting at the first point and including the last point. The
+    /// ground at a sample is the area with the height closest to the height of the path at that
+    /// point, samples that aren't above any area are skipped.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let route = [
+    ///     Vector3(150.0, -312.0, 64.0),
+    ///     Vector3(600.0, -312.0, 64.0),
+    ///     Vector3(600.0, 200.0, 128.0),
+    /// ];
+    /// for (distance, height) in tree.elevation_profile(&route, 16.0) {
+    ///     println!("{}: {}", distance, height);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn elevation_profile(&self, points: &[Vector3], step: f32) -> Vec<(f32, f32)> {
+        let mut profile = Vec::new();
+        if step <= 0.0 {
+            return profile;
+        }
+        let mut sample = |distance: f32, point: Vector3| {
+            if let Some(area) = self.open_area_under(point.0, point.1, point.2) {
+                profile.push((distance, area.quad.interpolated_z(point.0, point.1)));
+            }
+        };
+
+        // the distance along the path at the start of the current segment and of the next sample
+        let mut start = 0.0;
+        let mut next = 0.0;
+        for segment in points.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+            while next < start + length {
+                let t = (next - start) / length;
+                sample(
+                    next,
+                    Vector3(
+                        from.0 + (to.0 - from.0) * t,
+                        from.1 + (to.1 - from.1) * t,
+                        from.2 + (to.2 - from.2) * t,
+                    ),
+                );
+                next += step;
+            }
+            start += length;
+        }
+        if let Some(last) = points.last() {
+            sample(start, *last);
+        }
+
+        profile
+    }
+
+    /// Walk along a straight line over the mesh, following the connections between areas
+    ///
+    /// Returns `None` if the line stays on the mesh from start to end, or the first point where
+    /// the line leaves the mesh if it doesn't. Only the x/y coordinates of the areas are taken
+    /// into account, if multiple areas exist at the start of the line, the walk is started from
+    /// all of them.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// match tree.raycast_2d((150.0, -312.0), (600.0, -312.0)) {
+    ///     None => println!("the path is clear"),
+    ///     Some((x, y)) => println!("the path ends at {}, {}", x, y),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn raycast_2d(&self, from: (f32, f32), to: (f32, f32)) -> Option<(f32, f32)> {
+        let delta = (to.0 - from.0, to.1 - from.1);
+        let point = |t: f32| (from.0 + delta.0 * t, from.1 + delta.1 * t);
+
+        let mut furthest: Option<f32> = None;
+        let start_areas: Vec<&NavArea> = self
+            .query(from.0, from.1)
+            .filter(|area| area.contains(from.0, from.1))
+            .collect();
+
+        for start in start_areas {
+            // the line is clear if the walk from any of the start areas is clear
+            let gap = match self.walk_line(start, from, to, |_| {}) {
+                Ok(_) => return None,
+                Err(gap) => gap,
+            };
+            furthest = Some(furthest.map_or(gap, |furthest| furthest.max(gap)));
+        }
+
+        Some(point(furthest.unwrap_or(0.0)))
+    }
+
+    /// Walk along a straight line starting in an area, following the connections between areas
+    ///
+    /// Every area walked through is passed to `visit`, in order. Returns the area at the end of
+    /// the line, or the fraction of the line at which it leaves the mesh. Blocked areas and
+    /// disabled connections are treated as outside of the mesh.
+    pub(crate) fn walk_line<'a>(
+        &'a self,
+        start: &'a NavArea,
+        from: (f32, f32),
+        to: (f32, f32),
+        mut visit: impl FnMut(&'a NavArea),
+    ) -> Result<&'a NavArea, f32> {
+        let delta = (to.0 - from.0, to.1 - from.1);
+        let length = (delta.0 * delta.0 + delta.1 * delta.1).sqrt();
+        let step = if length > 0.0 {
+            EDGE_STEP / length
+        } else {
+            0.0
+        };
+        let point = |t: f32| (from.0 + delta.0 * t, from.1 + delta.1 * t);
+
+        if self.is_blocked(start.id) {
+            return Err(0.0);
+        }
+
+        let mut area = start;
+        let mut steps = 0;
+        loop {
+            visit(area);
+            let exit = exit_fraction(&area.quad, from, delta);
+            if exit >= 1.0 {
+                return Ok(area);
+            }
+            let (x, y) = point(exit + step);
+            let next = area
+                .connections
+                .iter()
+                .filter_map(|id| self.area(*id))
+                .find(|next| {
+                    next.contains(x, y)
+                        && !self.is_blocked(next.id)
+                        && !self.groups.is_disabled(area.id, next.id)
+                });
+            steps += 1;
+            match next {
+                Some(next) if steps <= self.len() => area = next,
+                _ => return Err(exit),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_raycast_2d() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    // walking to a connected area stays on the mesh
+    let area = tree.areas().next().unwrap();
+    let target = tree[*area.connections.iter().next().unwrap()].quad.center();
+    let start = area.quad.center();
+    assert_eq!(
+        None,
+        tree.raycast_2d((start.0, start.1), (target.0, target.1))
+    );
+
+    // walking out of the map ends at the edge of the mesh
+    let far = (start.0 + 100_000.0, start.1);
+    let (x, y) = tree.raycast_2d((start.0, start.1), far).unwrap();
+    assert_eq!(start.1, y);
+    assert!(x > start.0 && x < far.0);
+    assert!(tree.query(x - 1.0, y).next().is_some());
+
+    // starting off the mesh
+    assert_eq!(Some(far), tree.raycast_2d(far, (far.0 + 10.0, far.1)));
+}
+
+#[test]
+fn test_raycast_3d() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let area = tree.areas().next().unwrap();
+    let center = area.quad.center();
+
+    // straight down onto the area
+    let hit = tree
+        .raycast_3d(
+            Vector3(center.0, center.1, center.2 + 100.0),
+            Vector3(0.0, 0.0, -2.0),
+        )
+        .unwrap();
+    let expected = area.quad.interpolated_z(center.0, center.1);
+    assert!((hit.point.2 - expected).abs() < 0.01);
+    assert!((hit.distance - (center.2 + 100.0 - expected)).abs() < 0.01);
+
+    // at an angle, the hit point is on the surface of the hit area
+    let hit = tree
+        .raycast_3d(
+            Vector3(center.0 - 200.0, center.1 - 100.0, center.2 + 300.0),
+            Vector3(1.0, 0.5, -1.0),
+        )
+        .unwrap();
+    let surface = hit.area.quad.interpolated_z(hit.point.0, hit.point.1);
+    assert!((hit.point.2 - surface).abs() < 0.1);
+
+    // pointing up from above the map
+    assert!(tree
+        .raycast_3d(
+            Vector3(center.0, center.1, 100_000.0),
+            Vector3(0.0, 0.0, 1.0)
+        )
+        .is_none());
+}
+
+#[test]
+fn test_areas_along() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let (from, to) = ((-1000.0, -1030.0), (1500.0, -1030.0));
+    let crossings = tree.areas_along(from, to);
+    assert!(crossings.len() > 2);
+
+    for pair in crossings.windows(2) {
+        assert!(pair[0].entry_distance <= pair[1].entry_distance);
+    }
+    for crossing in &crossings {
+        assert!(crossing.entry_distance <= crossing.exit_distance);
+        assert!(crossing.exit_distance <= 2500.0 + 0.01);
+        assert!((crossing.entry.1 - -1030.0).abs() < 0.01);
+        assert!(crossing.area.contains(crossing.entry.0, crossing.entry.1));
+        assert!(crossing.area.contains(crossing.exit.0, crossing.exit.1));
+        assert!((crossing.entry.0 - from.0 - crossing.entry_distance).abs() < 0.1);
+    }
+
+    // a line in a single area
+    let area = tree.areas().next().unwrap();
+    let center = area.center();
+    let crossings = tree.areas_along((center.0, center.1), (center.0 + 1.0, center.1));
+    assert!(crossings.iter().any(|crossing| crossing.area.id == area.id));
+}
+
+#[test]
+fn test_elevation_profile() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let route = [
+        Vector3(-200.0, -1030.0, 220.0),
+        Vector3(320.0, -1030.0, 220.0),
+        Vector3(320.0, -800.0, 220.0),
+    ];
+    let profile = tree.elevation_profile(&route, 10.0);
+    assert!(!profile.is_empty());
+    assert_eq!(750.0, profile.last().unwrap().0);
+
+    for pair in profile.windows(2) {
+        assert!(pair[0].0 < pair[1].0);
+    }
+    for (distance, height) in &profile {
+        let (x, y) = if *distance <= 520.0 {
+            (-200.0 + distance, -1030.0)
+        } else {
+            (320.0, -1030.0 + (distance - 520.0))
+        };
+        assert!(tree
+            .query_strict(x, y)
+            .any(|area| (area.quad.interpolated_z(x, y) - height).abs() < 0.01));
+    }
+
+    // the samples are spaced by the step, except at the end
+    let full = tree.elevation_profile(&route[..2], 13.0);
+    assert!(full.len() <= 41);
+    assert!(tree.elevation_profile(&route, 0.0).is_empty());
+    assert!(tree.elevation_profile(&[], 10.0).is_empty());
+}
@@ -0,0 +1,354 @@
+use crate::navmesh::{NavArea, NavAreaId};
+use crate::{EdgeKind, NavCost, NavTree};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+/// The priority of an area in the open set, compared by the first value and then the second
+type Key = (f32, f32);
+
+fn key_cmp(a: Key, b: Key) -> Ordering {
+    a.0.partial_cmp(&b.0)
+        .unwrap_or(Ordering::Equal)
+        .then_with(|| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    key: Key,
+    id: NavAreaId,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        key_cmp(other.key, self.key).then_with(|| other.id.cmp(&self.id))
+    }
+}
+
+/// A path search that keeps it's state to cheaply repair the path when areas are blocked or
+/// unblocked, or the start moves along the path
+///
+/// The replanner uses D* Lite, which searches from the goal towards the start. After a change
+/// only the part of the search affected by the change is redone, which is much cheaper than a
+/// new search when the changes are close to the start, like when a door closes in front of a
+/// moving player.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, DistanceCost, Replanner};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let mut replanner = Replanner::new(&tree, 1.into(), 250.into(), DistanceCost);
+/// let path = replanner.path();
+///
+/// // a door closes
+/// replanner.set_blocked(120.into(), true);
+/// let detour = replanner.path();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct Replanner<'a, C: NavCost> {
+    tree: &'a NavTree,
+    cost: C,
+    start: NavAreaId,
+    goal: NavAreaId,
+    /// The start when the keys of the open areas were last calculated
+    last_start: NavAreaId,
+    /// Offset for the keys of the open areas, accounting for the moves of the start
+    key_modifier: f32,
+    /// The cost of reaching the goal from an area as of the last expansion of the area
+    g: HashMap<NavAreaId, f32>,
+    /// The cost of reaching the goal from an area based on the costs of it's successors
+    rhs: HashMap<NavAreaId, f32>,
+    /// The current key of every area in the open set, heap entries with other keys are outdated
+    open_keys: HashMap<NavAreaId, Key>,
+    open: BinaryHeap<Entry>,
+    blocked: HashSet<NavAreaId>,
+    incoming: HashMap<NavAreaId, Vec<(NavAreaId, EdgeKind)>>,
+}
+
+impl<'a, C: NavCost> Replanner<'a, C> {
+    /// Create a replanner for a path between two areas
+    ///
+    /// No search is done until the path is requested.
+    pub fn new(tree: &'a NavTree, start: NavAreaId, goal: NavAreaId, cost: C) -> Self {
+        let mut incoming: HashMap<NavAreaId, Vec<(NavAreaId, EdgeKind)>> = HashMap::new();
+        for area in tree.areas() {
+            for (next, kind) in tree.traversable_neighbors(area) {
+                incoming.entry(next.id).or_default().push((area.id, kind));
+            }
+        }
+
+        let mut replanner = Replanner {
+            tree,
+            cost,
+            start,
+            goal,
+            last_start: start,
+            key_modifier: 0.0,
+            g: HashMap::new(),
+            rhs: HashMap::new(),
+            open_keys: HashMap::new(),
+            open: BinaryHeap::new(),
+            blocked: HashSet::new(),
+            incoming,
+        };
+        if tree.area(goal).is_some() {
+            replanner.rhs.insert(goal, 0.0);
+            let key = replanner.key(goal);
+            replanner.push(goal, key);
+        }
+        replanner
+    }
+
+    /// The area the path starts from
+    pub fn start(&self) -> NavAreaId {
+        self.start
+    }
+
+    /// The area the path leads to
+    pub fn goal(&self) -> NavAreaId {
+        self.goal
+    }
+
+    /// Move the start of the path, usually to the next area of the path
+    pub fn set_start(&mut self, start: NavAreaId) {
+        if let (Some(last), Some(next)) = (self.tree.area(self.last_start), self.tree.area(start)) {
+            self.key_modifier += self.cost.estimate(last, next);
+            self.last_start = start;
+        }
+        self.start = start;
+    }
+
+    /// Block or unblock an area, blocked areas can't be entered or left
+    pub fn set_blocked(&mut self, id: NavAreaId, blocked: bool) {
+        let changed = if blocked {
+            self.blocked.insert(id)
+        } else {
+            self.blocked.remove(&id)
+        };
+        if changed {
+            self.update(id);
+            self.update_predecessors(id);
+        }
+    }
+
+    /// Whether an area is blocked
+    pub fn is_blocked(&self, id: NavAreaId) -> bool {
+        self.blocked.contains(&id)
+    }
+
+    /// Get the cheapest path from the start to the goal
+    ///
+    /// Only the parts of the search affected by changes since the last call are redone. Returns
+    /// `None` if the goal can't be reached from the start.
+    pub fn path(&mut self) -> Option<Vec<NavAreaId>> {
+        self.tree.area(self.start)?;
+        self.compute();
+        if self.g(self.start).is_infinite() {
+            return None;
+        }
+
+        let mut path = vec![self.start];
+        let mut current = self.start;
+        while current != self.goal {
+            let (next, _) = self.successors(current).min_by(|a, b| {
+                (a.1 + self.g(a.0))
+                    .partial_cmp(&(b.1 + self.g(b.0)))
+                    .unwrap_or(Ordering::Equal)
+            })?;
+            // the search is consistent after computing, so this only guards against bad costs
+            if path.len() > self.g.len() {
+                return None;
+            }
+            path.push(next);
+            current = next;
+        }
+        Some(path)
+    }
+
+    /// Get the cost of the cheapest path from the start to the goal
+    pub fn path_cost(&mut self) -> Option<f32> {
+        self.tree.area(self.start)?;
+        self.compute();
+        Some(self.g(self.start)).filter(|cost| cost.is_finite())
+    }
+
+    fn g(&self, id: NavAreaId) -> f32 {
+        self.g.get(&id).copied().unwrap_or(f32::INFINITY)
+    }
+
+    fn rhs(&self, id: NavAreaId) -> f32 {
+        self.rhs.get(&id).copied().unwrap_or(f32::INFINITY)
+    }
+
+    fn area(&self, id: NavAreaId) -> Option<&'a NavArea> {
+        self.tree.area(id).filter(|_| !self.blocked.contains(&id))
+    }
+
+    fn edge_cost(&self, from: NavAreaId, to: NavAreaId, kind: EdgeKind) -> Option<f32> {
+        self.tree
+            .move_cost(&self.cost, self.area(from)?, self.area(to)?, kind)
+    }
+
+    /// The areas that can be reached from an area, with the cost of moving there
+    fn successors(&self, id: NavAreaId) -> impl Iterator<Item = (NavAreaId, f32)> + '_ {
+        let tree = self.tree;
+        self.area(id).into_iter().flat_map(move |area| {
+            tree.traversable_neighbors(area)
+                .filter_map(move |(next, kind)| Some((next.id, self.edge_cost(id, next.id, kind)?)))
+        })
+    }
+
+    fn key(&self, id: NavAreaId) -> Key {
+        let cost = self.g(id).min(self.rhs(id));
+        let heuristic = match (self.tree.area(self.start), self.tree.area(id)) {
+            (Some(start), Some(area)) => self.cost.estimate(start, area),
+            _ => 0.0,
+        };
+        (cost + heuristic + self.key_modifier, cost)
+    }
+
+    fn push(&mut self, id: NavAreaId, key: Key) {
+        self.open_keys.insert(id, key);
+        self.open.push(Entry { key, id });
+    }
+
+    /// Get the area with the lowest key in the open set, dropping outdated entries
+    fn top(&mut self) -> Option<Entry> {
+        while let Some(entry) = self.open.peek() {
+            if self.open_keys.get(&entry.id) == Some(&entry.key) {
+                return Some(*entry);
+            }
+            self.open.pop();
+        }
+        None
+    }
+
+    /// Recalculate the cost of an area from it's successors and put it in the open set if the
+    /// cost changed
+    fn update(&mut self, id: NavAreaId) {
+        if id != self.goal {
+            let rhs = self
+                .successors(id)
+                .map(|(next, cost)| cost + self.g(next))
+                .fold(f32::INFINITY, f32::min);
+            self.rhs.insert(id, rhs);
+        }
+        self.open_keys.remove(&id);
+        if self.g(id) != self.rhs(id) {
+            let key = self.key(id);
+            self.push(id, key);
+        }
+    }
+
+    fn update_predecessors(&mut self, id: NavAreaId) {
+        let predecessors: Vec<NavAreaId> = self
+            .incoming
+            .get(&id)
+            .into_iter()
+            .flatten()
+            .map(|(previous, _)| *previous)
+            .collect();
+        for previous in predecessors {
+            self.update(previous);
+        }
+    }
+
+    fn compute(&mut self) {
+        while let Some(Entry { key, id }) = self.top() {
+            let start_key = self.key(self.start);
+            if key_cmp(key, start_key) != Ordering::Less
+                && self.rhs(self.start) == self.g(self.start)
+            {
+                break;
+            }
+
+            self.open.pop();
+            self.open_keys.remove(&id);
+            let new_key = self.key(id);
+            if key_cmp(key, new_key) == Ordering::Less {
+                self.push(id, new_key);
+            } else if self.g(id) > self.rhs(id) {
+                self.g.insert(id, self.rhs(id));
+                self.update_predecessors(id);
+            } else {
+                self.g.insert(id, f32::INFINITY);
+                self.update(id);
+                self.update_predecessors(id);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_replanner() {
+    use crate::DistanceCost;
+
+    /// Distance cost that doesn't allow entering some areas
+    struct Blocked<'a>(&'a HashSet<NavAreaId>);
+
+    impl NavCost for Blocked<'_> {
+        fn edge_cost(&self, from: &NavArea, to: &NavArea, kind: EdgeKind) -> Option<f32> {
+            if self.0.contains(&from.id) || self.0.contains(&to.id) {
+                None
+            } else {
+                DistanceCost.edge_cost(from, to, kind)
+            }
+        }
+    }
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+    let length = |path: &[NavAreaId]| -> f32 {
+        path.windows(2)
+            .map(|pair| tree[pair[0]].center().distance(tree[pair[1]].center()))
+            .sum()
+    };
+
+    let start = tree.nearest_area(-1000.0, -1030.0).unwrap().0.id;
+    let goal = tree.nearest_area(1500.0, 200.0).unwrap().0.id;
+    let mut replanner = Replanner::new(&tree, start, goal, DistanceCost);
+    let path = replanner.path().unwrap();
+    let expected = tree.find_path(start, goal).unwrap();
+    assert_eq!(Some(&start), path.first());
+    assert_eq!(Some(&goal), path.last());
+    assert!((length(&expected) - length(&path)).abs() < 0.1);
+    assert!((length(&path) - replanner.path_cost().unwrap()).abs() < 0.1);
+
+    // block an area on the path after moving along it
+    replanner.set_start(path[2]);
+    let mut blocked = HashSet::new();
+    blocked.insert(path[path.len() / 2]);
+    replanner.set_blocked(path[path.len() / 2], true);
+    let expected = tree.find_path_with_cost(path[2], goal, &Blocked(&blocked));
+    let detour = replanner.path();
+    assert_eq!(expected.is_some(), detour.is_some());
+    if let (Some(expected), Some(detour)) = (expected, detour) {
+        assert_eq!(Some(&path[2]), detour.first());
+        assert!(!detour.contains(&path[path.len() / 2]));
+        assert!((length(&expected) - length(&detour)).abs() < 0.1);
+    }
+
+    // unblocking restores the original path
+    replanner.set_blocked(path[path.len() / 2], false);
+    let restored = replanner.path().unwrap();
+    assert!((length(&path[2..]) - length(&restored)).abs() < 0.1);
+}
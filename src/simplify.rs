@@ -0,0 +1,181 @@
+use crate::navmesh::{NavArea, NavAreaId, Vector3};
+use crate::{NavPath, NavTree};
+
+/// Get the distance from a point to a line segment
+fn segment_distance(point: Vector3, from: Vector3, to: Vector3) -> f32 {
+    let delta = (to.0 - from.0, to.1 - from.1, to.2 - from.2);
+    let length = delta.0 * delta.0 + delta.1 * delta.1 + delta.2 * delta.2;
+    if length == 0.0 {
+        return point.distance(from);
+    }
+    let t = ((point.0 - from.0) * delta.0
+        + (point.1 - from.1) * delta.1
+        + (point.2 - from.2) * delta.2)
+        / length;
+    let t = t.clamp(0.0, 1.0);
+    point.distance(Vector3(
+        from.0 + delta.0 * t,
+        from.1 + delta.1 * t,
+        from.2 + delta.2 * t,
+    ))
+}
+
+/// Walk a straight line between two points, returning the areas walked through
+fn walk<'a>(
+    tree: &'a NavTree,
+    area: &'a NavArea,
+    from: Vector3,
+    to: Vector3,
+) -> Option<Vec<&'a NavArea>> {
+    let mut walked = Vec::new();
+    tree.walk_line(area, (from.0, from.1), (to.0, to.1), |area| {
+        walked.push(area)
+    })
+    .ok()?;
+    Some(walked)
+}
+
+impl NavTree {
+    /// Remove the points of a path that don't change the route by more than a tolerance
+    ///
+    /// A point is removed when it is within `tolerance` of the straight line between the points
+    /// before and after it and that line can be walked over the connections between the areas,
+    /// so the simplified path never leaves the mesh. The areas of the simplified path are the
+    /// areas the new lines walk through, moves that aren't straight lines over the mesh like
+    /// ladders are kept as is.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let from = Vector3(150.0, -312.0, 64.0);
+    /// let to = Vector3(1200.0, 200.0, 128.0);
+    /// if let Some(path) = tree.find_path_points(from, to) {
+    ///     let simplified = tree.simplify_path(&path, 16.0);
+    ///     println!("{} points instead of {}", simplified.points.len(), path.points.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn simplify_path(&self, path: &NavPath, tolerance: f32) -> NavPath {
+        let points = &path.points;
+        let start = match path.areas.first().and_then(|id| self.area(*id)) {
+            Some(start) if points.len() > 2 => start,
+            _ => return path.clone(),
+        };
+
+        let mut areas: Vec<NavAreaId> = vec![start.id];
+        let mut simplified = vec![points[0]];
+        let mut anchor = 0;
+        let mut area = start;
+        while anchor + 1 < points.len() {
+            // find the furthest point that can be reached in a straight line
+            let mut next = anchor + 1;
+            let mut walked = walk(self, area, points[anchor], points[next]);
+            for candidate in anchor + 2..points.len() {
+                let close = points[anchor + 1..candidate].iter().all(|point| {
+                    segment_distance(*point, points[anchor], points[candidate]) <= tolerance
+                });
+                if !close {
+                    break;
+                }
+                match walk(self, area, points[anchor], points[candidate]) {
+                    Some(candidate_walk) => {
+                        next = candidate;
+                        walked = Some(candidate_walk);
+                    }
+                    None => break,
+                }
+            }
+
+            let end = points[next];
+            match walked {
+                Some(walked) => {
+                    area = walked.last().copied().unwrap_or(area);
+                    areas.extend(walked.into_iter().skip(1).map(|area| area.id));
+                }
+                None => {
+                    // moves over ladders and links aren't straight lines over the mesh
+                    area = match self.open_area_under(end.0, end.1, end.2) {
+                        Some(next_area) => next_area,
+                        None => area,
+                    };
+                    if areas.last() != Some(&area.id) {
+                        areas.push(area.id);
+                    }
+                }
+            }
+            simplified.push(end);
+            anchor = next;
+        }
+
+        if let Some(last) = path.areas.last() {
+            if areas.last() != Some(last) {
+                areas.push(*last);
+            }
+        }
+
+        NavPath {
+            areas,
+            points: simplified,
+        }
+    }
+}
+
+#[test]
+fn test_simplify_path() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let from = tree.nearest_area(-1000.0, -1030.0).unwrap().0.center();
+    let to = tree.nearest_area(1500.0, 200.0).unwrap().0.center();
+    let path = tree.find_path_points(from, to).unwrap();
+
+    // points on a straight line are redundant
+    let mut padded = path.clone();
+    let (a, b) = (padded.points[0], padded.points[1]);
+    padded.points.insert(
+        1,
+        Vector3((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0, (a.2 + b.2) / 2.0),
+    );
+    let simplified = tree.simplify_path(&padded, 0.1);
+    assert!(simplified.points.len() < padded.points.len());
+    assert_eq!(Some(&from), simplified.points.first());
+    assert_eq!(Some(&to), simplified.points.last());
+
+    for tolerance in [0.0, 16.0, 100_000.0].iter().copied() {
+        let simplified = tree.simplify_path(&path, tolerance);
+        assert!(simplified.points.len() <= path.points.len());
+        assert!(simplified.points.len() >= 2);
+        assert_eq!(Some(&from), simplified.points.first());
+        assert_eq!(Some(&to), simplified.points.last());
+        assert_eq!(path.areas.first(), simplified.areas.first());
+        assert_eq!(path.areas.last(), simplified.areas.last());
+
+        // every shortcut of the simplified path stays on the mesh
+        let mut index = 0;
+        for line in simplified.points.windows(2) {
+            let next = index
+                + 1
+                + path.points[index + 1..]
+                    .iter()
+                    .position(|point| *point == line[1])
+                    .unwrap();
+            if next > index + 1 {
+                let (a, b) = (line[0], line[1]);
+                assert_eq!(None, tree.raycast_2d((a.0, a.1), (b.0, b.1)));
+            }
+            index = next;
+        }
+    }
+
+    let short = NavPath {
+        areas: vec![path.areas[0]],
+        points: vec![from, from],
+    };
+    assert_eq!(short, tree.simplify_path(&short, 10.0));
+}
@@ -0,0 +1,190 @@
+use crate::navmesh::{NavArea, NavAreaId, Vector3};
+use crate::path::{reconstruct, Candidate};
+use crate::{NavPath, NavTree};
+use std::collections::{BinaryHeap, HashMap};
+
+/// The cost of reaching an area and the area the straight line to it starts from
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    cost: f32,
+    parent: NavAreaId,
+}
+
+impl NavTree {
+    /// Find a path between two points on the mesh made of straight lines that can be walked
+    ///
+    /// Instead of moving from area to area, the path moves in straight lines between points where
+    /// it has to turn, skipping any areas in between as long as the straight line can be walked
+    /// over the connections between the areas. This gives short paths through open areas without
+    /// a separate smoothing step, but is slower than [`find_path_points`] since every step needs
+    /// to check if a straight line can be walked.
+    ///
+    /// The points of the path are the start and end points and the centers of the areas where the
+    /// path turns. Returns `None` if either point isn't above an area or the end can't be reached
+    /// from the start.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let from = Vector3(150.0, -312.0, 64.0);
+    /// let to = Vector3(1200.0, 200.0, 128.0);
+    /// if let Some(path) = tree.find_path_any_angle(from, to) {
+    ///     println!("the path turns {} times", path.points.len() - 2);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`find_path_points`]: #method.find_path_points
+    pub fn find_path_any_angle(&self, from: Vector3, to: Vector3) -> Option<NavPath> {
+        let start = self.open_area_under(from.0, from.1, from.2)?;
+        let goal = self.open_area_under(to.0, to.1, to.2)?;
+        let from = Vector3(from.0, from.1, start.quad.interpolated_z(from.0, from.1));
+        let to = Vector3(to.0, to.1, goal.quad.interpolated_z(to.0, to.1));
+
+        let position = |area: &NavArea| {
+            if area.id == start.id {
+                from
+            } else if area.id == goal.id {
+                to
+            } else {
+                area.center()
+            }
+        };
+        let can_walk = |from_area: &NavArea, to_area: &NavArea| {
+            let (a, b) = (position(from_area), position(to_area));
+            self.walk_line(from_area, (a.0, a.1), (b.0, b.1), |_| {})
+                .is_ok_and(|end| end.id == to_area.id)
+        };
+
+        let mut open = BinaryHeap::new();
+        let mut nodes: HashMap<NavAreaId, Node> = HashMap::new();
+        nodes.insert(
+            start.id,
+            Node {
+                cost: 0.0,
+                parent: start.id,
+            },
+        );
+        open.push(Candidate {
+            estimate: from.distance(to),
+            cost: 0.0,
+            id: start.id,
+        });
+
+        let mut found = false;
+        while let Some(Candidate { cost, id, .. }) = open.pop() {
+            if id == goal.id {
+                found = true;
+                break;
+            }
+            let node = nodes[&id];
+            if cost > node.cost {
+                continue;
+            }
+            let area = match self.area(id) {
+                Some(area) => area,
+                None => continue,
+            };
+            let parent = self.area(node.parent).unwrap_or(area);
+
+            for (next, _) in self.traversable_neighbors(area) {
+                // skip the current area if the line from it's parent to the next area is clear
+                let (parent, parent_cost) = if parent.id != area.id && can_walk(parent, next) {
+                    (parent, nodes[&parent.id].cost)
+                } else {
+                    (area, cost)
+                };
+                let next_cost = parent_cost + position(parent).distance(position(next));
+                if !nodes
+                    .get(&next.id)
+                    .is_some_and(|existing| next_cost >= existing.cost)
+                {
+                    nodes.insert(
+                        next.id,
+                        Node {
+                            cost: next_cost,
+                            parent: parent.id,
+                        },
+                    );
+                    open.push(Candidate {
+                        estimate: next_cost + position(next).distance(to),
+                        cost: next_cost,
+                        id: next.id,
+                    });
+                }
+            }
+        }
+        if !found {
+            return None;
+        }
+
+        let parents: HashMap<NavAreaId, NavAreaId> = nodes
+            .iter()
+            .filter(|(id, node)| **id != node.parent)
+            .map(|(id, node)| (*id, node.parent))
+            .collect();
+        let turns = reconstruct(&parents, goal.id);
+
+        let mut areas = vec![start.id];
+        let mut points = vec![from];
+        for pair in turns.windows(2) {
+            let (area, next) = (&self[pair[0]], &self[pair[1]]);
+            let (a, b) = (position(area), position(next));
+            let mut walked = Vec::new();
+            let walk = self.walk_line(area, (a.0, a.1), (b.0, b.1), |area| walked.push(area.id));
+            if walk.is_ok_and(|end| end.id == next.id) {
+                areas.extend(walked.into_iter().skip(1));
+            } else {
+                // moves over ladders and links aren't straight lines over the mesh
+                areas.push(next.id);
+            }
+            points.push(b);
+        }
+        if points.len() == 1 {
+            points.push(to);
+        }
+
+        Some(NavPath { areas, points })
+    }
+}
+
+#[test]
+fn test_find_path_any_angle() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let from = tree.nearest_area(-1000.0, -1030.0).unwrap().0.center();
+    let to = tree.nearest_area(1500.0, 200.0).unwrap().0.center();
+    let path = tree.find_path_any_angle(from, to).unwrap();
+    assert_eq!(Some(&from), path.points.first());
+    assert_eq!(Some(&to), path.points.last());
+    assert!(path.length() >= from.distance(to));
+
+    // the path is shorter than going from area center to area center
+    let center_path = tree
+        .find_path(path.areas[0], *path.areas.last().unwrap())
+        .unwrap();
+    let center_length: f32 = center_path
+        .windows(2)
+        .map(|pair| tree[pair[0]].center().distance(tree[pair[1]].center()))
+        .sum::<f32>()
+        + from.distance(tree[center_path[0]].center())
+        + to.distance(tree[*center_path.last().unwrap()].center());
+    assert!(path.length() <= center_length + 0.01);
+
+    // the areas of the path are connected
+    for pair in path.areas.windows(2) {
+        assert!(tree
+            .traversable_neighbors(&tree[pair[0]])
+            .any(|(next, _)| next.id == pair[1]));
+    }
+
+    let single = tree.find_path_any_angle(from, from).unwrap();
+    assert_eq!(vec![from, from], single.points);
+}
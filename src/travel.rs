@@ -0,0 +1,318 @@
+use crate::navmesh::{NavArea, NavAreaId};
+use crate::{EdgeKind, NavAttributeFlags, NavPath, NavTree};
+use std::collections::{HashMap, HashSet};
+
+/// The movement speeds of a player, in hammer units per second
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, MovementProfile, Vector3};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let from = Vector3(150.0, -312.0, 64.0);
+/// let to = Vector3(1200.0, 200.0, 128.0);
+/// if let Some(path) = tree.find_path_points(from, to) {
+///     let seconds = tree.estimate_travel_time(&path, MovementProfile::TF2_SCOUT);
+///     println!("a scout needs {:.1} seconds", seconds);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MovementProfile {
+    /// The speed when running
+    pub run_speed: f32,
+    /// The speed when crouching
+    pub crouch_speed: f32,
+    /// The speed when climbing a ladder
+    pub ladder_speed: f32,
+}
+
+impl MovementProfile {
+    const fn team_fortress(run_speed: f32) -> Self {
+        MovementProfile {
+            run_speed,
+            crouch_speed: run_speed / 3.0,
+            ladder_speed: 200.0,
+        }
+    }
+
+    /// A Team Fortress 2 scout
+    pub const TF2_SCOUT: MovementProfile = MovementProfile::team_fortress(400.0);
+    /// A Team Fortress 2 soldier
+    pub const TF2_SOLDIER: MovementProfile = MovementProfile::team_fortress(240.0);
+    /// A Team Fortress 2 pyro
+    pub const TF2_PYRO: MovementProfile = MovementProfile::team_fortress(300.0);
+    /// A Team Fortress 2 demoman
+    pub const TF2_DEMOMAN: MovementProfile = MovementProfile::team_fortress(280.0);
+    /// A Team Fortress 2 heavy
+    pub const TF2_HEAVY: MovementProfile = MovementProfile::team_fortress(230.0);
+    /// A Team Fortress 2 engineer
+    pub const TF2_ENGINEER: MovementProfile = MovementProfile::team_fortress(300.0);
+    /// A Team Fortress 2 medic
+    pub const TF2_MEDIC: MovementProfile = MovementProfile::team_fortress(320.0);
+    /// A Team Fortress 2 sniper
+    pub const TF2_SNIPER: MovementProfile = MovementProfile::team_fortress(300.0);
+    /// A Team Fortress 2 spy
+    pub const TF2_SPY: MovementProfile = MovementProfile::team_fortress(320.0);
+    /// A Counter-Strike player holding a knife
+    pub const COUNTER_STRIKE: MovementProfile = MovementProfile {
+        run_speed: 250.0,
+        crouch_speed: 85.0,
+        ladder_speed: 200.0,
+    };
+
+    /// The speed when moving through an area
+    pub fn speed_in(&self, area: &NavArea) -> f32 {
+        if area.attributes().contains(NavAttributeFlags::CROUCH) {
+            self.crouch_speed
+        } else {
+            self.run_speed
+        }
+    }
+}
+
+/// Measurements of a path to compare routes by
+///
+/// The length, climb and descent can be measured from the points of a path alone, using
+/// `PathMetrics::from(&path)`. The distance covered in areas with an attribute also needs the
+/// areas of the path, see [`NavTree::path_metrics`].
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::{get_area_tree, NavAttributeFlags, PathMetrics, Vector3};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// let from = Vector3(150.0, -312.0, 64.0);
+/// let to = Vector3(1200.0, 200.0, 128.0);
+/// if let Some(path) = tree.find_path_points(from, to) {
+///     let metrics = PathMetrics::from(&path);
+///     println!("the path climbs {} units", metrics.climb);
+///
+///     let metrics = tree.path_metrics(&path);
+///     let crouched = metrics.distance_with(NavAttributeFlags::CROUCH);
+///     println!("{} of {} units are crouched", crouched, metrics.length);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`NavTree::path_metrics`]: ./struct.NavTree.html#method.path_metrics
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PathMetrics {
+    /// The total length of the path
+    pub length: f32,
+    /// The total height gained along the path
+    pub climb: f32,
+    /// The total height lost along the path
+    pub descent: f32,
+    /// The distance covered in areas with every single attribute flag
+    pub attribute_distances: HashMap<NavAttributeFlags, f32>,
+}
+
+impl PathMetrics {
+    /// The distance covered in areas with an attribute flag
+    pub fn distance_with(&self, flag: NavAttributeFlags) -> f32 {
+        self.attribute_distances.get(&flag).copied().unwrap_or(0.0)
+    }
+}
+
+impl From<&NavPath> for PathMetrics {
+    fn from(path: &NavPath) -> Self {
+        let mut metrics = PathMetrics::default();
+        for segment in path.points.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            metrics.length += from.distance(to);
+            let height = to.2 - from.2;
+            if height > 0.0 {
+                metrics.climb += height;
+            } else {
+                metrics.descent -= height;
+            }
+        }
+        metrics
+    }
+}
+
+impl NavTree {
+    /// Measure a path, including the distance covered in areas with every attribute
+    ///
+    /// Only the parts of the path inside the areas of the path are counted for the attributes.
+    pub fn path_metrics(&self, path: &NavPath) -> PathMetrics {
+        let path_areas: HashSet<NavAreaId> = path.areas.iter().copied().collect();
+        let mut metrics = PathMetrics::from(path);
+
+        for segment in path.points.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            for crossing in self.areas_along((from.0, from.1), (to.0, to.1)) {
+                if !path_areas.contains(&crossing.area.id) {
+                    continue;
+                }
+                let length = crossing.entry.distance(crossing.exit);
+                let flags = crossing.area.flags;
+                for bit in (0..32).map(|bit| 1 << bit).filter(|bit| flags & bit != 0) {
+                    *metrics
+                        .attribute_distances
+                        .entry(NavAttributeFlags(bit))
+                        .or_default() += length;
+                }
+            }
+        }
+
+        metrics
+    }
+
+    /// Estimate the time in seconds it takes to follow a path
+    ///
+    /// Every part of the path is traveled at the speed for the area it goes through, parts of the
+    /// path outside of the areas of the path are traveled at the running speed. Moves between
+    /// areas connected by a ladder add the time to climb the height difference between the areas.
+    pub fn estimate_travel_time(&self, path: &NavPath, profile: MovementProfile) -> f32 {
+        let path_areas: HashSet<NavAreaId> = path.areas.iter().copied().collect();
+
+        let mut time = 0.0;
+        for segment in path.points.windows(2) {
+            let (from, to) = (segment[0], segment[1]);
+            let mut covered = 0.0;
+            for crossing in self.areas_along((from.0, from.1), (to.0, to.1)) {
+                if path_areas.contains(&crossing.area.id) {
+                    let length = crossing.entry.distance(crossing.exit);
+                    covered += length;
+                    time += length / profile.speed_in(crossing.area);
+                }
+            }
+            time += (from.distance(to) - covered).max(0.0) / profile.run_speed;
+        }
+
+        for pair in path.areas.windows(2) {
+            let (area, next) = match (self.area(pair[0]), self.area(pair[1])) {
+                (Some(area), Some(next)) => (area, next),
+                _ => continue,
+            };
+            let kinds: Vec<EdgeKind> = self
+                .traversable_neighbors(area)
+                .filter(|(neighbor, _)| neighbor.id == next.id)
+                .map(|(_, kind)| kind)
+                .collect();
+            // areas without a traversable edge between them, like at off-mesh links, aren't climbed
+            let climb_only = !kinds.is_empty()
+                && kinds
+                    .iter()
+                    .all(|kind| *kind == EdgeKind::LadderUp || *kind == EdgeKind::LadderDown);
+            if climb_only {
+                let climb = (next.center().2 - area.center().2).abs();
+                time += climb / profile.ladder_speed;
+            }
+        }
+
+        time
+    }
+}
+
+#[test]
+fn test_estimate_travel_time() {
+    use crate::Vector3;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let from = tree.nearest_area(-1000.0, -1030.0).unwrap().0.center();
+    let to = tree.nearest_area(1500.0, 200.0).unwrap().0.center();
+    let path = tree.find_path_points(from, to).unwrap();
+
+    let scout = tree.estimate_travel_time(&path, MovementProfile::TF2_SCOUT);
+    let heavy = tree.estimate_travel_time(&path, MovementProfile::TF2_HEAVY);
+    assert!(scout > 0.0);
+    assert!(heavy > scout);
+
+    // without crouch areas or ladders the time is the length at running speed
+    let crouch = path
+        .areas
+        .iter()
+        .any(|id| tree[*id].attributes().contains(NavAttributeFlags::CROUCH));
+    if !crouch {
+        assert!((scout - path.length() / 400.0).abs() < 0.01);
+    }
+
+    let empty = NavPath {
+        areas: vec![path.areas[0]],
+        points: vec![Vector3(0.0, 0.0, 0.0)],
+    };
+    assert_eq!(
+        0.0,
+        tree.estimate_travel_time(&empty, MovementProfile::COUNTER_STRIKE)
+    );
+
+    // consecutive areas that aren't connected are not climbed between
+    let first = &tree[path.areas[0]];
+    let unconnected = tree
+        .areas()
+        .find(|area| {
+            (area.center().2 - first.center().2).abs() > 100.0
+                && tree
+                    .traversable_neighbors(first)
+                    .all(|(next, _)| next.id != area.id)
+        })
+        .unwrap();
+    let jump = NavPath {
+        areas: vec![first.id, unconnected.id],
+        points: vec![Vector3(0.0, 0.0, 0.0)],
+    };
+    assert_eq!(
+        0.0,
+        tree.estimate_travel_time(&jump, MovementProfile::COUNTER_STRIKE)
+    );
+}
+
+#[test]
+fn test_path_metrics() {
+    use crate::Vector3;
+
+    let path = NavPath {
+        areas: vec![],
+        points: vec![
+            Vector3(0.0, 0.0, 0.0),
+            Vector3(3.0, 4.0, 0.0),
+            Vector3(3.0, 4.0, 10.0),
+            Vector3(3.0, 4.0, 4.0),
+        ],
+    };
+    let metrics = PathMetrics::from(&path);
+    assert_eq!(21.0, metrics.length);
+    assert_eq!(10.0, metrics.climb);
+    assert_eq!(6.0, metrics.descent);
+    assert!(metrics.attribute_distances.is_empty());
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+
+    let from = tree.nearest_area(-1000.0, -1030.0).unwrap().0.center();
+    let to = tree.nearest_area(1500.0, 200.0).unwrap().0.center();
+    let path = tree.find_path_points(from, to).unwrap();
+    let metrics = tree.path_metrics(&path);
+    assert!((path.length() - metrics.length).abs() < 0.01);
+    assert!((metrics.climb - metrics.descent - (to.2 - from.2)).abs() < 0.01);
+    for (flag, distance) in &metrics.attribute_distances {
+        assert_eq!(1, flag.0.count_ones());
+        assert!(*distance > 0.0);
+        assert!(*distance <= metrics.length + 0.01);
+        assert!(path
+            .areas
+            .iter()
+            .any(|id| tree[*id].attributes().contains(*flag)));
+    }
+    let crouched = path
+        .areas
+        .iter()
+        .any(|id| tree[*id].attributes().contains(NavAttributeFlags::CROUCH));
+    assert_eq!(
+        crouched,
+        metrics.distance_with(NavAttributeFlags::CROUCH) > 0.0
+    );
+}
@@ -0,0 +1,1842 @@
+use crate::groups::ConnectionGroups;
+use crate::link::{OffMeshLinkId, OffMeshLinks};
+use crate::navmesh::{
+    HammerUnit, LadderDirection, NavArea, NavAreaId, NavDirection, NavLadder, NavLadderId, Vector3,
+};
+use crate::parser::read_mesh_with_hashes;
+use crate::{tree_bounds, NavFlagFilter, NavGame, ParseError, Rect};
+use aabb_quadtree::{ItemId, QuadTree, Spatial};
+use bitbuffer::{BitReadStream, LittleEndian};
+use euclid::{TypedPoint2D, TypedSize2D};
+use std::collections::{HashMap, HashSet};
+use std::ops::Index;
+use thiserror::Error;
+
+/// A tree of all navigation areas, including all area data
+///
+/// Unlike the [`NavQuadTree`], areas can be looked up by their id
+///
+/// [`NavQuadTree`]: ./struct.NavQuadTree.html
+pub struct NavTree {
+    tree: QuadTree<NavArea, HammerUnit, [(ItemId, Rect); 4]>,
+    index: HashMap<NavAreaId, AreaEntry>,
+    ladders: HashMap<NavLadderId, NavLadder>,
+    rect: Rect,
+    extent: (Vector3, Vector3),
+    centers: HashMap<NavAreaId, Vector3>,
+    pub(crate) links: OffMeshLinks,
+    pub(crate) blocked: HashSet<NavAreaId>,
+    pub(crate) groups: ConnectionGroups,
+    game: NavGame,
+}
+
+#[derive(Clone, Copy)]
+struct AreaEntry {
+    item: ItemId,
+    hash: u64,
+}
+
+/// A lightweight reference to an area in a [`NavTree`]
+///
+/// Handles stay valid until the area is removed or replaced, or the tree is refreshed.
+///
+/// [`NavTree`]: ./struct.NavTree.html
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct AreaHandle(ItemId);
+
+/// Errors that can occur when editing the areas of a [`NavTree`]
+///
+/// [`NavTree`]: ./struct.NavTree.html
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum TreeEditError {
+    /// The area handle doesn't refer to an area in the tree
+    #[error("The area handle doesn't refer to an area")]
+    UnknownHandle,
+    /// An area with the id already exists
+    #[error("Area {0} already exists")]
+    DuplicateArea(NavAreaId),
+    /// The area is outside of the bounds of the tree
+    #[error("Area {0} is outside of the bounds of the tree")]
+    OutOfBounds(NavAreaId),
+}
+
+/// The changes made to a tree by [`NavTree::refresh_from`]
+///
+/// [`NavTree::refresh_from`]: ./struct.NavTree.html#method.refresh_from
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub struct RefreshSummary {
+    pub added: usize,
+    pub updated: usize,
+    pub removed: usize,
+    pub unchanged: usize,
+}
+
+/// How an area can be reached from a neighboring area
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum EdgeKind {
+    /// By walking through the shared edge between the areas
+    Walk,
+    /// By climbing up a ladder
+    LadderUp,
+    /// By climbing down a ladder
+    LadderDown,
+    /// By using an off-mesh link
+    OffMesh(OffMeshLinkId),
+}
+
+/// Parse all navigation areas from a nav file into a tree
+///
+/// The game specific data of the areas is read as stored by Team Fortress 2, use
+/// [`get_area_tree_for_game`] for the nav files of other games.
+///
+/// ## Examples
+///
+/// ```no_run
+/// use sourcenav::get_area_tree;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let file = std::fs::read("path/to/navfile.nav")?;
+/// let tree = get_area_tree(file)?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`get_area_tree_for_game`]: ./fn.get_area_tree_for_game.html
+pub fn get_area_tree(data: impl Into<BitReadStream<LittleEndian>>) -> Result<NavTree, ParseError> {
+    get_area_tree_for_game(data, NavGame::TeamFortress2)
+}
+
+/// Parse all navigation areas from a nav file made for a specific game into a tree
+pub fn get_area_tree_for_game(
+    data: impl Into<BitReadStream<LittleEndian>>,
+    game: NavGame,
+) -> Result<NavTree, ParseError> {
+    let (mesh, hashes) = read_mesh_with_hashes(data.into(), game)?;
+
+    Ok(NavTree::build(
+        mesh.areas.into_iter().zip(hashes),
+        mesh.ladders,
+        game,
+    ))
+}
+
+/// Get the minimum and maximum corners of the bounding box of areas
+pub(crate) fn extent<'a>(areas: impl Iterator<Item = &'a NavArea>) -> (Vector3, Vector3) {
+    let mut min = Vector3(f32::INFINITY, f32::INFINITY, f32::INFINITY);
+    let mut max = Vector3(f32::NEG_INFINITY, f32::NEG_INFINITY, f32::NEG_INFINITY);
+    for corner in areas.flat_map(|area| area.quad.corners().to_vec()) {
+        min = Vector3(
+            min.0.min(corner.0),
+            min.1.min(corner.1),
+            min.2.min(corner.2),
+        );
+        max = Vector3(
+            max.0.max(corner.0),
+            max.1.max(corner.1),
+            max.2.max(corner.2),
+        );
+    }
+    if min.0 > max.0 {
+        (Vector3(0.0, 0.0, 0.0), Vector3(0.0, 0.0, 0.0))
+    } else {
+        (min, max)
+    }
+}
+
+impl NavTree {
+    pub(crate) fn build(
+        areas: impl Iterator<Item = (NavArea, u64)>,
+        ladders: Vec<NavLadder>,
+        game: NavGame,
+    ) -> Self {
+        let areas: Vec<_> = areas.collect();
+        let rect = tree_bounds(areas.iter().map(|(area, _)| &area.quad));
+        let extent = extent(areas.iter().map(|(area, _)| area));
+        let centers = areas
+            .iter()
+            .map(|(area, _)| (area.id, area.center()))
+            .collect();
+        let mut tree = QuadTree::default(rect, areas.len());
+        let mut index = HashMap::with_capacity(areas.len());
+
+        for (area, hash) in areas {
+            let id = area.id;
+            if let Some(item) = tree.insert(area) {
+                index.insert(id, AreaEntry { item, hash });
+            }
+        }
+
+        NavTree {
+            tree,
+            index,
+            ladders: ladders
+                .into_iter()
+                .map(|ladder| (ladder.id, ladder))
+                .collect(),
+            rect,
+            extent,
+            centers,
+            links: OffMeshLinks::default(),
+            blocked: HashSet::new(),
+            groups: ConnectionGroups::default(),
+            game,
+        }
+    }
+
+    /// Find the navigation areas at a x/y cooordinate
+    pub fn query(&self, x: f32, y: f32) -> impl Iterator<Item = &NavArea> {
+        let query_box = Rect::new(TypedPoint2D::new(x, y), TypedSize2D::new(1.0, 1.0));
+
+        self.tree
+            .query(query_box)
+            .into_iter()
+            .map(|(area, ..)| area)
+    }
+
+    /// Find the navigation areas containing a x/y coordinate
+    ///
+    /// Unlike [`query`], which matches the bounding boxes of the areas against a small box
+    /// around the point, only areas that contain the point itself, including at their edges,
+    /// are returned.
+    ///
+    /// [`query`]: #method.query
+    pub fn query_strict(&self, x: f32, y: f32) -> impl Iterator<Item = &NavArea> {
+        let query_box = Rect::new(
+            TypedPoint2D::new(x - 1.0, y - 1.0),
+            TypedSize2D::new(2.0, 2.0),
+        );
+
+        self.tree
+            .query(query_box)
+            .into_iter()
+            .map(|(area, ..)| area)
+            .filter(move |area| area.contains(x, y))
+    }
+
+    /// Find the navigation areas at a x/y coordinate that match a flag filter
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, NavAttributeFlags, NavFlagFilter};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let filter = NavFlagFilter::without(NavAttributeFlags::NAV_BLOCKER);
+    /// let open = tree.query_filtered(150.0, -312.0, filter);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_filtered(
+        &self,
+        x: f32,
+        y: f32,
+        filter: NavFlagFilter,
+    ) -> impl Iterator<Item = &NavArea> {
+        self.query(x, y).filter(move |area| filter.matches(area))
+    }
+
+    /// Find all navigation areas overlapping a rectangle that match a flag filter
+    pub fn query_rect_filtered(
+        &self,
+        min: (f32, f32),
+        max: (f32, f32),
+        filter: NavFlagFilter,
+    ) -> impl Iterator<Item = &NavArea> {
+        self.query_rect(min, max)
+            .filter(move |area| filter.matches(area))
+    }
+
+    /// Find all navigation areas within a radius around a x/y coordinate that match a flag filter
+    pub fn query_radius_filtered(
+        &self,
+        x: f32,
+        y: f32,
+        radius: f32,
+        filter: NavFlagFilter,
+    ) -> impl Iterator<Item = &NavArea> {
+        self.query_radius(x, y, radius)
+            .filter(move |area| filter.matches(area))
+    }
+
+    /// Find the navigation areas at a x/y coordinate with a height close to a z coordinate
+    ///
+    /// Only areas where the height at the x/y coordinate differs at most `tolerance` from `z` are
+    /// returned, to find the areas on a single floor.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let areas = tree.query_3d(150.0, -312.0, 64.0, 32.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_3d(
+        &self,
+        x: f32,
+        y: f32,
+        z: f32,
+        tolerance: f32,
+    ) -> impl Iterator<Item = &NavArea> {
+        self.query(x, y)
+            .filter(move |area| (area.quad.interpolated_z(x, y) - z).abs() <= tolerance)
+    }
+
+    /// Find the z-height of a specfic x/y cooordinate
+    ///
+    /// Note that multiple heights might exist for a given x/y coooridnate
+    pub fn find_z_height<'a>(&'a self, x: f32, y: f32) -> impl Iterator<Item = f32> + 'a {
+        self.query(x, y)
+            .map(move |area| area.quad.interpolated_z(x, y))
+    }
+
+    /// Find all navigation areas overlapping a rectangle, given by it's minimum and maximum x/y
+    /// coordinates
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let count = tree.query_rect((-500.0, -500.0), (500.0, 500.0)).count();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_rect(&self, min: (f32, f32), max: (f32, f32)) -> impl Iterator<Item = &NavArea> {
+        let query_box = Rect::new(
+            TypedPoint2D::new(min.0, min.1),
+            TypedSize2D::new(max.0 - min.0, max.1 - min.1),
+        );
+
+        self.tree
+            .query(query_box)
+            .into_iter()
+            .map(|(area, ..)| area)
+    }
+
+    /// Find all navigation areas within a radius around a x/y coordinate
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for area in tree.query_radius(150.0, -312.0, 500.0) {
+    ///     println!("area {} is nearby", area.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_radius(&self, x: f32, y: f32, radius: f32) -> impl Iterator<Item = &NavArea> {
+        // pad the box to include areas touching the edge of the circle
+        let query_box = Rect::new(
+            TypedPoint2D::new(x - radius - 1.0, y - radius - 1.0),
+            TypedSize2D::new(radius * 2.0 + 2.0, radius * 2.0 + 2.0),
+        );
+
+        self.tree
+            .query(query_box)
+            .into_iter()
+            .map(|(area, ..)| area)
+            .filter(move |area| area.quad.distance_2d(x, y) <= radius)
+    }
+
+    /// Find all navigation areas within a distance of a line segment between two x/y coordinates
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for area in tree.query_corridor((150.0, -312.0), (1200.0, -312.0), 64.0) {
+    ///     println!("area {} is along the way", area.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_corridor(
+        &self,
+        from: (f32, f32),
+        to: (f32, f32),
+        radius: f32,
+    ) -> impl Iterator<Item = &NavArea> {
+        self.query_rect(
+            (
+                from.0.min(to.0) - radius - 1.0,
+                from.1.min(to.1) - radius - 1.0,
+            ),
+            (
+                from.0.max(to.0) + radius + 1.0,
+                from.1.max(to.1) + radius + 1.0,
+            ),
+        )
+        .filter(move |area| area.quad.segment_distance_2d(from, to) <= radius)
+    }
+
+    /// Find all navigation areas overlapping a convex polygon, given by it's x/y corners
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let capture_zone = [(-200.0, -400.0), (200.0, -500.0), (300.0, -100.0), (-100.0, 0.0)];
+    /// for area in tree.query_polygon(&capture_zone) {
+    ///     println!("area {} is in the capture zone", area.id);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_polygon<'a>(
+        &'a self,
+        polygon: &'a [(f32, f32)],
+    ) -> impl Iterator<Item = &'a NavArea> + 'a {
+        let (min, max) = polygon.iter().fold(
+            ((f32::MAX, f32::MAX), (f32::MIN, f32::MIN)),
+            |(min, max), point| {
+                (
+                    (min.0.min(point.0), min.1.min(point.1)),
+                    (max.0.max(point.0), max.1.max(point.1)),
+                )
+            },
+        );
+        let (min, max) = if polygon.is_empty() {
+            ((0.0, 0.0), (0.0, 0.0))
+        } else {
+            ((min.0 - 1.0, min.1 - 1.0), (max.0 + 1.0, max.1 + 1.0))
+        };
+
+        self.query_rect(min, max)
+            .filter(move |area| area.quad.intersects_polygon(polygon))
+    }
+
+    /// Find the area closest to a x/y coordinate and the horizontal distance to it's edge
+    ///
+    /// The distance is `0` if the point is inside the area
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// if let Some((area, distance)) = tree.nearest_area(150.0, -312.0) {
+    ///     println!("area {} is {} units away", area.id, distance);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nearest_area(&self, x: f32, y: f32) -> Option<(&NavArea, f32)> {
+        self.nearest_area_within(x, y, self.max_distance(x, y))
+    }
+
+    /// Find the area closest to a x/y coordinate, if it's within a maximum distance
+    pub fn nearest_area_within(
+        &self,
+        x: f32,
+        y: f32,
+        max_distance: f32,
+    ) -> Option<(&NavArea, f32)> {
+        self.nearest_matching(x, y, max_distance, |_| true)
+    }
+
+    /// Find the area closest to a x/y coordinate that matches a predicate and the horizontal
+    /// distance to it's edge
+    ///
+    /// The search area is expanded around the point until a matching area is found, so only the
+    /// areas close to the point are checked when a matching area is nearby.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, NavAttributeFlags};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let cover = tree.nearest_area_where(150.0, -312.0, |area| !area.hiding_spots.is_empty());
+    /// let open = tree.nearest_area_where(150.0, -312.0, |area| {
+    ///     !area.attributes().contains(NavAttributeFlags::NAV_BLOCKER)
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nearest_area_where(
+        &self,
+        x: f32,
+        y: f32,
+        predicate: impl Fn(&NavArea) -> bool,
+    ) -> Option<(&NavArea, f32)> {
+        self.nearest_matching(x, y, self.max_distance(x, y), predicate)
+    }
+
+    /// The distance from a x/y coordinate to the furthest corner of the bounds of the tree
+    ///
+    /// No area can be further away from the coordinate than this
+    pub(crate) fn max_distance(&self, x: f32, y: f32) -> f32 {
+        let (min, max) = self.bounds();
+        let corners = [
+            (min.0, min.1),
+            (max.0, min.1),
+            (min.0, max.1),
+            (max.0, max.1),
+        ];
+        corners
+            .iter()
+            .map(|(cx, cy)| ((cx - x).powi(2) + (cy - y).powi(2)).sqrt())
+            .fold(0.0, f32::max)
+    }
+
+    fn nearest_matching(
+        &self,
+        x: f32,
+        y: f32,
+        max_distance: f32,
+        predicate: impl Fn(&NavArea) -> bool,
+    ) -> Option<(&NavArea, f32)> {
+        let mut radius = 32.0f32.min(max_distance);
+        loop {
+            let query_box = Rect::new(
+                TypedPoint2D::new(x - radius - 1.0, y - radius - 1.0),
+                TypedSize2D::new(radius * 2.0 + 2.0, radius * 2.0 + 2.0),
+            );
+            let nearest = self
+                .tree
+                .query(query_box)
+                .into_iter()
+                .map(|(area, ..)| (area, area.quad.distance_2d(x, y)))
+                .filter(|(_, distance)| *distance <= radius)
+                .filter(|(area, _)| predicate(area))
+                .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+            // an area outside of the circle can still be closer than an area in the corner of
+            // the query box, so only areas inside the circle are considered
+            if nearest.is_some() || radius >= max_distance {
+                return nearest;
+            }
+            radius = (radius * 2.0).min(max_distance);
+        }
+    }
+
+    /// Find the point on the surface of any area closest to a point
+    ///
+    /// Points outside of an area are moved onto the closest edge of the area.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// // a player position in the air
+    /// if let Some(ground) = tree.clamp_to_mesh(150.0, -312.0, 400.0) {
+    ///     println!("the player is above {:?}", ground);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clamp_to_mesh(&self, x: f32, y: f32, z: f32) -> Option<Vector3> {
+        let distance = |point: &Vector3| {
+            ((point.0 - x).powi(2) + (point.1 - y).powi(2) + (point.2 - z).powi(2)).sqrt()
+        };
+
+        // the closest point can't be further away horizontally than the closest point of the
+        // horizontally nearest area
+        let (nearest, _) = self.nearest_area(x, y)?;
+        let candidate = nearest.quad.closest_point(x, y);
+        let max_distance = distance(&candidate);
+
+        let (_, closest) = self
+            .query_radius(x, y, max_distance)
+            .map(|area| area.quad.closest_point(x, y))
+            .map(|point| (distance(&point), point))
+            .fold((max_distance, candidate), |best, next| {
+                if next.0 < best.0 {
+                    next
+                } else {
+                    best
+                }
+            });
+        Some(closest)
+    }
+
+    /// Get an area by it's id
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for area in tree.query(150.0, -312.0) {
+    ///     for connected in area.connections.iter() {
+    ///         let connected = &tree[*connected];
+    ///         println!("area {} is connected to {}", area.id, connected.id);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn area(&self, id: NavAreaId) -> Option<&NavArea> {
+        self.index
+            .get(&id)
+            .and_then(|entry| self.tree.get(entry.item))
+    }
+
+    /// Get the areas connected to an area with the direction of the connection
+    ///
+    /// Connections to areas that don't exist in the tree are skipped
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for area in tree.query(150.0, -312.0) {
+    ///     for (neighbor, direction) in tree.neighbors(area) {
+    ///         println!("area {} is {} of {}", neighbor.id, direction.name(), area.id);
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn neighbors<'a>(
+        &'a self,
+        area: &'a NavArea,
+    ) -> impl Iterator<Item = (&'a NavArea, NavDirection)> + 'a {
+        NavDirection::ALL.iter().flat_map(move |direction| {
+            area.connections[*direction]
+                .iter()
+                .filter_map(move |id| Some((self.area(*id)?, *direction)))
+        })
+    }
+
+    /// Get the shared edge between two connected areas
+    ///
+    /// The ends of the edge are returned as the left and right end as seen when walking from `a`
+    /// to `b`, with the height of `a` at both ends. Returns `None` if either area doesn't exist,
+    /// `a` has no connection to `b` or the areas don't share any part of their edges.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for area in tree.query(150.0, -312.0) {
+    ///     for (neighbor, _) in tree.neighbors(area) {
+    ///         if let Some((left, right)) = tree.portal(area.id, neighbor.id) {
+    ///             println!("area {} opens from {:?} to {:?}", neighbor.id, left, right);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn portal(&self, a: NavAreaId, b: NavAreaId) -> Option<(Vector3, Vector3)> {
+        let from = self.area(a)?;
+        let to = self.area(b)?;
+        let direction = *NavDirection::ALL
+            .iter()
+            .find(|direction| from.connections[**direction].contains(&b))?;
+        from.quad.portal(&to.quad, direction)
+    }
+
+    /// Get all areas that can be reached from an area, by walking or climbing a ladder
+    ///
+    /// Connections and ladders referencing areas or ladders that don't exist in the tree are
+    /// skipped, blocked areas can't be reached and have no traversable neighbors. Connections
+    /// in disabled connection groups are skipped.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, EdgeKind};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for area in tree.query(150.0, -312.0) {
+    ///     for (neighbor, kind) in tree.traversable_neighbors(area) {
+    ///         if kind == EdgeKind::LadderUp {
+    ///             println!("area {} can be reached by ladder", neighbor.id);
+    ///         }
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn traversable_neighbors<'a>(
+        &'a self,
+        area: &'a NavArea,
+    ) -> impl Iterator<Item = (&'a NavArea, EdgeKind)> + 'a {
+        let walk = self
+            .neighbors(area)
+            .map(|(neighbor, _)| (neighbor, EdgeKind::Walk));
+        let climb = LadderDirection::ALL.iter().flat_map(move |direction| {
+            let kind = match direction {
+                LadderDirection::Up => EdgeKind::LadderUp,
+                LadderDirection::Down => EdgeKind::LadderDown,
+            };
+            area.ladder_connections[*direction]
+                .iter()
+                .filter_map(move |id| self.ladder(*id))
+                .flat_map(move |ladder| ladder.areas(*direction))
+                .filter_map(move |id| Some((self.area(id)?, kind)))
+        });
+        let links = self
+            .links
+            .usable_from(area.id)
+            .filter_map(move |(link, id)| Some((self.area(id)?, EdgeKind::OffMesh(link.id))));
+        let blocked = self.is_blocked(area.id);
+        walk.chain(climb).chain(links).filter(move |(next, _)| {
+            !blocked && !self.is_blocked(next.id) && !self.groups.is_disabled(area.id, next.id)
+        })
+    }
+
+    /// Get a ladder by it's id
+    pub fn ladder(&self, id: NavLadderId) -> Option<&NavLadder> {
+        self.ladders.get(&id)
+    }
+
+    /// Get all ladders in the tree
+    pub fn ladders(&self) -> impl Iterator<Item = &NavLadder> {
+        self.ladders.values()
+    }
+
+    /// Get the area at a x/y coordinate with the height closest to a z coordinate
+    pub(crate) fn area_under(&self, x: f32, y: f32, z: f32) -> Option<&NavArea> {
+        self.area_under_where(x, y, z, |_| true)
+    }
+
+    /// Get the unblocked area at a x/y coordinate with the height closest to a z coordinate
+    ///
+    /// Used to find the area a movement starts or ends in
+    pub(crate) fn open_area_under(&self, x: f32, y: f32, z: f32) -> Option<&NavArea> {
+        self.area_under_where(x, y, z, |area| !self.is_blocked(area.id))
+    }
+
+    fn area_under_where(
+        &self,
+        x: f32,
+        y: f32,
+        z: f32,
+        predicate: impl Fn(&NavArea) -> bool,
+    ) -> Option<&NavArea> {
+        let height_difference = |area: &NavArea| (area.quad.interpolated_z(x, y) - z).abs();
+        self.query_strict(x, y)
+            .filter(|area| predicate(area))
+            .min_by(|a, b| {
+                height_difference(a)
+                    .partial_cmp(&height_difference(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+    }
+
+    /// Get the surface normal of the area at a point
+    ///
+    /// The area at the point is the area at the x/y coordinate with the height closest to the z
+    /// coordinate, `None` is returned if there is no area at the x/y coordinate.
+    pub fn normal_at(&self, x: f32, y: f32, z: f32) -> Option<Vector3> {
+        Some(self.area_under(x, y, z)?.normal_at(x, y))
+    }
+
+    /// Get the slope of the area at a point in degrees
+    ///
+    /// The area at the point is the area at the x/y coordinate with the height closest to the z
+    /// coordinate, `None` is returned if there is no area at the x/y coordinate.
+    pub fn slope_at(&self, x: f32, y: f32, z: f32) -> Option<f32> {
+        Some(self.area_under(x, y, z)?.slope_at(x, y))
+    }
+
+    /// Find all areas that can be reached from the area at a point
+    ///
+    /// The area at the point is the area at the x/y coordinate with the height closest to the z
+    /// coordinate. Areas are reached through their connections and, if `ladders` is set, by
+    /// climbing the ladders connected to the areas. The set is empty if there is no area at the
+    /// point.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let region = tree.region_at(150.0, -312.0, 64.0, true);
+    /// if region.len() < tree.len() {
+    ///     println!("not all areas can be reached from this point");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn region_at(&self, x: f32, y: f32, z: f32, ladders: bool) -> HashSet<NavAreaId> {
+        let mut region = HashSet::new();
+        let mut queue: Vec<&NavArea> = self.open_area_under(x, y, z).into_iter().collect();
+        region.extend(queue.iter().map(|area| area.id));
+
+        while let Some(area) = queue.pop() {
+            for (next, kind) in self.traversable_neighbors(area) {
+                if (ladders || kind == EdgeKind::Walk) && region.insert(next.id) {
+                    queue.push(next);
+                }
+            }
+        }
+
+        region
+    }
+
+    /// Get all navigation areas in the tree
+    pub fn areas(&self) -> impl Iterator<Item = &NavArea> {
+        self.tree.iter().map(|(_, (area, _))| area)
+    }
+
+    /// Get all areas in the tree with the hash of their binary data
+    #[cfg_attr(not(feature = "cache"), allow(dead_code))]
+    pub(crate) fn areas_with_hashes(&self) -> impl Iterator<Item = (&NavArea, u64)> {
+        let index = &self.index;
+        self.areas().map(move |area| (area, index[&area.id].hash))
+    }
+
+    /// Get the centers of all areas in the tree by their id
+    ///
+    /// The centers are calculated when building the tree and kept up to date when the tree is
+    /// changed.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// for (id, center) in tree.centers() {
+    ///     println!("area {} is centered at {:?}", id, center);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn centers(&self) -> &HashMap<NavAreaId, Vector3> {
+        &self.centers
+    }
+
+    /// Recalculate the bounds and centers of the areas after the areas changed
+    fn update_cached(&mut self) {
+        self.extent = extent(self.areas());
+        let centers = self.areas().map(|area| (area.id, area.center())).collect();
+        self.centers = centers;
+    }
+
+    /// Add an area that was inserted into the tree to the bounds and centers
+    fn cache_area(&mut self, id: NavAreaId, bounds: (Vector3, Vector3), center: Vector3) {
+        self.centers.insert(id, center);
+        if self.centers.len() == 1 {
+            self.extent = bounds;
+        } else {
+            let (min, max) = self.extent;
+            self.extent = (
+                Vector3(
+                    min.0.min(bounds.0 .0),
+                    min.1.min(bounds.0 .1),
+                    min.2.min(bounds.0 .2),
+                ),
+                Vector3(
+                    max.0.max(bounds.1 .0),
+                    max.1.max(bounds.1 .1),
+                    max.2.max(bounds.1 .2),
+                ),
+            );
+        }
+    }
+
+    /// Remove an area that was removed from the tree from the bounds and centers
+    fn uncache_area(&mut self, area: &NavArea) {
+        self.centers.remove(&area.id);
+        // the bounds can only shrink if the area was touching them
+        let (min, max) = self.extent;
+        let (area_min, area_max) = extent(std::iter::once(area));
+        if area_min.0 <= min.0
+            || area_min.1 <= min.1
+            || area_min.2 <= min.2
+            || area_max.0 >= max.0
+            || area_max.1 >= max.1
+            || area_max.2 >= max.2
+        {
+            self.extent = extent(self.areas());
+        }
+    }
+
+    /// Get all navigation areas in the tree with their handle
+    pub fn areas_with_handles(&self) -> impl Iterator<Item = (AreaHandle, &NavArea)> {
+        self.tree
+            .iter()
+            .map(|(item, (area, _))| (AreaHandle(*item), area))
+    }
+
+    /// Find the navigation areas at a x/y coordinate with their handle
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mut tree = get_area_tree(file)?;
+    /// let handles: Vec<_> = tree
+    ///     .query_handles(150.0, -312.0)
+    ///     .map(|(handle, _)| handle)
+    ///     .collect();
+    /// for handle in handles {
+    ///     tree.remove(handle);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn query_handles(&self, x: f32, y: f32) -> impl Iterator<Item = (AreaHandle, &NavArea)> {
+        let query_box = Rect::new(TypedPoint2D::new(x, y), TypedSize2D::new(1.0, 1.0));
+
+        self.tree
+            .query(query_box)
+            .into_iter()
+            .map(|(area, _, item)| (AreaHandle(item), area))
+    }
+
+    /// Get the handle of an area by it's id
+    pub fn handle(&self, id: NavAreaId) -> Option<AreaHandle> {
+        self.index.get(&id).map(|entry| AreaHandle(entry.item))
+    }
+
+    /// Get an area by it's handle
+    pub fn get(&self, handle: AreaHandle) -> Option<&NavArea> {
+        self.tree.get(handle.0)
+    }
+
+    /// Remove an area from the tree
+    ///
+    /// Returns `None` if the handle doesn't refer to an area in the tree
+    pub fn remove(&mut self, handle: AreaHandle) -> Option<NavArea> {
+        let (area, _) = self.tree.remove(handle.0)?;
+        self.index.remove(&area.id);
+        self.uncache_area(&area);
+        Some(area)
+    }
+
+    /// Replace an area in the tree, returning the handle of the new area
+    ///
+    /// The new area doesn't need to have the same id as the area it replaces. Fails without
+    /// changing the tree if the handle doesn't refer to an area in the tree, another area in the
+    /// tree already has the id of the new area, or the new area is outside of the bounds of the
+    /// tree.
+    pub fn replace(
+        &mut self,
+        handle: AreaHandle,
+        area: NavArea,
+    ) -> Result<AreaHandle, TreeEditError> {
+        let old_id = self.get(handle).ok_or(TreeEditError::UnknownHandle)?.id;
+        if area.id != old_id && self.index.contains_key(&area.id) {
+            return Err(TreeEditError::DuplicateArea(area.id));
+        }
+        if !self.rect.contains_rect(&area.aabb()) {
+            return Err(TreeEditError::OutOfBounds(area.id));
+        }
+
+        let id = area.id;
+        let bounds = extent(std::iter::once(&area));
+        let center = area.center();
+        let item = self
+            .tree
+            .insert(area)
+            .ok_or(TreeEditError::OutOfBounds(id))?;
+        if let Some((old, _)) = self.tree.remove(handle.0) {
+            self.uncache_area(&old);
+        }
+        self.index.remove(&old_id);
+        // without the binary data of the area, the area is always updated by the next refresh
+        self.index.insert(id, AreaEntry { item, hash: 0 });
+        self.cache_area(id, bounds, center);
+        Ok(AreaHandle(item))
+    }
+
+    /// The total horizontal area covered by all areas in square hammer units
+    ///
+    /// Overlapping areas, like areas on different floors, are counted separately
+    pub fn total_area(&self) -> f32 {
+        self.areas()
+            .map(|area| area.quad.width() * area.quad.height())
+            .sum()
+    }
+
+    /// The total area of the sloped surfaces of all areas in square hammer units
+    pub fn total_surface_area(&self) -> f32 {
+        self.areas().map(|area| area.quad.surface_area()).sum()
+    }
+
+    /// The minimum and maximum corners of the bounding box of all areas
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let (min, max) = tree.bounds();
+    /// println!("the map is {} units wide", max.0 - min.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bounds(&self) -> (Vector3, Vector3) {
+        self.extent
+    }
+
+    /// The game the nav file was parsed for
+    pub fn game(&self) -> NavGame {
+        self.game
+    }
+
+    /// The number of areas in the tree
+    pub fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Whether the tree contains no areas
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    /// Update the tree from a changed nav file
+    ///
+    /// Only areas that have been added, removed or changed since the tree was build are updated,
+    /// areas are matched by their id and compared by the hash of their binary data.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::get_area_tree;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mut tree = get_area_tree(file)?;
+    ///
+    /// // the nav file is edited
+    ///
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let summary = tree.refresh_from(file)?;
+    /// println!("{} areas changed", summary.updated);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn refresh_from(
+        &mut self,
+        data: impl Into<BitReadStream<LittleEndian>>,
+    ) -> Result<RefreshSummary, ParseError> {
+        let (mesh, hashes) = read_mesh_with_hashes(data.into(), self.game)?;
+        let mut summary = RefreshSummary::default();
+        let mut changed = Vec::new();
+
+        for (index, (area, hash)) in mesh.areas.iter().zip(hashes.iter()).enumerate() {
+            match self.index.get(&area.id) {
+                Some(entry) if entry.hash == *hash => summary.unchanged += 1,
+                Some(_) => {
+                    summary.updated += 1;
+                    changed.push(index);
+                }
+                None => {
+                    summary.added += 1;
+                    changed.push(index);
+                }
+            }
+        }
+
+        let seen: HashSet<NavAreaId> = mesh.areas.iter().map(|area| area.id).collect();
+        let removed: Vec<NavAreaId> = self
+            .index
+            .keys()
+            .filter(|id| !seen.contains(id))
+            .copied()
+            .collect();
+        summary.removed = removed.len();
+
+        // areas outside of the bounds of the current tree require the tree to be rebuild
+        let fits = changed
+            .iter()
+            .all(|index| self.rect.contains_rect(&mesh.areas[*index].aabb()));
+        if !fits {
+            let mut tree =
+                NavTree::build(mesh.areas.into_iter().zip(hashes), mesh.ladders, self.game);
+            // blocked areas, links and groups aren't part of the nav file and are kept by the
+            // rebuild
+            tree.blocked = std::mem::take(&mut self.blocked);
+            tree.links = std::mem::take(&mut self.links);
+            tree.groups = std::mem::take(&mut self.groups);
+            *self = tree;
+            self.forget_areas(&removed);
+            return Ok(summary);
+        }
+
+        self.ladders = mesh
+            .ladders
+            .into_iter()
+            .map(|ladder| (ladder.id, ladder))
+            .collect();
+
+        for id in &removed {
+            if let Some(entry) = self.index.remove(id) {
+                self.tree.remove(entry.item);
+            }
+        }
+        self.forget_areas(&removed);
+
+        let changed: HashSet<usize> = changed.into_iter().collect();
+        for (index, (area, hash)) in mesh.areas.into_iter().zip(hashes).enumerate() {
+            if !changed.contains(&index) {
+                continue;
+            }
+            let id = area.id;
+            if let Some(entry) = self.index.remove(&id) {
+                self.tree.remove(entry.item);
+            }
+            if let Some(item) = self.tree.insert(area) {
+                self.index.insert(id, AreaEntry { item, hash });
+            }
+        }
+
+        self.update_cached();
+
+        Ok(summary)
+    }
+
+    /// Drop the state kept for areas that were removed by a refresh
+    fn forget_areas(&mut self, removed: &[NavAreaId]) {
+        if removed.is_empty() {
+            return;
+        }
+        let removed: HashSet<NavAreaId> = removed.iter().copied().collect();
+        self.blocked.retain(|id| !removed.contains(id));
+        self.links.remove_areas(&removed);
+        self.groups.remove_areas(&removed);
+    }
+}
+
+impl Index<NavAreaId> for NavTree {
+    type Output = NavArea;
+
+    /// Get an area by it's id
+    ///
+    /// ## Panics
+    ///
+    /// Panics if no area with the id exists in the tree
+    fn index(&self, id: NavAreaId) -> &NavArea {
+        self.area(id)
+            .expect("no area with the id exists in the tree")
+    }
+}
+
+#[test]
+fn test_refresh() {
+    use bitbuffer::BitReadBuffer;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = get_area_tree(file.clone()).unwrap();
+    assert_eq!(1930, tree.len());
+
+    let summary = tree.refresh_from(file.clone()).unwrap();
+    assert_eq!(
+        RefreshSummary {
+            unchanged: 1930,
+            ..RefreshSummary::default()
+        },
+        summary
+    );
+
+    // change the height of the first area
+    let mut changed = file;
+    let mesh = crate::read_mesh(changed.clone()).unwrap();
+    let first_id = mesh.areas[0].id;
+    let header_length = 4 + 4 + 4 + 4 + 1 + 2 + 1 + 4;
+    let z_offset = header_length + 4 + 4 + 8;
+    changed[z_offset..z_offset + 4].copy_from_slice(&1000.0f32.to_le_bytes());
+
+    let data = BitReadStream::new(BitReadBuffer::new(changed, LittleEndian));
+    let summary = tree.refresh_from(data).unwrap();
+    assert_eq!(1, summary.updated);
+    assert_eq!(1929, summary.unchanged);
+    assert_eq!(1930, tree.len());
+
+    let area = tree.area(first_id).unwrap();
+    assert_eq!(1000.0, area.quad.north_west.2);
+}
+
+#[test]
+fn test_refresh_rebuild_keeps_state() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = get_area_tree(file.clone()).unwrap();
+    let mut mesh = crate::read_mesh(file).unwrap();
+
+    let blocked = mesh.areas[1].id;
+    let removed = mesh.areas[2].id;
+    let (from, to) = (mesh.areas[3].id, mesh.areas[4].id);
+    tree.set_blocked(blocked, true);
+    tree.set_blocked(removed, true);
+    let link = tree.add_area_link(from, to, 1.0, false).unwrap();
+    let dropped = tree.add_area_link(from, removed, 1.0, false).unwrap();
+    tree.add_connection_group("door", vec![(from, to), (from, removed)]);
+    tree.set_group_enabled("door", false);
+
+    // moving an area outside of the bounds of the tree forces a rebuild
+    let (_, max) = tree.bounds();
+    let moved = &mut mesh.areas[0].quad;
+    let width = moved.width();
+    moved.north_west.0 = max.0 + 1000.0;
+    moved.south_east.0 = max.0 + 1000.0 + width;
+    mesh.areas.remove(2);
+
+    let summary = tree
+        .refresh_from(mesh.to_bytes(mesh.major_version).unwrap())
+        .unwrap();
+    assert_eq!(1, summary.updated);
+    assert_eq!(1, summary.removed);
+    assert_eq!(1929, tree.len());
+    let (_, new_max) = tree.bounds();
+    assert!(new_max.0 > max.0);
+
+    assert!(tree.is_blocked(blocked));
+    assert!(!tree.is_blocked(removed));
+    assert_eq!(vec![blocked], tree.blocked_areas().collect::<Vec<_>>());
+    assert!(tree.link(dropped).is_none());
+    assert_eq!(Some(false), tree.is_group_enabled("door"));
+    assert!(tree.groups.is_disabled(to, from));
+    assert!(!tree.groups.is_disabled(from, removed));
+    assert_eq!(1, tree.groups.connections("door").unwrap().len());
+    let link = tree.link(link).unwrap();
+    assert_eq!((from, to), (link.from, link.to));
+}
+
+#[test]
+fn test_nearest_area() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let area = tree.areas().next().unwrap();
+    let center = area.quad.center();
+    let (nearest, distance) = tree.nearest_area(center.0, center.1).unwrap();
+    assert_eq!(0.0, distance);
+    assert!(nearest.quad.distance_2d(center.0, center.1) == 0.0);
+
+    // far outside of the map
+    let (_, max) = tree.bounds();
+    let (x, y) = (max.0 + 1000.0, max.1 + 1000.0);
+    let (nearest, distance) = tree.nearest_area(x, y).unwrap();
+    let closest = tree
+        .areas()
+        .map(|area| area.quad.distance_2d(x, y))
+        .fold(f32::INFINITY, f32::min);
+    assert_eq!(closest, distance);
+    assert_eq!(closest, nearest.quad.distance_2d(x, y));
+
+    assert!(tree.nearest_area_within(x, y, 100.0).is_none());
+}
+
+#[test]
+fn test_query_radius() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let center = tree.areas().next().unwrap().quad.center();
+    let mut found: Vec<_> = tree
+        .query_radius(center.0, center.1, 500.0)
+        .map(|area| area.id)
+        .collect();
+    let mut expected: Vec<_> = tree
+        .areas()
+        .filter(|area| area.quad.distance_2d(center.0, center.1) <= 500.0)
+        .map(|area| area.id)
+        .collect();
+    found.sort();
+    expected.sort();
+    assert!(expected.len() > 1);
+    assert_eq!(expected, found);
+}
+
+#[test]
+fn test_query_rect() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let (min, max) = ((-1000.0, -1000.0), (1000.0, 500.0));
+    let mut found: Vec<_> = tree.query_rect(min, max).map(|area| area.id).collect();
+    let mut expected: Vec<_> = tree
+        .areas()
+        .filter(|area| {
+            area.quad.north_west.0 < max.0
+                && area.quad.south_east.0 > min.0
+                && area.quad.north_west.1 < max.1
+                && area.quad.south_east.1 > min.1
+        })
+        .map(|area| area.id)
+        .collect();
+    found.sort();
+    expected.sort();
+    assert!(expected.len() > 1);
+    assert_eq!(expected, found);
+}
+
+#[test]
+fn test_query_3d() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    // find a point with areas on multiple floors
+    let (x, y, areas) = tree
+        .areas()
+        .map(|area| {
+            let center = area.quad.center();
+            let areas: Vec<_> = tree.query(center.0, center.1).collect();
+            (center.0, center.1, areas)
+        })
+        .find(|(x, y, areas)| {
+            areas.len() > 1 && {
+                let heights: Vec<_> = areas
+                    .iter()
+                    .map(|area| area.quad.interpolated_z(*x, *y))
+                    .collect();
+                heights
+                    .iter()
+                    .any(|a| heights.iter().any(|b| a - b > 100.0))
+            }
+        })
+        .unwrap();
+
+    let z = areas[0].quad.interpolated_z(x, y);
+    let found: Vec<_> = tree.query_3d(x, y, z, 32.0).collect();
+    assert!(found.iter().any(|area| area.id == areas[0].id));
+    assert!(found.len() < areas.len());
+}
+
+#[test]
+fn test_area_lookup() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    for area in tree.areas() {
+        assert_eq!(area.id, tree[area.id].id);
+        for connected in area.connections.iter() {
+            assert_eq!(*connected, tree.area(*connected).unwrap().id);
+        }
+    }
+    assert!(tree.area(0u32.into()).is_none());
+}
+
+#[test]
+fn test_query_corridor() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let from = tree.areas().next().unwrap().quad.center();
+    let (from, to) = ((from.0, from.1), (from.0 + 1500.0, from.1 + 500.0));
+    let mut found: Vec<_> = tree
+        .query_corridor(from, to, 100.0)
+        .map(|area| area.id)
+        .collect();
+    found.sort();
+
+    // sample points along the corridor
+    let mut sampled: Vec<_> = (0..=100)
+        .flat_map(|step| {
+            let t = step as f32 / 100.0;
+            let (x, y) = (from.0 + (to.0 - from.0) * t, from.1 + (to.1 - from.1) * t);
+            tree.query_radius(x, y, 100.0)
+                .map(|area| area.id)
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    sampled.sort();
+    sampled.dedup();
+
+    assert!(found.len() > 1);
+    assert!(sampled.iter().all(|id| found.contains(id)));
+    // the line itself is included
+    assert!(tree
+        .query_corridor(from, to, 0.0)
+        .all(|area| found.contains(&area.id)));
+}
+
+#[test]
+fn test_clamp_to_mesh() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let area = tree.areas().next().unwrap();
+    let center = area.quad.center();
+
+    // a point in the air above an area ends up on the area
+    let clamped = tree
+        .clamp_to_mesh(center.0, center.1, center.2 + 20.0)
+        .unwrap();
+    assert_eq!((center.0, center.1), (clamped.0, clamped.1));
+    assert!(tree.query(clamped.0, clamped.1).any(|area| (area
+        .quad
+        .interpolated_z(clamped.0, clamped.1)
+        - clamped.2)
+        .abs()
+        < 0.01));
+
+    // a point outside of the map is moved onto an edge
+    let (x, y) = (tree.bounds().1 .0 + 500.0, center.1);
+    let clamped = tree.clamp_to_mesh(x, y, center.2).unwrap();
+    assert!(clamped.0 < x);
+    assert!(tree.nearest_area(clamped.0, clamped.1).unwrap().1 == 0.0);
+}
+
+#[test]
+fn test_region_at() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let center = tree.areas().next().unwrap().quad.center();
+    let region = tree.region_at(center.0, center.1, center.2, true);
+    assert!(region.len() > 1);
+    for id in &region {
+        assert!(tree[*id]
+            .connections
+            .iter()
+            .all(|connected| region.contains(connected)));
+    }
+
+    assert!(tree.region_at(-100_000.0, 0.0, 0.0, true).is_empty());
+}
+
+#[test]
+fn test_query_filtered() {
+    use crate::NavAttributeFlags;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let flagged = tree.areas().find(|area| area.flags != 0).unwrap();
+    let flags = flagged.attributes();
+    let center = flagged.quad.center();
+
+    assert!(tree
+        .query_filtered(center.0, center.1, NavFlagFilter::with(flags))
+        .any(|area| area.id == flagged.id));
+    assert!(tree
+        .query_filtered(center.0, center.1, NavFlagFilter::without(flags))
+        .all(|area| area.id != flagged.id));
+
+    let (min, max) = tree.bounds();
+    let bounds = ((min.0, min.1), (max.0, max.1));
+    let with_flags = tree
+        .query_rect_filtered(bounds.0, bounds.1, NavFlagFilter::with(flags))
+        .count();
+    let without_flags = tree
+        .query_rect_filtered(bounds.0, bounds.1, NavFlagFilter::without(flags))
+        .count();
+    assert_eq!(
+        tree.areas()
+            .filter(|area| area.attributes().contains(flags))
+            .count(),
+        with_flags
+    );
+    assert!(with_flags + without_flags <= tree.len());
+    assert_eq!(
+        tree.len(),
+        tree.query_radius_filtered(center.0, center.1, 100_000.0, NavFlagFilter::default())
+            .count()
+    );
+    assert!(NavAttributeFlags::NONE.is_empty());
+}
+
+#[test]
+fn test_neighbors() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    for area in tree.areas() {
+        let neighbors: Vec<_> = tree.neighbors(area).collect();
+        assert_eq!(area.connections.iter().count(), neighbors.len());
+        for (neighbor, direction) in neighbors {
+            assert!(area.connections[direction].contains(&neighbor.id));
+        }
+    }
+}
+
+#[test]
+fn test_traversable_neighbors() {
+    use crate::{LadderConnections, NavLadder, NavMeshBuilder, NavQuad};
+
+    let mut builder = NavMeshBuilder::new(NavGame::Unknown);
+    let quad = |z: f32| NavQuad {
+        north_west: Vector3(0.0, 0.0, z),
+        south_east: Vector3(100.0, 100.0, z),
+        north_east_z: z,
+        south_west_z: z,
+    };
+    let bottom = builder.add_area(quad(0.0)).unwrap();
+    let top = builder.add_area(quad(200.0)).unwrap();
+    let mut mesh = builder.build();
+
+    mesh.ladders.push(NavLadder {
+        id: 1.into(),
+        width: 20.0,
+        top: Vector3(50.0, 0.0, 200.0),
+        bottom: Vector3(50.0, 0.0, 0.0),
+        length: 200.0,
+        direction: NavDirection::North,
+        is_dangling: false,
+        top_forward_area: Some(top),
+        top_left_area: None,
+        top_right_area: None,
+        top_behind_area: None,
+        bottom_area: Some(bottom),
+    });
+    let mut up = LadderConnections::default();
+    up.0[LadderDirection::Up as usize].push(1.into());
+    mesh.areas[0].ladder_connections = up;
+    let mut down = LadderConnections::default();
+    down.0[LadderDirection::Down as usize].push(1.into());
+    mesh.areas[1].ladder_connections = down;
+
+    let tree = NavTree::build(
+        mesh.areas.into_iter().map(|area| (area, 0)),
+        mesh.ladders,
+        NavGame::Unknown,
+    );
+    let neighbors: Vec<_> = tree
+        .traversable_neighbors(&tree[bottom])
+        .map(|(area, kind)| (area.id, kind))
+        .collect();
+    assert_eq!(vec![(top, EdgeKind::LadderUp)], neighbors);
+    let neighbors: Vec<_> = tree
+        .traversable_neighbors(&tree[top])
+        .map(|(area, kind)| (area.id, kind))
+        .collect();
+    assert_eq!(vec![(bottom, EdgeKind::LadderDown)], neighbors);
+
+    assert_eq!(2, tree.region_at(50.0, 50.0, 0.0, true).len());
+    assert_eq!(1, tree.region_at(50.0, 50.0, 0.0, false).len());
+}
+
+#[test]
+fn test_total_area() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let area = tree.total_area();
+    let surface_area = tree.total_surface_area();
+    assert!(area > 0.0);
+    // slopes have a larger surface than the area they cover
+    assert!(surface_area > area);
+    assert!(surface_area < area * 1.5);
+}
+
+#[test]
+fn test_bounds() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let (min, max) = tree.bounds();
+    for corner in tree.areas().flat_map(|area| area.quad.corners().to_vec()) {
+        assert!(corner.0 >= min.0 && corner.0 <= max.0);
+        assert!(corner.1 >= min.1 && corner.1 <= max.1);
+        assert!(corner.2 >= min.2 && corner.2 <= max.2);
+    }
+    assert!(tree.areas().any(|area| area.quad.north_west.0 == min.0));
+}
+
+#[test]
+fn test_query_polygon() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    // a rectangle matches the rectangle query, including areas touching the edge
+    let square = [
+        (-500.0, -500.0),
+        (500.0, -500.0),
+        (500.0, 500.0),
+        (-500.0, 500.0),
+    ];
+    let mut rect: Vec<_> = tree
+        .query_rect((-501.0, -501.0), (501.0, 501.0))
+        .filter(|area| {
+            area.quad.south_east.0 >= -500.0
+                && area.quad.north_west.0 <= 500.0
+                && area.quad.south_east.1 >= -500.0
+                && area.quad.north_west.1 <= 500.0
+        })
+        .map(|area| area.id)
+        .collect();
+    let mut polygon: Vec<_> = tree.query_polygon(&square).map(|area| area.id).collect();
+    rect.sort();
+    polygon.sort();
+    assert!(!polygon.is_empty());
+    assert_eq!(rect, polygon);
+
+    // a triangle only contains part of it's bounding box
+    let triangle = [(-1000.0, -1000.0), (1000.0, -1000.0), (-1000.0, 1000.0)];
+    let in_triangle: Vec<_> = tree.query_polygon(&triangle).collect();
+    let in_box = tree
+        .query_rect((-1000.0, -1000.0), (1000.0, 1000.0))
+        .count();
+    assert!(!in_triangle.is_empty());
+    assert!(in_triangle.len() < in_box);
+    for area in in_triangle {
+        // the north west corner of any overlapping area lies on the inside of the diagonal
+        assert!(area.quad.north_west.0 + area.quad.north_west.1 <= 0.0);
+    }
+
+    assert_eq!(0, tree.query_polygon(&[]).count());
+}
+
+#[test]
+fn test_portal() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let mut portals = 0;
+    for area in tree.areas() {
+        for (neighbor, direction) in tree.neighbors(area) {
+            let (left, right) = match tree.portal(area.id, neighbor.id) {
+                Some(portal) => portal,
+                None => continue,
+            };
+            portals += 1;
+
+            // connected areas don't always touch, the portal is on the edge of the first area
+            for end in &[left, right] {
+                assert!(area.quad.distance_2d(end.0, end.1) < 0.01);
+                assert!((area.quad.interpolated_z(end.0, end.1) - end.2).abs() < 0.01);
+            }
+
+            // the left end is on the left when moving into the neighbor
+            let center = area.quad.center();
+            let forward = match direction {
+                NavDirection::North => (0.0, -1.0),
+                NavDirection::East => (1.0, 0.0),
+                NavDirection::South => (0.0, 1.0),
+                NavDirection::West => (-1.0, 0.0),
+            };
+            let side = |point: Vector3| {
+                forward.0 * (point.1 - center.1) - forward.1 * (point.0 - center.0)
+            };
+            assert!(side(left) >= side(right));
+        }
+    }
+    assert!(portals > tree.len());
+
+    let area = tree.areas().next().unwrap();
+    assert_eq!(None, tree.portal(area.id, area.id));
+}
+
+#[test]
+fn test_query_strict() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    for area in tree.areas().take(200) {
+        let quad = &area.quad;
+        let center = quad.center();
+        assert!(area.contains(center.0, center.1));
+        assert!(tree
+            .query_strict(center.0, center.1)
+            .any(|found| found.id == area.id));
+
+        // points just outside of the south east corner are not in the area
+        let (x, y) = (quad.south_east.0 + 0.5, quad.south_east.1 + 0.5);
+        assert!(!area.contains(x, y));
+        assert!(tree.query_strict(x, y).all(|found| found.contains(x, y)));
+        assert!(tree.query_strict(x, y).all(|found| found.id != area.id));
+
+        // points on the edge are included
+        let (x, y) = (quad.south_east.0, quad.south_east.1);
+        assert!(tree.query_strict(x, y).any(|found| found.id == area.id));
+    }
+}
+
+#[test]
+fn test_handles() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = get_area_tree(file).unwrap();
+
+    for (handle, area) in tree.areas_with_handles() {
+        assert_eq!(Some(handle), tree.handle(area.id));
+        assert_eq!(area.id, tree.get(handle).unwrap().id);
+    }
+
+    let (handle, area) = tree.query_handles(320.0, -1030.0).next().unwrap();
+    let id = area.id;
+    let mut replacement = area.clone();
+    replacement.flags = 0x4;
+
+    let new_handle = tree.replace(handle, replacement).unwrap();
+    assert_eq!(None, tree.get(handle));
+    assert_eq!(0x4, tree.get(new_handle).unwrap().flags);
+    assert_eq!(0x4, tree.area(id).unwrap().flags);
+    assert_eq!(1930, tree.len());
+
+    let mut outside = tree.area(id).unwrap().clone();
+    outside.quad.north_west.0 = -100_000.0;
+    assert!(matches!(
+        tree.replace(new_handle, outside),
+        Err(TreeEditError::OutOfBounds(_))
+    ));
+    assert_eq!(0x4, tree.area(id).unwrap().flags);
+
+    // the id of another area can't be taken over
+    let other = tree.areas().find(|area| area.id != id).unwrap().id;
+    let mut duplicate = tree.area(id).unwrap().clone();
+    duplicate.id = other;
+    assert!(matches!(
+        tree.replace(new_handle, duplicate),
+        Err(TreeEditError::DuplicateArea(dup)) if dup == other
+    ));
+    assert_eq!(0x4, tree.area(id).unwrap().flags);
+    assert!(tree.area(other).is_some());
+    assert_eq!(1930, tree.len());
+
+    let removed = tree.remove(new_handle).unwrap();
+    assert_eq!(id, removed.id);
+    assert_eq!(None, tree.area(id));
+    assert_eq!(None, tree.handle(id));
+    assert_eq!(None, tree.remove(new_handle));
+    assert_eq!(1929, tree.len());
+    assert!(!tree.centers().contains_key(&id));
+
+    // removing the area at the edge of the map shrinks the bounds
+    let (min, _) = tree.bounds();
+    let (handle, edge) = tree
+        .areas_with_handles()
+        .find(|(_, area)| area.quad.north_west.0 == min.0)
+        .unwrap();
+    let edge_id = edge.id;
+    let mut moved = edge.clone();
+    moved.quad.north_west.0 += 1.0;
+    let handle = tree.replace(handle, moved).unwrap();
+    assert_eq!(extent(tree.areas()), tree.bounds());
+    assert_eq!(
+        tree.area(edge_id).unwrap().center(),
+        tree.centers()[&edge_id]
+    );
+
+    tree.remove(handle);
+    assert_eq!(extent(tree.areas()), tree.bounds());
+    assert_eq!(tree.len(), tree.centers().len());
+}
+
+#[test]
+fn test_nearest_area_where() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+    let (x, y) = (320.0, -1030.0);
+
+    let with_spots = |area: &NavArea| area.hiding_spots.len() > 2;
+    let (found, distance) = tree.nearest_area_where(x, y, with_spots).unwrap();
+    assert!(with_spots(found));
+    let expected = tree
+        .areas()
+        .filter(|area| with_spots(area))
+        .map(|area| area.quad.distance_2d(x, y))
+        .fold(f32::INFINITY, f32::min);
+    assert_eq!(expected, distance);
+
+    assert_eq!(
+        tree.nearest_area(x, y).map(|(_, distance)| distance),
+        tree.nearest_area_where(x, y, |_| true)
+            .map(|(_, distance)| distance)
+    );
+    assert!(tree.nearest_area_where(x, y, |_| false).is_none());
+}
+
+#[test]
+fn test_centers() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut tree = get_area_tree(file).unwrap();
+    assert_eq!(tree.len(), tree.centers().len());
+
+    for area in tree.areas() {
+        let center = tree.centers()[&area.id];
+        assert_eq!(area.center(), center);
+        assert!(area.contains(center.0, center.1));
+        let corners = area.quad.corners();
+        let (low, high) = corners
+            .iter()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(low, high), corner| {
+                (low.min(corner.2), high.max(corner.2))
+            });
+        assert!(center.2 >= low && center.2 <= high);
+    }
+
+    let id = tree.areas().next().unwrap().id;
+    tree.remove(tree.handle(id).unwrap());
+    assert!(!tree.centers().contains_key(&id));
+    assert_eq!(tree.len(), tree.centers().len());
+}
+
+#[test]
+fn test_slope() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = get_area_tree(file).unwrap();
+
+    let flat = tree
+        .areas()
+        .find(|area| {
+            area.quad
+                .corners()
+                .iter()
+                .all(|c| c.2 == area.quad.north_west.2)
+        })
+        .unwrap();
+    let center = flat.quad.center();
+    assert_eq!(Some(0.0), tree.slope_at(center.0, center.1, center.2));
+    assert_eq!(
+        Some(Vector3(0.0, 0.0, 1.0)),
+        tree.normal_at(center.0, center.1, center.2)
+    );
+
+    // the top of a slope
+    let (x, y) = (320.0, -1030.0);
+    let area = tree.query(x, y).next().unwrap();
+    let slope = area.slope_at(x, y);
+    assert!(slope > 10.0 && slope < 60.0);
+
+    // the normal is perpendicular to the surface
+    let normal = area.normal_at(x, y);
+    let step = 1.0;
+    let along_x = area.quad.interpolated_z(x + step, y) - area.quad.interpolated_z(x, y);
+    assert!((normal.0 * step + normal.2 * along_x).abs() < 0.01);
+
+    assert_eq!(None, tree.slope_at(-100_000.0, 0.0, 0.0));
+}
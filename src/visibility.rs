@@ -0,0 +1,608 @@
+use crate::navmesh::{NavArea, NavAreaId, NavQuad, Vector3, VisibleArea};
+use crate::{NavMesh, NavMeshView};
+use std::collections::{HashMap, HashSet};
+
+/// Height of the eyes of a player above the ground
+const EYE_HEIGHT: f32 = 62.0;
+/// Distance between the samples taken along a line of sight
+const SAMPLE_DISTANCE: f32 = 16.0;
+const GRID_SIZE: f32 = 128.0;
+
+const MATRIX_MAGIC: &[u8; 8] = b"NAVVISIB";
+
+/// Grid of the areas overlapping each cell, to quickly find the ground under a point
+struct AreaGrid<'a> {
+    areas: &'a [NavArea],
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+fn cell(value: f32) -> i32 {
+    (value / GRID_SIZE).floor() as i32
+}
+
+impl<'a> AreaGrid<'a> {
+    fn new(areas: &'a [NavArea]) -> Self {
+        let mut cells: HashMap<_, Vec<_>> = HashMap::new();
+        for (index, area) in areas.iter().enumerate() {
+            let quad = &area.quad;
+            for x in cell(quad.north_west.0)..=cell(quad.south_east.0) {
+                for y in cell(quad.north_west.1)..=cell(quad.south_east.1) {
+                    cells.entry((x, y)).or_default().push(index);
+                }
+            }
+        }
+        AreaGrid { areas, cells }
+    }
+
+    /// Whether there is ground under a point that is not higher than the point
+    fn has_ground_below(&self, x: f32, y: f32, z: f32) -> bool {
+        self.cells
+            .get(&(cell(x), cell(y)))
+            .into_iter()
+            .flatten()
+            .map(|index| &self.areas[*index].quad)
+            .filter(|quad| quad.contains(x, y))
+            .any(|quad| quad.interpolated_z(x, y) <= z)
+    }
+
+    /// Whether a line stays above the mesh without leaving it
+    fn line_clear(&self, from: Vector3, to: Vector3) -> bool {
+        let length = ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt();
+        let steps = (length / SAMPLE_DISTANCE).ceil().max(1.0) as usize;
+        (0..=steps).all(|step| {
+            let t = step as f32 / steps as f32;
+            self.has_ground_below(
+                from.0 + (to.0 - from.0) * t,
+                from.1 + (to.1 - from.1) * t,
+                from.2 + (to.2 - from.2) * t,
+            )
+        })
+    }
+}
+
+fn eye_point(quad: &NavQuad, x: f32, y: f32) -> Vector3 {
+    Vector3(x, y, quad.interpolated_z(x, y) + EYE_HEIGHT)
+}
+
+fn center_eye(quad: &NavQuad) -> Vector3 {
+    eye_point(
+        quad,
+        (quad.north_west.0 + quad.south_east.0) / 2.0,
+        (quad.north_west.1 + quad.south_east.1) / 2.0,
+    )
+}
+
+/// Estimate the areas visible from every area using only the geometry of the areas
+///
+/// Without access to the level geometry, everything that isn't above the navigation mesh is
+/// assumed to block sight. An area is visible when the line of sight from the center of an area
+/// to the center of the other area stays above the mesh, and completely visible when the same
+/// holds for the corners of the other area.
+pub(crate) fn compute_visibility(areas: &[NavArea], max_distance: f32) -> Vec<Vec<VisibleArea>> {
+    let grid = AreaGrid::new(areas);
+    let eyes: Vec<Vector3> = areas.iter().map(|area| center_eye(&area.quad)).collect();
+
+    (0..areas.len())
+        .map(|index| {
+            let from = eyes[index];
+            let mut visible: Vec<VisibleArea> = areas
+                .iter()
+                .enumerate()
+                .filter(|(other_index, _)| *other_index != index)
+                .filter(|(other_index, _)| {
+                    let to = eyes[*other_index];
+                    (to.0 - from.0).powi(2) + (to.1 - from.1).powi(2) <= max_distance.powi(2)
+                })
+                .filter(|(other_index, _)| grid.line_clear(from, eyes[*other_index]))
+                .map(|(_, other)| {
+                    let complete = other.quad.corners().iter().all(|corner| {
+                        grid.line_clear(from, eye_point(&other.quad, corner.0, corner.1))
+                    });
+                    let attributes = if complete {
+                        VisibleArea::POTENTIALLY_VISIBLE
+                            | VisibleArea::PARTIALLY_VISIBLE
+                            | VisibleArea::COMPLETELY_VISIBLE
+                    } else {
+                        VisibleArea::POTENTIALLY_VISIBLE | VisibleArea::PARTIALLY_VISIBLE
+                    };
+                    VisibleArea {
+                        id: other.id,
+                        attributes,
+                    }
+                })
+                .collect();
+            visible.sort_by_key(|visible| visible.id);
+            visible
+        })
+        .collect()
+}
+
+/// Get the visibility attributes of all areas visible from an area
+///
+/// The visible areas of the area itself take precedence over the areas inherited from other
+/// areas, inheritance is followed until an area doesn't inherit or a loop is found.
+pub(crate) fn resolve_visibility(view: &NavMeshView, area: &NavArea) -> HashMap<NavAreaId, u8> {
+    let mut resolved = HashMap::with_capacity(area.visible_areas.len());
+    let mut seen = HashSet::new();
+    let mut current = Some(area);
+    while let Some(area) = current {
+        if !seen.insert(area.id) {
+            break;
+        }
+        for visible in &area.visible_areas {
+            resolved.entry(visible.id).or_insert(visible.attributes);
+        }
+        current = match area.inherit_visibility_from_area_id {
+            0 => None,
+            id => view.area(id.into()),
+        };
+    }
+    resolved
+}
+
+/// Get the visibility attributes of an area as seen from another area
+///
+/// Follows the same inheritance rules as [`resolve_visibility`] without resolving all areas
+fn find_visibility(view: &NavMeshView, area: &NavArea, target: NavAreaId) -> Option<u8> {
+    let mut seen = HashSet::new();
+    let mut current = Some(area);
+    while let Some(area) = current {
+        if !seen.insert(area.id) {
+            break;
+        }
+        if let Some(visible) = area
+            .visible_areas
+            .iter()
+            .find(|visible| visible.id == target)
+        {
+            return Some(visible.attributes);
+        }
+        current = match area.inherit_visibility_from_area_id {
+            0 => None,
+            id => view.area(id.into()),
+        };
+    }
+    None
+}
+
+/// Find the area a player is standing on with it's eyes at a point
+fn area_under_eye(areas: &[NavArea], eye: Vector3) -> Option<&NavArea> {
+    let feet = eye.2 - EYE_HEIGHT;
+    areas
+        .iter()
+        .filter(|area| area.contains(eye.0, eye.1))
+        .map(|area| (area, (area.quad.interpolated_z(eye.0, eye.1) - feet).abs()))
+        .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(area, _)| area)
+}
+
+impl NavMeshView<'_> {
+    /// Get the visibility attributes of area `b` as seen from area `a`
+    ///
+    /// Visibility inherited from other areas is resolved, `None` is returned if either area
+    /// doesn't exist or `b` isn't listed as visible from `a`.
+    pub fn is_visible(&self, a: NavAreaId, b: NavAreaId) -> Option<u8> {
+        find_visibility(self, self.area(a)?, b)
+    }
+
+    /// Estimate whether there is a line of sight between the eyes of players at two points
+    ///
+    /// See [`NavMesh::line_of_sight`]
+    ///
+    /// [`NavMesh::line_of_sight`]: ./struct.NavMesh.html#method.line_of_sight
+    pub fn line_of_sight(&self, p1: Vector3, p2: Vector3) -> Option<bool> {
+        let areas = &self.mesh().areas;
+        let first = area_under_eye(areas, p1)?;
+        let second = area_under_eye(areas, p2)?;
+        if first.id == second.id {
+            return Some(true);
+        }
+
+        let visible = |attributes: Option<u8>| {
+            attributes.unwrap_or(0) & VisibleArea::POTENTIALLY_VISIBLE != 0
+        };
+        Some(
+            visible(find_visibility(self, first, second.id))
+                || visible(find_visibility(self, second, first.id)),
+        )
+    }
+}
+
+/// The potential visibility between all areas as a bitset, see [`NavMesh::visibility_matrix`]
+///
+/// [`NavMesh::visibility_matrix`]: ./struct.NavMesh.html#method.visibility_matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct VisibilityMatrix {
+    ids: Vec<NavAreaId>,
+    index: HashMap<NavAreaId, usize>,
+    bits: Vec<u64>,
+}
+
+impl VisibilityMatrix {
+    fn new(ids: Vec<NavAreaId>) -> Self {
+        let index = ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+        let bits = vec![0; (ids.len() * ids.len()).div_ceil(64)];
+        VisibilityMatrix { ids, index, bits }
+    }
+
+    fn set(&mut self, from: usize, to: usize) {
+        let bit = from * self.ids.len() + to;
+        self.bits[bit / 64] |= 1 << (bit % 64);
+    }
+
+    /// The number of areas in the matrix
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the matrix contains no areas
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// The ids of the areas, in the order of the rows and columns of the matrix
+    pub fn ids(&self) -> &[NavAreaId] {
+        &self.ids
+    }
+
+    /// Get the row and column of an area in the matrix
+    pub fn index_of(&self, id: NavAreaId) -> Option<usize> {
+        self.index.get(&id).copied()
+    }
+
+    /// Whether the area at the `to` index is potentially visible from the area at the `from` index
+    pub fn get(&self, from: usize, to: usize) -> bool {
+        if from >= self.ids.len() || to >= self.ids.len() {
+            return false;
+        }
+        let bit = from * self.ids.len() + to;
+        self.bits[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    /// Whether an area is potentially visible from another area
+    pub fn is_visible(&self, from: NavAreaId, to: NavAreaId) -> bool {
+        match (self.index_of(from), self.index_of(to)) {
+            (Some(from), Some(to)) => self.get(from, to),
+            _ => false,
+        }
+    }
+
+    /// Serialize the matrix into a compact binary format
+    ///
+    /// The data consists of the magic `NAVVISIB`, the number of areas and the ids of the areas
+    /// as little endian `u32`, followed by the row major bits of the matrix, least significant
+    /// bit first.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let bit_count = self.ids.len() * self.ids.len();
+        let mut out = Vec::with_capacity(12 + self.ids.len() * 4 + bit_count.div_ceil(8));
+        out.extend_from_slice(MATRIX_MAGIC);
+        out.extend_from_slice(&(self.ids.len() as u32).to_le_bytes());
+        for id in &self.ids {
+            out.extend_from_slice(&u32::from(*id).to_le_bytes());
+        }
+        out.extend(
+            self.bits
+                .iter()
+                .flat_map(|block| block.to_le_bytes().to_vec())
+                .take(bit_count.div_ceil(8)),
+        );
+        out
+    }
+
+    /// Load a matrix serialized with [`to_bytes`]
+    ///
+    /// Returns `None` if the data isn't a valid serialized matrix
+    ///
+    /// [`to_bytes`]: #method.to_bytes
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let data = data.strip_prefix(&MATRIX_MAGIC[..])?;
+        let read_u32 = |data: &[u8], offset: usize| -> Option<u32> {
+            let bytes = data.get(offset..offset + 4)?;
+            Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+        let count = read_u32(data, 0)? as usize;
+        let ids = (0..count)
+            .map(|index| read_u32(data, 4 + index * 4).map(NavAreaId::from))
+            .collect::<Option<Vec<_>>>()?;
+
+        let bytes = data.get(4 + count * 4..)?;
+        if bytes.len() != (count * count).div_ceil(8) {
+            return None;
+        }
+        let mut matrix = VisibilityMatrix::new(ids);
+        for (index, chunk) in bytes.chunks(8).enumerate() {
+            let mut block = [0; 8];
+            block[..chunk.len()].copy_from_slice(chunk);
+            matrix.bits[index] = u64::from_le_bytes(block);
+        }
+        Some(matrix)
+    }
+}
+
+impl NavMesh {
+    /// Expand the visible areas of all areas into a matrix of the potential visibility between
+    /// all areas
+    ///
+    /// Visibility inherited from other areas is resolved.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let matrix = mesh.visibility_matrix();
+    /// std::fs::write("path/to/visibility.bin", matrix.to_bytes())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn visibility_matrix(&self) -> VisibilityMatrix {
+        let view = self.view();
+        let mut matrix = VisibilityMatrix::new(self.areas.iter().map(|area| area.id).collect());
+        for (from, area) in self.areas.iter().enumerate() {
+            for (id, attributes) in resolve_visibility(&view, area) {
+                if attributes & VisibleArea::POTENTIALLY_VISIBLE == 0 {
+                    continue;
+                }
+                if let Some(to) = matrix.index_of(id) {
+                    matrix.set(from, to);
+                }
+            }
+        }
+        matrix
+    }
+
+    /// Get the visibility attributes of area `b` as seen from area `a`
+    ///
+    /// Visibility inherited from other areas is resolved, `None` is returned if either area
+    /// doesn't exist or `b` isn't listed as visible from `a`. Use [`NavMeshView::is_visible`]
+    /// when doing many lookups.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{read_mesh, VisibleArea};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let (a, b) = (mesh.areas[0].id, mesh.areas[1].id);
+    /// if let Some(attributes) = mesh.is_visible(a, b) {
+    ///     let complete = attributes & VisibleArea::COMPLETELY_VISIBLE != 0;
+    ///     println!("area {} is visible from area {}, completely: {}", b, a, complete);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`NavMeshView::is_visible`]: ./struct.NavMeshView.html#method.is_visible
+    pub fn is_visible(&self, a: NavAreaId, b: NavAreaId) -> Option<u8> {
+        self.view().is_visible(a, b)
+    }
+
+    /// Get the potentially visible set of an area, all areas that are potentially visible from
+    /// the area
+    ///
+    /// Visibility inherited from other areas is resolved. With `bidirectional`, areas that can
+    /// see the area are included as well, even if they aren't listed as visible from the area
+    /// itself. Returns `None` if the area doesn't exist.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let area = mesh.areas[0].id;
+    /// if let Some(exposed) = mesh.pvs(area, true) {
+    ///     println!("area {} is exposed to {} areas", area, exposed.len());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pvs(&self, area: NavAreaId, bidirectional: bool) -> Option<HashSet<NavAreaId>> {
+        let view = self.view();
+        let source = view.area(area)?;
+        let mut visible: HashSet<NavAreaId> = resolve_visibility(&view, source)
+            .into_iter()
+            .filter(|(_, attributes)| attributes & VisibleArea::POTENTIALLY_VISIBLE != 0)
+            .map(|(id, _)| id)
+            .collect();
+
+        if bidirectional {
+            visible.extend(
+                self.areas
+                    .iter()
+                    .filter(|other| {
+                        find_visibility(&view, other, area).unwrap_or(0)
+                            & VisibleArea::POTENTIALLY_VISIBLE
+                            != 0
+                    })
+                    .map(|other| other.id),
+            );
+        }
+        visible.remove(&area);
+
+        Some(visible)
+    }
+
+    /// Estimate whether there is a line of sight between the eyes of players at two points
+    ///
+    /// The points are the eye positions of the players, the areas the players are standing on are
+    /// found at eye height below the points. There is a line of sight when either area is
+    /// potentially visible from the other, or both players stand in the same area. This only uses
+    /// the visibility data of the nav file and can't account for the exact level geometry.
+    ///
+    /// Returns `None` if either point isn't above an area.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{read_mesh, Vector3};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    /// let sniper = Vector3(150.0, -312.0, 126.0);
+    /// let target = Vector3(1200.0, -312.0, 190.0);
+    /// if mesh.line_of_sight(sniper, target) == Some(true) {
+    ///     println!("the sniper can see the target");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn line_of_sight(&self, p1: Vector3, p2: Vector3) -> Option<bool> {
+        self.view().line_of_sight(p1, p2)
+    }
+}
+
+#[test]
+fn test_visibility_matrix() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let matrix = mesh.visibility_matrix();
+    assert_eq!(1930, matrix.len());
+
+    let area = mesh
+        .areas
+        .iter()
+        .find(|area| area.visible_areas.iter().any(VisibleArea::is_visible))
+        .unwrap();
+    let visible = area
+        .visible_areas
+        .iter()
+        .find(|visible| visible.is_visible())
+        .unwrap();
+    assert!(matrix.is_visible(area.id, visible.id));
+
+    let bytes = matrix.to_bytes();
+    assert_eq!(12 + 1930 * 4 + (1930usize * 1930).div_ceil(8), bytes.len());
+    assert_eq!(Some(matrix), VisibilityMatrix::from_bytes(&bytes));
+    assert_eq!(
+        None,
+        VisibilityMatrix::from_bytes(&bytes[..bytes.len() - 1])
+    );
+}
+
+#[test]
+fn test_is_visible() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    let view = mesh.view();
+
+    for area in &mesh.areas {
+        for visible in &area.visible_areas {
+            assert_eq!(
+                Some(visible.attributes),
+                view.is_visible(area.id, visible.id)
+            );
+        }
+        for (id, attributes) in resolve_visibility(&view, area) {
+            assert_eq!(Some(attributes), view.is_visible(area.id, id));
+        }
+    }
+
+    let inheriting = mesh
+        .areas
+        .iter()
+        .find(|area| area.inherit_visibility_from_area_id != 0);
+    if let Some(area) = inheriting {
+        let parent = view
+            .area(area.inherit_visibility_from_area_id.into())
+            .unwrap();
+        for visible in &parent.visible_areas {
+            assert!(mesh.is_visible(area.id, visible.id).is_some());
+        }
+    }
+
+    assert_eq!(
+        None,
+        mesh.is_visible(NavAreaId::from(u32::MAX), mesh.areas[0].id)
+    );
+}
+
+#[test]
+fn test_line_of_sight() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    let view = mesh.view();
+
+    let area = mesh
+        .areas
+        .iter()
+        .find(|area| area.visible_areas.iter().any(VisibleArea::is_visible))
+        .unwrap();
+    let eye = center_eye(&area.quad);
+    assert_eq!(Some(true), mesh.line_of_sight(eye, eye));
+
+    let visible = view
+        .area(
+            area.visible_areas
+                .iter()
+                .find(|visible| visible.is_visible())
+                .unwrap()
+                .id,
+        )
+        .unwrap();
+    let other_eye = center_eye(&visible.quad);
+    assert_eq!(Some(true), view.line_of_sight(eye, other_eye));
+    assert_eq!(Some(true), view.line_of_sight(other_eye, eye));
+
+    let hidden = mesh
+        .areas
+        .iter()
+        .find(|other| {
+            other.id != area.id
+                && view.is_visible(area.id, other.id).is_none()
+                && view.is_visible(other.id, area.id).is_none()
+                && area_under_eye(&mesh.areas, center_eye(&other.quad)).map(|a| a.id)
+                    == Some(other.id)
+        })
+        .unwrap();
+    assert_eq!(
+        Some(false),
+        view.line_of_sight(eye, center_eye(&hidden.quad))
+    );
+
+    assert_eq!(None, view.line_of_sight(eye, Vector3(-100_000.0, 0.0, 0.0)));
+}
+
+#[test]
+fn test_pvs() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+    let matrix = mesh.visibility_matrix();
+
+    let area = mesh
+        .areas
+        .iter()
+        .find(|area| area.visible_areas.iter().any(VisibleArea::is_visible))
+        .unwrap();
+    let from = matrix.index_of(area.id).unwrap();
+
+    let pvs = mesh.pvs(area.id, false).unwrap();
+    let expected: HashSet<_> = mesh
+        .areas
+        .iter()
+        .filter(|other| other.id != area.id)
+        .filter(|other| matrix.get(from, matrix.index_of(other.id).unwrap()))
+        .map(|other| other.id)
+        .collect();
+    assert!(!pvs.is_empty());
+    assert_eq!(expected, pvs);
+
+    let both = mesh.pvs(area.id, true).unwrap();
+    assert!(both.is_superset(&pvs));
+    for other in &mesh.areas {
+        if other.id != area.id && matrix.get(matrix.index_of(other.id).unwrap(), from) {
+            assert!(both.contains(&other.id));
+        }
+    }
+
+    assert_eq!(None, mesh.pvs(NavAreaId::from(u32::MAX), false));
+}
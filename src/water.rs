@@ -0,0 +1,130 @@
+use crate::navmesh::NavArea;
+use crate::NavTree;
+use std::collections::HashSet;
+
+/// A body of water in the map, given by the x/y rectangle it covers and the height of it's surface
+///
+/// Nav files don't store which areas are underwater, the engine determines this at runtime from
+/// the level geometry. The water volumes of a map therefore need to be provided separately.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WaterVolume {
+    pub min: (f32, f32),
+    pub max: (f32, f32),
+    /// The z height of the water surface
+    pub surface: f32,
+}
+
+impl WaterVolume {
+    pub fn new(min: (f32, f32), max: (f32, f32), surface: f32) -> Self {
+        WaterVolume { min, max, surface }
+    }
+
+    /// Get the depth of the water above a point, `None` if the point isn't in the water volume
+    pub fn depth(&self, x: f32, y: f32, z: f32) -> Option<f32> {
+        let inside =
+            (self.min.0..=self.max.0).contains(&x) && (self.min.1..=self.max.1).contains(&y);
+        if inside && z < self.surface {
+            Some(self.surface - z)
+        } else {
+            None
+        }
+    }
+}
+
+impl NavArea {
+    /// Get the depth of the water at a x/y point of the area, `0` if the area isn't underwater at
+    /// the point
+    pub fn water_depth(&self, x: f32, y: f32, water: &[WaterVolume]) -> f32 {
+        let z = self.quad.interpolated_z(x, y);
+        water
+            .iter()
+            .filter_map(|volume| volume.depth(x, y, z))
+            .fold(0.0, f32::max)
+    }
+
+    /// Whether the center of the area is underwater
+    pub fn is_underwater(&self, water: &[WaterVolume]) -> bool {
+        let center = self.quad.center();
+        water
+            .iter()
+            .any(|volume| volume.depth(center.0, center.1, center.2).is_some())
+    }
+}
+
+impl NavTree {
+    /// Find all areas with their center underwater
+    pub fn underwater_areas<'a>(
+        &'a self,
+        water: &'a [WaterVolume],
+    ) -> impl Iterator<Item = &'a NavArea> + 'a {
+        // areas can overlap multiple volumes
+        let mut seen = HashSet::new();
+        water
+            .iter()
+            .flat_map(move |volume| self.query_rect(volume.min, volume.max))
+            .filter(move |area| area.is_underwater(water))
+            .filter(move |area| seen.insert(area.id))
+    }
+
+    /// Get the depth of the water at a point of the mesh
+    ///
+    /// The ground at the point is the area at the x/y coordinate with the height closest to the z
+    /// coordinate, `None` is returned if there is no area at the x/y coordinate.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{get_area_tree, WaterVolume};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let tree = get_area_tree(file)?;
+    /// let water = [WaterVolume::new((-512.0, -512.0), (512.0, 512.0), -64.0)];
+    /// if let Some(depth) = tree.water_depth_at(150.0, -312.0, -100.0, &water) {
+    ///     let speed = if depth > 32.0 { 0.5 } else { 1.0 };
+    ///     println!("moving at {} times the normal speed", speed);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn water_depth_at(&self, x: f32, y: f32, z: f32, water: &[WaterVolume]) -> Option<f32> {
+        Some(self.area_under(x, y, z)?.water_depth(x, y, water))
+    }
+}
+
+#[test]
+fn test_water_depth() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let tree = crate::get_area_tree(file).unwrap();
+    let (min, max) = tree.bounds();
+
+    // flood the lowest parts of the map
+    let surface = min.2 + (max.2 - min.2) / 4.0;
+    let water = [WaterVolume::new((min.0, min.1), (max.0, max.1), surface)];
+
+    let underwater: Vec<_> = tree.underwater_areas(&water).collect();
+    assert!(!underwater.is_empty());
+    assert!(underwater.len() < tree.len());
+    let mut ids: Vec<_> = underwater.iter().map(|area| area.id).collect();
+    ids.sort();
+    ids.dedup();
+    assert_eq!(underwater.len(), ids.len());
+
+    for area in underwater {
+        let center = area.quad.center();
+        let depth = tree
+            .water_depth_at(center.0, center.1, center.2, &water)
+            .unwrap();
+        assert!(depth > 0.0);
+        assert!((depth - (surface - area.quad.interpolated_z(center.0, center.1))).abs() < 1.0);
+    }
+
+    let dry = tree
+        .areas()
+        .find(|area| !area.is_underwater(&water))
+        .unwrap();
+    let center = dry.quad.center();
+    assert_eq!(0.0, dry.water_depth(center.0, center.1, &[]));
+    assert_eq!(None, tree.water_depth_at(-100_000.0, 0.0, 0.0, &water));
+}
@@ -0,0 +1,531 @@
+use crate::navmesh::{NavArea, NavLadder, NavPlace, Vector3, VisibleArea};
+use crate::visibility::compute_visibility;
+use crate::NavMesh;
+use std::collections::HashMap;
+use std::io::{self, Write};
+use thiserror::Error;
+
+/// Errors that can occur when writing a binary nav file
+#[derive(Debug, Error)]
+pub enum WriteError {
+    /// An error occurred while writing to the output
+    #[error("Error while writing data: {0}")]
+    Io(#[from] io::Error),
+    /// The requested nav version can't be written
+    #[error("The major version ({0}) can't be written")]
+    UnsupportedVersion(u32),
+    /// The attribute flags of an area don't fit in the flag size of the requested version
+    #[error("The flags of area {area} ({flags:#X}) don't fit in the flags for version {version}")]
+    FlagsOutOfRange { area: u32, flags: u32, version: u32 },
+    /// A list contains more items than the nav format can store
+    #[error("Too many {what} ({count}) to write")]
+    TooMany { what: &'static str, count: usize },
+    /// An area references a place that isn't in the place table
+    #[error("Area {area} references unknown place {place}")]
+    UnknownPlace { area: u32, place: u16 },
+}
+
+/// How the visibility data of the areas is written
+///
+/// Visibility data makes up most of the size of analyzed nav files
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum VisibilityMode {
+    /// Write the visibility data of the areas as is
+    #[default]
+    Keep,
+    /// Don't write any visibility data
+    Strip,
+    /// Estimate the visibility from the geometry of the areas before writing
+    ///
+    /// Only areas within `max_distance` of each other are considered visible
+    Regenerate { max_distance: f32 },
+}
+
+/// Options for writing a nav file
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WriteOptions {
+    pub visibility: VisibilityMode,
+}
+
+struct NavWriter<W: Write> {
+    out: W,
+}
+
+impl<W: Write> NavWriter<W> {
+    fn u8(&mut self, value: u8) -> io::Result<()> {
+        self.out.write_all(&[value])
+    }
+
+    fn u16(&mut self, value: u16) -> io::Result<()> {
+        self.out.write_all(&value.to_le_bytes())
+    }
+
+    fn u32(&mut self, value: u32) -> io::Result<()> {
+        self.out.write_all(&value.to_le_bytes())
+    }
+
+    fn f32(&mut self, value: f32) -> io::Result<()> {
+        self.out.write_all(&value.to_le_bytes())
+    }
+
+    fn vector(&mut self, value: &Vector3) -> io::Result<()> {
+        self.f32(value.0)?;
+        self.f32(value.1)?;
+        self.f32(value.2)
+    }
+
+    fn bytes(&mut self, value: &[u8]) -> io::Result<()> {
+        self.out.write_all(value)
+    }
+
+    fn count_u8(&mut self, what: &'static str, count: usize) -> Result<(), WriteError> {
+        if count > u8::MAX as usize {
+            return Err(WriteError::TooMany { what, count });
+        }
+        Ok(self.u8(count as u8)?)
+    }
+
+    fn count_u32(&mut self, what: &'static str, count: usize) -> Result<(), WriteError> {
+        if count > u32::MAX as usize {
+            return Err(WriteError::TooMany { what, count });
+        }
+        Ok(self.u32(count as u32)?)
+    }
+}
+
+impl NavMesh {
+    /// Write the navigation mesh as a nav file of a specific major version
+    ///
+    /// Data that isn't supported by the requested version is not written.
+    ///
+    /// All data from the parsed file, including game specific data, is kept. Writing an unmodified
+    /// mesh with the version it was parsed from reproduces the original file byte for byte.
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::read_mesh;
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mut mesh = read_mesh(file)?;
+    /// mesh.areas[0].flags = 0;
+    ///
+    /// let out = File::create("path/to/edited.nav")?;
+    /// mesh.write(mesh.major_version, out)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write(&self, version: u32, out: impl Write) -> Result<(), WriteError> {
+        self.write_with_options(version, out, WriteOptions::default())
+    }
+
+    /// Write the navigation mesh as a nav file of a specific major version, with custom options
+    ///
+    /// ## Examples
+    ///
+    /// ```no_run
+    /// use sourcenav::{read_mesh, VisibilityMode, WriteOptions};
+    /// use std::fs::File;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let file = std::fs::read("path/to/navfile.nav")?;
+    /// let mesh = read_mesh(file)?;
+    ///
+    /// let out = File::create("path/to/small.nav")?;
+    /// let options = WriteOptions {
+    ///     visibility: VisibilityMode::Strip,
+    /// };
+    /// mesh.write_with_options(mesh.major_version, out, options)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn write_with_options(
+        &self,
+        version: u32,
+        out: impl Write,
+        options: WriteOptions,
+    ) -> Result<(), WriteError> {
+        if !(6..=16).contains(&version) {
+            return Err(WriteError::UnsupportedVersion(version));
+        }
+
+        let mut writer = NavWriter { out };
+
+        writer.u32(0xFEED_FACE)?;
+        writer.u32(version)?;
+        if version >= 10 {
+            writer.u32(self.minor_version)?;
+        }
+        writer.u32(self.bsp_size)?;
+        if version >= 14 {
+            writer.u8(self.is_analyzed as u8)?;
+        }
+
+        let place_indices = write_places(&mut writer, &self.places)?;
+
+        if version >= 12 {
+            writer.u8(self.has_unnamed_areas as u8)?;
+        }
+
+        let regenerated = match options.visibility {
+            VisibilityMode::Regenerate { max_distance } => {
+                Some(compute_visibility(&self.areas, max_distance))
+            }
+            _ => None,
+        };
+
+        writer.count_u32("areas", self.areas.len())?;
+        for (index, area) in self.areas.iter().enumerate() {
+            let visibility = match (&regenerated, options.visibility) {
+                (Some(regenerated), _) => Some((regenerated[index].as_slice(), 0)),
+                (None, VisibilityMode::Strip) => Some((&[][..], 0)),
+                _ => None,
+            };
+            write_area(&mut writer, area, version, visibility, &place_indices)?;
+        }
+
+        writer.count_u32("ladders", self.ladders.len())?;
+        for ladder in &self.ladders {
+            write_ladder(&mut writer, ladder, version)?;
+        }
+
+        writer.bytes(&self.custom_data)?;
+
+        writer.out.flush()?;
+
+        Ok(())
+    }
+
+    /// Write the navigation mesh into a buffer as a nav file of a specific major version
+    pub fn to_bytes(&self, version: u32) -> Result<Vec<u8>, WriteError> {
+        self.to_bytes_with_options(version, WriteOptions::default())
+    }
+
+    /// Write the navigation mesh into a buffer as a nav file of a specific major version, with
+    /// custom options
+    pub fn to_bytes_with_options(
+        &self,
+        version: u32,
+        options: WriteOptions,
+    ) -> Result<Vec<u8>, WriteError> {
+        let mut out = Vec::new();
+        self.write_with_options(version, &mut out, options)?;
+        Ok(out)
+    }
+}
+
+/// Write the place table, returning the index every place id is written as
+///
+/// Places are referenced by their position in the table, starting at 1
+fn write_places<W: Write>(
+    writer: &mut NavWriter<W>,
+    places: &[NavPlace],
+) -> Result<HashMap<u16, u16>, WriteError> {
+    if places.len() > u16::MAX as usize {
+        return Err(WriteError::TooMany {
+            what: "places",
+            count: places.len(),
+        });
+    }
+    writer.u16(places.len() as u16)?;
+    for place in places {
+        // names are stored null terminated
+        let length = place.name.len() + 1;
+        if length > u16::MAX as usize {
+            return Err(WriteError::TooMany {
+                what: "place name bytes",
+                count: length,
+            });
+        }
+        writer.u16(length as u16)?;
+        writer.bytes(place.name.as_bytes())?;
+        writer.u8(0)?;
+    }
+    Ok(places
+        .iter()
+        .enumerate()
+        .map(|(index, place)| (place.id, index as u16 + 1))
+        .collect())
+}
+
+/// Write an area, optionally replacing the visible areas and inherited visibility
+fn write_area<W: Write>(
+    writer: &mut NavWriter<W>,
+    area: &NavArea,
+    version: u32,
+    visibility: Option<(&[VisibleArea], u32)>,
+    place_indices: &HashMap<u16, u16>,
+) -> Result<(), WriteError> {
+    writer.u32(area.id.into())?;
+
+    let flag_bits = if version <= 8 {
+        8
+    } else if version <= 12 {
+        16
+    } else {
+        32
+    };
+    if flag_bits < 32 && area.flags >> flag_bits != 0 {
+        return Err(WriteError::FlagsOutOfRange {
+            area: area.id.into(),
+            flags: area.flags,
+            version,
+        });
+    }
+    match flag_bits {
+        8 => writer.u8(area.flags as u8)?,
+        16 => writer.u16(area.flags as u16)?,
+        _ => writer.u32(area.flags)?,
+    }
+
+    writer.vector(&area.quad.north_west)?;
+    writer.vector(&area.quad.south_east)?;
+    writer.f32(area.quad.north_east_z)?;
+    writer.f32(area.quad.south_west_z)?;
+
+    for direction in area.connections.0.iter() {
+        writer.count_u32("connections", direction.len())?;
+        for id in direction {
+            writer.u32((*id).into())?;
+        }
+    }
+
+    writer.count_u8("hiding spots", area.hiding_spots.len())?;
+    for spot in &area.hiding_spots {
+        writer.u32(spot.id)?;
+        writer.vector(&spot.location)?;
+        writer.u8(spot.flags)?;
+    }
+
+    if version < 15 {
+        writer.count_u8("approach areas", area.approach_areas.len())?;
+        for approach in &area.approach_areas {
+            writer.u32(approach.approach_here.into())?;
+            writer.u32(approach.approach_pre.into())?;
+            writer.u8(approach.approach_type)?;
+            writer.u32(approach.approach_next.into())?;
+            writer.u8(approach.approach_how)?;
+        }
+    }
+
+    writer.count_u32("encounter paths", area.encounter_paths.len())?;
+    for path in &area.encounter_paths {
+        writer.u32(path.from_area_id.into())?;
+        writer.u8(path.from_direction)?;
+        writer.u32(path.to_area_id.into())?;
+        writer.u8(path.to_direction)?;
+        writer.count_u8("encounter spots", path.spots.len())?;
+        for spot in &path.spots {
+            writer.u32(spot.order)?;
+            writer.u8(spot.distance)?;
+        }
+    }
+
+    let place = match area.place {
+        0 => 0,
+        place => *place_indices.get(&place).ok_or(WriteError::UnknownPlace {
+            area: area.id.into(),
+            place,
+        })?,
+    };
+    writer.u16(place)?;
+
+    if version >= 7 {
+        for direction in area.ladder_connections.0.iter() {
+            writer.count_u32("ladder connections", direction.len())?;
+            for id in direction {
+                writer.u32((*id).into())?;
+            }
+        }
+    }
+
+    if version >= 8 {
+        writer.f32(area.earliest_occupy_first_team)?;
+        writer.f32(area.earliest_occupy_second_team)?;
+    }
+
+    if version >= 11 {
+        writer.f32(area.light_intensity.north_west)?;
+        writer.f32(area.light_intensity.north_east)?;
+        writer.f32(area.light_intensity.south_west)?;
+        writer.f32(area.light_intensity.south_east)?;
+    }
+
+    if version >= 16 {
+        let (visible_areas, inherit_visibility_from_area_id) =
+            visibility.unwrap_or((&area.visible_areas, area.inherit_visibility_from_area_id));
+        writer.count_u32("visible areas", visible_areas.len())?;
+        for visible in visible_areas {
+            writer.u32(visible.id.into())?;
+            writer.u8(visible.attributes)?;
+        }
+        writer.u32(inherit_visibility_from_area_id)?;
+    }
+
+    writer.bytes(&area.custom_data)?;
+
+    Ok(())
+}
+
+fn write_ladder<W: Write>(
+    writer: &mut NavWriter<W>,
+    ladder: &NavLadder,
+    version: u32,
+) -> Result<(), WriteError> {
+    writer.u32(ladder.id.into())?;
+    writer.f32(ladder.width)?;
+    writer.vector(&ladder.top)?;
+    writer.vector(&ladder.bottom)?;
+    writer.f32(ladder.length)?;
+    writer.u32(ladder.direction as u8 as u32)?;
+    if version == 6 {
+        writer.u8(ladder.is_dangling as u8)?;
+    }
+
+    let areas = [
+        ladder.top_forward_area,
+        ladder.top_left_area,
+        ladder.top_right_area,
+        ladder.top_behind_area,
+        ladder.bottom_area,
+    ];
+    for area in areas.iter() {
+        writer.u32(area.map(u32::from).unwrap_or(0))?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_write_read() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file).unwrap();
+
+    let written = mesh.to_bytes(mesh.major_version).unwrap();
+    let parsed = crate::read_mesh(written).unwrap();
+
+    assert_eq!(mesh, parsed);
+}
+
+#[test]
+fn test_round_trip_identical() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file.clone()).unwrap();
+
+    assert!(file == mesh.to_bytes(mesh.major_version).unwrap());
+
+    // unknown data at the end of the file is preserved
+    let mut file = file;
+    file.extend_from_slice(&[1, 2, 3, 4, 5]);
+    let mesh = crate::read_mesh(file.clone()).unwrap();
+
+    assert_eq!(vec![1, 2, 3, 4, 5], mesh.custom_data);
+    assert!(file == mesh.to_bytes(mesh.major_version).unwrap());
+}
+
+#[test]
+fn test_place_id_gap() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut mesh = crate::read_mesh(file).unwrap();
+
+    mesh.places = vec![
+        NavPlace {
+            id: 3,
+            name: "Cellar".to_string(),
+        },
+        NavPlace {
+            id: 7,
+            name: "Bridge".to_string(),
+        },
+    ];
+    mesh.areas[0].place = 7;
+    mesh.areas[1].place = 3;
+    mesh.areas[2].place = 0;
+
+    let read = crate::read_mesh(mesh.to_bytes(mesh.major_version).unwrap()).unwrap();
+    assert_eq!(
+        vec![1, 2],
+        read.places.iter().map(|place| place.id).collect::<Vec<_>>()
+    );
+    assert_eq!(2, read.areas[0].place);
+    assert_eq!(1, read.areas[1].place);
+    assert_eq!(0, read.areas[2].place);
+
+    mesh.areas[0].place = 5;
+    assert!(matches!(
+        mesh.to_bytes(mesh.major_version),
+        Err(WriteError::UnknownPlace { place: 5, .. })
+    ));
+}
+
+#[test]
+fn test_long_place_name() {
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mut mesh = crate::read_mesh(file).unwrap();
+
+    mesh.places.push(NavPlace {
+        id: mesh.places.len() as u16 + 1,
+        name: "a".repeat(u16::MAX as usize - 1),
+    });
+    let written = mesh.to_bytes(mesh.major_version).unwrap();
+    assert_eq!(mesh.places, crate::read_mesh(written).unwrap().places);
+
+    mesh.places.last_mut().unwrap().name.push('a');
+    assert!(matches!(
+        mesh.to_bytes(mesh.major_version),
+        Err(WriteError::TooMany { count: 65536, .. })
+    ));
+}
+
+#[test]
+fn test_visibility_mode() {
+    use crate::navmesh::NavQuad;
+
+    let file = std::fs::read("data/pl_badwater.nav").unwrap();
+    let mesh = crate::read_mesh(file.clone()).unwrap();
+
+    let options = WriteOptions {
+        visibility: VisibilityMode::Strip,
+    };
+    let written = mesh
+        .to_bytes_with_options(mesh.major_version, options)
+        .unwrap();
+    assert!(written.len() < file.len());
+    let stripped = crate::read_mesh(written).unwrap();
+    assert!(stripped
+        .areas
+        .iter()
+        .all(|area| area.visible_areas.is_empty()));
+    assert_eq!(mesh.areas.len(), stripped.areas.len());
+
+    // two open areas next to each other and a third area far away
+    let mut builder = crate::NavMeshBuilder::new(crate::NavGame::TeamFortress2);
+    let quad = |x: f32| NavQuad {
+        north_west: Vector3(x, 0.0, 0.0),
+        south_east: Vector3(x + 100.0, 100.0, 0.0),
+        north_east_z: 0.0,
+        south_west_z: 0.0,
+    };
+    let first = builder.add_area(quad(0.0)).unwrap();
+    let second = builder.add_area(quad(100.0)).unwrap();
+    builder.add_area(quad(1000.0)).unwrap();
+    let mesh = builder.build();
+
+    let options = WriteOptions {
+        visibility: VisibilityMode::Regenerate {
+            max_distance: 2000.0,
+        },
+    };
+    let written = mesh.to_bytes_with_options(16, options).unwrap();
+    let regenerated = crate::read_mesh(written).unwrap();
+    let visible: Vec<_> = regenerated.areas[0]
+        .visible_areas
+        .iter()
+        .map(|visible| visible.id)
+        .collect();
+    assert_eq!(vec![second], visible);
+    assert_eq!(first, regenerated.areas[1].visible_areas[0].id);
+    assert!(regenerated.areas[2].visible_areas.is_empty());
+}